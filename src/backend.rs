@@ -0,0 +1,149 @@
+use blaze::{Diagnostic, Severity, Span};
+
+use crate::json::Json;
+
+pub struct Backend {
+    lines: Vec<(usize, usize)>,
+    source: String,
+}
+
+impl Backend {
+    pub fn new(rust: &str, source: &str) -> Self {
+        let lines = rust
+            .lines()
+            .enumerate()
+            .filter_map(|(index, text)| {
+                let line = text.trim().strip_prefix("// line ")?;
+                Some((index + 1, line.parse().ok()?))
+            })
+            .collect();
+
+        Self {
+            lines,
+            source: source.to_string(),
+        }
+    }
+
+    pub fn convert(&self, message: &Json) -> Option<Diagnostic> {
+        let severity = match message.get("level")?.as_str()? {
+            "warning" => Severity::Warning,
+            level if level.starts_with("error") => Severity::Error,
+            _ => return None,
+        };
+
+        // The resolver already warns about deprecated items with Blaze's
+        // own positions, so rustc's copy would only repeat it.
+        let code = message.get("code").and_then(|code| code.get("code"));
+
+        if code.and_then(Json::as_str) == Some("deprecated") {
+            return None;
+        }
+
+        let text = message.get("message")?.as_str()?;
+        let spans = message.get("spans").and_then(Json::as_array);
+        let spans = spans.unwrap_or(&[]);
+
+        if spans.is_empty()
+            && (text.starts_with("aborting due to")
+                || text.ends_with("emitted"))
+        {
+            return None;
+        }
+
+        let primary = spans
+            .iter()
+            .find(|span| span.get("is_primary") == Some(&Json::Bool(true)))
+            .or_else(|| spans.first());
+
+        let position = primary.and_then(|span| {
+            let line = span.get("line_start")?.as_number()? as usize;
+            let column = span.get("column_start")?.as_number()? as usize;
+
+            Some((line, column))
+        });
+
+        let mut notes: Vec<String> = primary
+            .and_then(|span| span.get("label"))
+            .and_then(Json::as_str)
+            .map(|label| label.to_string())
+            .into_iter()
+            .collect();
+
+        if let Some(children) = message.get("children").and_then(Json::as_array)
+        {
+            notes.extend(
+                children
+                    .iter()
+                    .filter_map(|child| child.get("message")?.as_str())
+                    .filter(|child| !child.starts_with("`#["))
+                    .map(|child| child.to_string()),
+            );
+        }
+
+        let line = position.and_then(|(line, _)| self.lookup(line));
+
+        let (line, span, message) = match line {
+            Some(line) => (line, self.span(line), text.to_string()),
+            None => {
+                if let Some((line, column)) = position {
+                    notes.push(format!(
+                        "At line {}, column {} of the generated Rust.",
+                        line, column
+                    ));
+                }
+
+                (0, None, format!("Internal backend error: {}", text))
+            }
+        };
+
+        Some(Diagnostic {
+            severity,
+            line,
+            span,
+            code: None,
+            location: String::new(),
+            message,
+            note: if notes.is_empty() {
+                None
+            } else {
+                Some(notes.join("\n"))
+            },
+            lint: match severity {
+                Severity::Warning => Some("warnings"),
+                _ => None,
+            },
+        })
+    }
+
+    fn lookup(&self, line: usize) -> Option<usize> {
+        self.lines
+            .iter()
+            .rev()
+            .find(|(generated, _)| *generated <= line)
+            .map(|(_, line)| *line)
+    }
+
+    fn span(&self, line: usize) -> Option<Span> {
+        let mut start = if self.source.starts_with('\u{feff}') {
+            '\u{feff}'.len_utf8()
+        } else {
+            0
+        };
+
+        for (index, text) in self.source[start..].split('\n').enumerate() {
+            if index + 1 == line {
+                let indent = text.len() - text.trim_start().len();
+
+                return Some(Span {
+                    start: start + indent,
+                    end: (start + text.trim_end().len()).max(start + indent),
+                    column: text[..indent].chars().count() + 1,
+                });
+            }
+
+            start += text.len() + 1;
+        }
+
+        None
+    }
+}
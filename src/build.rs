@@ -0,0 +1,115 @@
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::Diagnostic;
+
+#[derive(Debug)]
+pub enum BuildError {
+    Io(io::Error),
+    Compile(PathBuf, Vec<Diagnostic>),
+}
+
+impl Error for BuildError {}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(formatter, "{}", error),
+            Self::Compile(path, diagnostics) => {
+                write!(formatter, "Failed to compile {}.", path.display())?;
+
+                for diagnostic in diagnostics {
+                    write!(formatter, "\n{}", diagnostic)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl From<io::Error> for BuildError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+// Compiles every script in a directory into OUT_DIR, for use from build
+// scripts. Each script becomes a module named after its file, with a public
+// run function that calls its main, and blaze.rs in OUT_DIR declares them all:
+//
+//     include!(concat!(env!("OUT_DIR"), "/blaze.rs"));
+//
+//     fn main() {
+//         hello::run();
+//     }
+pub fn compile_dir(directory: impl AsRef<Path>) -> Result<(), BuildError> {
+    let directory = directory.as_ref();
+    let output =
+        env::var_os("OUT_DIR").map(PathBuf::from).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "OUT_DIR is not set")
+        })?;
+
+    println!("cargo:rerun-if-changed={}", directory.display());
+
+    let mut scripts: Vec<PathBuf> = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()?;
+    scripts.retain(|path| path.extension().is_some_and(|e| e == "blz"));
+    scripts.sort();
+
+    let modules = output.join("blaze");
+    fs::create_dir_all(&modules)?;
+
+    let mut index = String::new();
+
+    for script in scripts {
+        println!("cargo:rerun-if-changed={}", script.display());
+
+        let source = fs::read_to_string(&script)?;
+        let rust = crate::compile(&source).map_err(|diagnostics| {
+            BuildError::Compile(script.clone(), diagnostics)
+        })?;
+
+        let name = module(&script);
+        let path = modules.join(format!("{}.rs", name));
+        fs::write(
+            &path,
+            format!("{}\npub fn run() {{\n    main();\n}}\n", rust),
+        )?;
+
+        index.push_str(&format!(
+            "#[path = {:?}]\npub mod {};\n",
+            path.to_string_lossy(),
+            name
+        ));
+    }
+
+    fs::write(output.join("blaze.rs"), index)?;
+
+    Ok(())
+}
+
+fn module(script: &Path) -> String {
+    let stem = script.file_stem().unwrap_or_default().to_string_lossy();
+    let mut name: String = stem
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        name.insert(0, '_');
+    }
+
+    name
+}
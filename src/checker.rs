@@ -0,0 +1,2359 @@
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::fmt;
+use std::iter;
+use std::mem;
+use std::slice;
+
+use crate::code::Code;
+use crate::error::TypeError;
+use crate::expr;
+use crate::kind::Kind;
+use crate::pattern;
+use crate::stmt;
+use crate::suggest::suggest;
+use crate::token::{Span, Token};
+use crate::value::{Suffix, Value};
+use crate::variant;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Type {
+    Integer,
+    Float,
+    Bool,
+    Str,
+    Unit,
+    Array(Box<Type>),
+    Map(Box<Type>, Box<Type>),
+    Set(Box<Type>),
+    Optional(Box<Type>),
+    Fallible(Box<Type>, Box<Type>),
+    Function(Vec<Type>, Box<Type>),
+    Named(String),
+    Unknown,
+}
+
+impl Type {
+    fn is_number(&self) -> bool {
+        matches!(self, Self::Integer | Self::Float)
+    }
+
+    fn function(parameters: &[Type], output: Type) -> Self {
+        Self::Function(parameters.to_vec(), Box::new(output))
+    }
+
+    // Map keys and set elements become Rust hash keys, which floats,
+    // functions, collections and user types can't be.
+    fn is_hashable(&self) -> bool {
+        match self {
+            Self::Integer | Self::Bool | Self::Str | Self::Unit => true,
+            Self::Unknown => true,
+            Self::Array(element) | Self::Optional(element) => {
+                element.is_hashable()
+            }
+            Self::Fallible(value, error) => {
+                value.is_hashable() && error.is_hashable()
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Integer => write!(formatter, "i32"),
+            Self::Float => write!(formatter, "f64"),
+            Self::Bool => write!(formatter, "bool"),
+            Self::Str => write!(formatter, "str"),
+            Self::Unit => write!(formatter, "()"),
+            Self::Array(element) => write!(formatter, "[{}]", element),
+            Self::Map(key, value) => {
+                write!(formatter, "map[{}, {}]", key, value)
+            }
+            Self::Set(element) => write!(formatter, "set[{}]", element),
+            Self::Optional(inner) => write!(formatter, "{}?", inner),
+            Self::Fallible(value, error) => {
+                write!(formatter, "{} ! {}", value, error)
+            }
+            Self::Function(parameters, output) => {
+                let parameters: Vec<String> = parameters
+                    .iter()
+                    .map(|parameter| parameter.to_string())
+                    .collect();
+
+                write!(formatter, "fn({})", parameters.join(", "))?;
+
+                if **output != Self::Unit {
+                    write!(formatter, ": {}", output)?;
+                }
+
+                Ok(())
+            }
+            Self::Named(name) => write!(formatter, "{}", name),
+            Self::Unknown => write!(formatter, "_"),
+        }
+    }
+}
+
+fn builtins() -> Vec<(&'static str, Type)> {
+    use Type::*;
+
+    let integer = || Type::function(&[Integer], Integer);
+    let integers = || Type::function(&[Integer, Integer], Integer);
+    let checked =
+        || Type::function(&[Integer, Integer], Optional(Box::new(Integer)));
+    let float = || Type::function(&[Float], Float);
+
+    vec![
+        ("args", Type::function(&[], Array(Box::new(Str)))),
+        ("bit", Type::function(&[Integer, Integer], Bool)),
+        ("checked_add", checked()),
+        ("checked_div", checked()),
+        ("checked_mul", checked()),
+        ("checked_sub", checked()),
+        ("clock", Type::function(&[], Float)),
+        ("cos", float()),
+        ("count_ones", integer()),
+        ("count_zeros", integer()),
+        ("env", Type::function(&[Str], Optional(Box::new(Str)))),
+        ("floor", float()),
+        ("input", Type::function(&[], Str)),
+        ("input_num", Type::function(&[], Optional(Box::new(Float)))),
+        ("leading_zeros", integer()),
+        ("len", Type::function(&[Unknown], Integer)),
+        ("lower", Type::function(&[Str], Str)),
+        (
+            "parse_num",
+            Type::function(&[Str], Optional(Box::new(Float))),
+        ),
+        ("random", Type::function(&[], Float)),
+        ("rotate_left", integers()),
+        ("rotate_right", integers()),
+        ("saturating_add", integers()),
+        ("saturating_mul", integers()),
+        ("saturating_sub", integers()),
+        ("sin", float()),
+        ("sqrt", float()),
+        ("substring", Type::function(&[Str, Integer, Integer], Str)),
+        ("to_string", Type::function(&[Unknown], Str)),
+        ("trailing_zeros", integer()),
+        ("upper", Type::function(&[Str], Str)),
+        ("wrapping_add", integers()),
+        ("wrapping_div", integers()),
+        ("wrapping_mul", integers()),
+        ("wrapping_neg", integer()),
+        ("wrapping_sub", integers()),
+    ]
+}
+
+fn integer(expr: &expr::Expr) -> Option<i32> {
+    match expr {
+        expr::Expr::Grouping(expr) => integer(&expr.expression),
+        expr::Expr::Literal(expr) => match &expr.value {
+            Value::Number(digits, None | Some(Suffix::Integer)) => {
+                digits.parse().ok()
+            }
+            _ => None,
+        },
+        expr::Expr::Unary(expr) => match (expr.operator.kind, &expr.right) {
+            (Kind::Minus, expr::Expr::Literal(literal)) => match &literal.value
+            {
+                Value::Number(digits, None | Some(Suffix::Integer)) => {
+                    format!("-{}", digits).parse().ok()
+                }
+                _ => None,
+            },
+            (Kind::Minus, right) => integer(right)?.checked_neg(),
+            (Kind::Bang, right) => Some(!integer(right)?),
+            _ => None,
+        },
+        expr::Expr::Binary(expr) => arithmetic(
+            expr.operator.kind,
+            integer(&expr.left)?,
+            integer(&expr.right)?,
+        ),
+        _ => None,
+    }
+}
+
+fn arithmetic(kind: Kind, left: i32, right: i32) -> Option<i32> {
+    match kind {
+        Kind::Plus => left.checked_add(right),
+        Kind::Minus => left.checked_sub(right),
+        Kind::Star => left.checked_mul(right),
+        Kind::Slash => left.checked_div(right),
+        Kind::LessLess => left.checked_shl(u32::try_from(right).ok()?),
+        Kind::GreaterGreater => left.checked_shr(u32::try_from(right).ok()?),
+        Kind::Amp => Some(left & right),
+        Kind::Bar => Some(left | right),
+        Kind::Caret => Some(left ^ right),
+        _ => None,
+    }
+}
+
+fn irrefutable(pattern: Option<&pattern::Pattern>) -> bool {
+    matches!(
+        pattern,
+        None | Some(pattern::Pattern::Binding(_))
+            | Some(pattern::Pattern::Wildcard(_))
+    )
+}
+
+fn constructor(pattern: &pattern::Pattern) -> Option<&str> {
+    match pattern {
+        pattern::Pattern::Literal(literal) => match literal.value {
+            Value::True => Some("true"),
+            Value::False => Some("false"),
+            _ => None,
+        },
+        pattern::Pattern::Path(path) => Some(&path.name.lexeme),
+        _ => None,
+    }
+}
+
+fn arms(cases: &[String]) -> String {
+    if cases.iter().all(|case| case == "_") {
+        return "Add a '_' arm for the values that aren't listed.".to_string();
+    }
+
+    let mut listed: Vec<String> = cases
+        .iter()
+        .take(3)
+        .map(|case| format!("'{}'", case))
+        .collect();
+
+    if cases.len() > 3 {
+        listed.push(format!("{} more", cases.len() - 3));
+    }
+
+    let listed = match listed.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, [first])) => format!("{} and {}", first, last),
+        Some((last, rest)) => format!("{}, and {}", rest.join(", "), last),
+        None => String::new(),
+    };
+
+    format!(
+        "Add {} for {}, or a '_' arm for the rest.",
+        if cases.len() == 1 { "an arm" } else { "arms" },
+        listed
+    )
+}
+
+fn specialize<'a>(
+    row: &[Option<&'a pattern::Pattern>],
+    name: &str,
+    arity: usize,
+) -> Option<Vec<Option<&'a pattern::Pattern>>> {
+    let fields = match row[0] {
+        pattern if irrefutable(pattern) => vec![None; arity],
+        Some(pattern::Pattern::Path(path)) if path.name.lexeme == name => path
+            .fields
+            .iter()
+            .map(Some)
+            .chain(iter::repeat(None))
+            .take(arity)
+            .collect(),
+        Some(pattern) if constructor(pattern) == Some(name) => Vec::new(),
+        _ => return None,
+    };
+
+    Some(fields.into_iter().chain(row[1..].iter().copied()).collect())
+}
+
+// A program that has been through the checker, along with what the
+// generator needs to know about it. It can only be made by checking, so the
+// generator never sees statements the checker hasn't looked at.
+pub struct Program<'a> {
+    pub(crate) statements: &'a [stmt::Stmt],
+    pub(crate) coercions: HashSet<usize>,
+}
+
+pub struct Checker {
+    errors: Vec<TypeError>,
+    line: usize,
+    span: Span,
+    scopes: Vec<HashMap<String, Type>>,
+    aliases: HashMap<String, variant::Variant>,
+    resolving: HashSet<String>,
+    structs: HashMap<String, Vec<(String, Type)>>,
+    enums: HashMap<String, Vec<(String, Vec<Type>)>>,
+    methods: HashMap<(String, String), (bool, Type)>,
+    receiver: Option<Type>,
+    output: Option<Type>,
+    catching: Vec<Option<Type>>,
+    loops: Vec<(Option<String>, bool, Option<Type>)>,
+    scrutinee: Type,
+    coercions: HashSet<usize>,
+    elements: HashMap<usize, Type>,
+}
+
+impl Checker {
+    pub fn new() -> Self {
+        Self {
+            errors: Vec::new(),
+            line: 1,
+            span: Span::default(),
+            scopes: Vec::new(),
+            aliases: HashMap::new(),
+            resolving: HashSet::new(),
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            methods: HashMap::new(),
+            receiver: None,
+            output: None,
+            catching: Vec::new(),
+            loops: Vec::new(),
+            scrutinee: Type::Unknown,
+            coercions: HashSet::new(),
+            elements: HashMap::new(),
+        }
+    }
+
+    pub fn check<'a>(
+        &mut self,
+        statements: &'a [stmt::Stmt],
+    ) -> (Program<'a>, Vec<TypeError>) {
+        self.scopes.push(
+            builtins()
+                .into_iter()
+                .map(|(name, kind)| (name.to_string(), kind))
+                .collect(),
+        );
+
+        self.collect(statements);
+
+        for statement in statements {
+            statement.accept(self);
+        }
+
+        self.scopes.clear();
+        self.elements.clear();
+
+        let program = Program {
+            statements,
+            coercions: mem::take(&mut self.coercions),
+        };
+
+        (program, mem::take(&mut self.errors))
+    }
+
+    fn collect(&mut self, statements: &[stmt::Stmt]) {
+        for statement in statements {
+            match statement {
+                stmt::Stmt::Struct(stmt) => {
+                    self.structs.insert(stmt.name.lexeme.clone(), Vec::new());
+                }
+                stmt::Stmt::Enum(stmt) => {
+                    self.enums.insert(stmt.name.lexeme.clone(), Vec::new());
+                }
+                stmt::Stmt::Type(stmt) => {
+                    self.aliases
+                        .insert(stmt.name.lexeme.clone(), stmt.variant.clone());
+                }
+                _ => (),
+            }
+        }
+
+        for statement in statements {
+            match statement {
+                stmt::Stmt::Struct(stmt) => {
+                    self.at(&stmt.name);
+
+                    let fields = stmt
+                        .fields
+                        .iter()
+                        .map(|(name, variant)| {
+                            (name.lexeme.clone(), variant.accept(self))
+                        })
+                        .collect();
+
+                    self.structs.insert(stmt.name.lexeme.clone(), fields);
+                }
+                stmt::Stmt::Enum(stmt) => {
+                    self.at(&stmt.name);
+
+                    let variants = stmt
+                        .variants
+                        .iter()
+                        .map(|(name, fields)| {
+                            let fields = fields
+                                .iter()
+                                .map(|field| field.accept(self))
+                                .collect();
+
+                            (name.lexeme.clone(), fields)
+                        })
+                        .collect();
+
+                    self.enums.insert(stmt.name.lexeme.clone(), variants);
+                }
+                stmt::Stmt::Impl(stmt) => {
+                    self.at(&stmt.name);
+
+                    for method in stmt.methods.iter() {
+                        if let stmt::Stmt::Function(method) = method {
+                            let kind = self.signature(method);
+
+                            self.methods.insert(
+                                (
+                                    stmt.name.lexeme.clone(),
+                                    method.name.lexeme.clone(),
+                                ),
+                                (method.receiver, kind),
+                            );
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        for statement in statements {
+            let name = match statement {
+                stmt::Stmt::Struct(stmt) => &stmt.name,
+                stmt::Stmt::Enum(stmt) => &stmt.name,
+                _ => continue,
+            };
+
+            let named = Type::Named(name.lexeme.clone());
+            let mut seen = HashSet::new();
+
+            if self.fields(&name.lexeme).iter().any(|field| {
+                self.holds(field, &|kind| *kind == named, false, &mut seen)
+            }) {
+                self.at(name);
+                self.push(
+                    Code::RecursiveType,
+                    &Code::RecursiveType.message(&[&name.lexeme]),
+                    Some(format!(
+                        "Put it in an array, like '[{}]', to break the cycle.",
+                        name.lexeme
+                    )),
+                );
+            }
+        }
+
+        self.hoist(statements);
+
+        for statement in statements {
+            match statement {
+                stmt::Stmt::Let(stmt) => {
+                    let kind = match &stmt.variant {
+                        Some(variant) => variant.accept(self),
+                        None => Type::Unknown,
+                    };
+
+                    self.declare(&stmt.name.lexeme, kind);
+                }
+                stmt::Stmt::Const(stmt) => {
+                    let kind = stmt.variant.accept(self);
+                    self.declare(&stmt.name.lexeme, kind);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn fields(&self, name: &str) -> Vec<Type> {
+        if let Some(fields) = self.structs.get(name) {
+            return fields.iter().map(|(_, kind)| kind.clone()).collect();
+        }
+
+        match self.enums.get(name) {
+            Some(variants) => variants
+                .iter()
+                .flat_map(|(_, fields)| fields.iter().cloned())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // Whether a value of this type holds a matching one inline, looking
+    // through optionals, results, and the fields of structs and enums. The
+    // elements of collections only count with `collections` set, since
+    // they live behind a pointer.
+    fn holds(
+        &self,
+        kind: &Type,
+        found: &dyn Fn(&Type) -> bool,
+        collections: bool,
+        seen: &mut HashSet<String>,
+    ) -> bool {
+        if found(kind) {
+            return true;
+        }
+
+        match kind {
+            Type::Optional(inner) => {
+                self.holds(inner, found, collections, seen)
+            }
+            Type::Fallible(value, error) => {
+                self.holds(value, found, collections, seen)
+                    || self.holds(error, found, collections, seen)
+            }
+            Type::Array(element) | Type::Set(element) if collections => {
+                self.holds(element, found, collections, seen)
+            }
+            Type::Map(key, value) if collections => {
+                self.holds(key, found, collections, seen)
+                    || self.holds(value, found, collections, seen)
+            }
+            Type::Named(name) if seen.insert(name.clone()) => self
+                .fields(name)
+                .iter()
+                .any(|field| self.holds(field, found, collections, seen)),
+            _ => false,
+        }
+    }
+
+    fn key(&mut self, kind: &Type) {
+        if !kind.is_hashable() {
+            self.error(
+                Code::UnhashableKey,
+                &Code::UnhashableKey.message(&[kind]),
+            );
+        }
+    }
+
+    fn hoist(&mut self, statements: &[stmt::Stmt]) {
+        for statement in statements {
+            if let stmt::Stmt::Function(stmt) = statement {
+                self.at(&stmt.name);
+
+                let kind = self.signature(stmt);
+                self.declare(&stmt.name.lexeme, kind);
+            }
+        }
+    }
+
+    fn signature(&mut self, stmt: &stmt::Function) -> Type {
+        let parameters: Vec<Type> = stmt
+            .parameters
+            .iter()
+            .map(|(_, variant)| variant.accept(self))
+            .collect();
+
+        Type::Function(parameters, Box::new(self.output(&stmt.output)))
+    }
+
+    fn output(&mut self, output: &Option<variant::Variant>) -> Type {
+        match output {
+            Some(variant) => variant.accept(self),
+            None => Type::Unit,
+        }
+    }
+
+    fn at(&mut self, token: &Token) {
+        self.line = token.line;
+        self.span = token.span;
+    }
+
+    fn declare(&mut self, name: &str, kind: Type) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), kind);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Type {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .cloned()
+            .unwrap_or(Type::Unknown)
+    }
+
+    fn accepts(&self, expected: &Type, actual: &Type) -> bool {
+        match (expected, actual) {
+            (Type::Unknown, _) | (_, Type::Unknown) => true,
+            (Type::Array(expected), Type::Array(actual))
+            | (Type::Set(expected), Type::Set(actual))
+            | (Type::Optional(expected), Type::Optional(actual)) => {
+                self.accepts(expected, actual)
+            }
+            (
+                Type::Map(expected_key, expected_value),
+                Type::Map(actual_key, actual_value),
+            )
+            | (
+                Type::Fallible(expected_key, expected_value),
+                Type::Fallible(actual_key, actual_value),
+            ) => {
+                self.accepts(expected_key, actual_key)
+                    && self.accepts(expected_value, actual_value)
+            }
+            (
+                Type::Function(expected_parameters, expected_output),
+                Type::Function(actual_parameters, actual_output),
+            ) => {
+                expected_parameters.len() == actual_parameters.len()
+                    && expected_parameters
+                        .iter()
+                        .zip(actual_parameters.iter())
+                        .all(|(expected, actual)| {
+                            self.accepts(expected, actual)
+                        })
+                    && self.accepts(expected_output, actual_output)
+            }
+            _ => expected == actual,
+        }
+    }
+
+    fn coerces(&self, expected: &Type, actual: &Type) -> bool {
+        match expected {
+            Type::Optional(inner) if !matches!(actual, Type::Optional(_)) => {
+                self.accepts(inner, actual)
+            }
+            _ => self.accepts(expected, actual),
+        }
+    }
+
+    fn expect(&mut self, expected: &Type, actual: &Type, coerce: bool) {
+        let accepted = if coerce {
+            self.coerces(expected, actual)
+        } else {
+            self.accepts(expected, actual)
+        };
+
+        if !accepted {
+            self.error(
+                Code::MismatchedTypes,
+                &Code::MismatchedTypes.message(&[&expected, &actual]),
+            );
+        }
+    }
+
+    fn join(&self, left: &Type, right: &Type) -> Option<Type> {
+        match (left, right) {
+            (Type::Unknown, kind) | (kind, Type::Unknown) => Some(kind.clone()),
+            (Type::Optional(left), Type::Optional(right)) => {
+                let inner = self.join(left, right)?;
+                Some(Type::Optional(Box::new(inner)))
+            }
+            (Type::Optional(inner), kind) | (kind, Type::Optional(inner)) => {
+                let inner = self.join(inner, kind)?;
+                Some(Type::Optional(Box::new(inner)))
+            }
+            (Type::Array(left), Type::Array(right)) => {
+                let element = self.join(left, right)?;
+                Some(Type::Array(Box::new(element)))
+            }
+            (Type::Set(left), Type::Set(right)) => {
+                let element = self.join(left, right)?;
+                Some(Type::Set(Box::new(element)))
+            }
+            (
+                Type::Map(left_key, left_value),
+                Type::Map(right_key, right_value),
+            ) => {
+                let key = self.join(left_key, right_key)?;
+                let value = self.join(left_value, right_value)?;
+                Some(Type::Map(Box::new(key), Box::new(value)))
+            }
+            _ if self.accepts(left, right) => Some(left.clone()),
+            _ => None,
+        }
+    }
+
+    fn element(&mut self, value: &expr::Expr, joined: &mut Type) {
+        let kind = value.accept(self);
+
+        if let Some(kind) = self.join(joined, &kind) {
+            *joined = kind;
+        }
+
+        self.elements.insert(value.id(), kind);
+    }
+
+    fn kind(&self, value: &expr::Expr) -> Type {
+        match self.elements.get(&value.id()) {
+            Some(kind) => kind.clone(),
+            None => Type::Unknown,
+        }
+    }
+
+    fn coerce(&mut self, expected: &Type, value: &expr::Expr, actual: &Type) {
+        match (expected, value) {
+            (Type::Array(element), expr::Expr::Array(array)) => {
+                for value in array.elements.iter() {
+                    let kind = self.kind(value);
+                    self.coerce(element, value, &kind);
+                }
+
+                return;
+            }
+            (Type::Map(key, element), expr::Expr::Map(map)) => {
+                for (entry_key, entry_value) in map.entries.iter() {
+                    let kind = self.kind(entry_key);
+                    self.coerce(key, entry_key, &kind);
+
+                    let kind = self.kind(entry_value);
+                    self.coerce(element, entry_value, &kind);
+                }
+
+                return;
+            }
+            (Type::Set(element), expr::Expr::SetLiteral(set)) => {
+                for value in set.elements.iter() {
+                    let kind = self.kind(value);
+                    self.coerce(element, value, &kind);
+                }
+
+                return;
+            }
+            (Type::Set(_), expr::Expr::Map(map)) if map.entries.is_empty() => {
+                return;
+            }
+            (Type::Optional(inner), expr::Expr::Map(map))
+                if map.entries.is_empty()
+                    && matches!(**inner, Type::Set(_)) =>
+            {
+                self.coercions.insert(value.id());
+                return;
+            }
+            _ => (),
+        }
+
+        self.expect(expected, actual, true);
+
+        if matches!(expected, Type::Optional(_))
+            && !matches!(actual, Type::Optional(_))
+        {
+            self.coercions.insert(value.id());
+        }
+    }
+
+    fn condition(&mut self, condition: &expr::Expr) {
+        let kind = condition.accept(self);
+
+        if !self.accepts(&Type::Bool, &kind) {
+            self.error(
+                Code::MismatchedTypes,
+                &format!("Condition must be 'bool', not '{}'.", kind),
+            );
+        }
+    }
+
+    fn arguments(&mut self, parameters: &[Type], arguments: &[expr::Expr]) {
+        let kinds: Vec<Type> = arguments
+            .iter()
+            .map(|argument| argument.accept(self))
+            .collect();
+
+        if parameters.len() != arguments.len() {
+            self.error(
+                Code::WrongCount,
+                &Code::WrongCount
+                    .message(&[&parameters.len(), &arguments.len()]),
+            );
+
+            return;
+        }
+
+        for ((parameter, argument), kind) in
+            parameters.iter().zip(arguments.iter()).zip(kinds.iter())
+        {
+            self.coerce(parameter, argument, kind);
+        }
+    }
+
+    fn collection(&mut self, name: &Token, arguments: &[expr::Expr]) -> Type {
+        let kinds: Vec<Type> = arguments
+            .iter()
+            .map(|argument| argument.accept(self))
+            .collect();
+
+        self.at(name);
+
+        let count = match name.lexeme.as_str() {
+            "insert" => 3,
+            "pop" => 1,
+            _ => 2,
+        };
+
+        if kinds.len() != count {
+            self.error(
+                Code::WrongCount,
+                &Code::WrongCount.message(&[&count, &kinds.len()]),
+            );
+
+            return Type::Unknown;
+        }
+
+        match (name.lexeme.as_str(), kinds[0].clone()) {
+            (_, Type::Unknown) => Type::Unknown,
+            ("push", Type::Array(element)) => {
+                self.coerce(&element, &arguments[1], &kinds[1]);
+                Type::Unit
+            }
+            ("pop", Type::Array(element)) => Type::Optional(element),
+            ("add", Type::Set(element)) => {
+                self.coerce(&element, &arguments[1], &kinds[1]);
+                Type::Unit
+            }
+            ("contains", Type::Map(key, _)) | ("contains", Type::Set(key)) => {
+                self.expect(&key, &kinds[1], false);
+                Type::Bool
+            }
+            ("insert", Type::Map(key, value)) => {
+                self.expect(&key, &kinds[1], false);
+                self.coerce(&value, &arguments[2], &kinds[2]);
+                Type::Unit
+            }
+            ("remove", Type::Map(key, value)) => {
+                self.expect(&key, &kinds[1], false);
+                Type::Optional(value)
+            }
+            ("remove", Type::Set(element)) => {
+                self.expect(&element, &kinds[1], false);
+                Type::Bool
+            }
+            (name, kind) => {
+                let message = match name {
+                    "add" => format!("Can't add to '{}'.", kind),
+                    "contains" => format!("Can't search '{}'.", kind),
+                    "insert" => format!("Can't insert into '{}'.", kind),
+                    "pop" => format!("Can't pop from '{}'.", kind),
+                    "push" => format!("Can't push to '{}'.", kind),
+                    _ => format!("Can't remove from '{}'.", kind),
+                };
+
+                self.error(Code::MismatchedTypes, &message);
+                Type::Unknown
+            }
+        }
+    }
+
+    fn numeric(&mut self, name: &Token, arguments: &[expr::Expr]) -> Type {
+        let kinds: Vec<Type> = arguments
+            .iter()
+            .map(|argument| argument.accept(self))
+            .collect();
+
+        self.at(name);
+
+        let count = if name.lexeme == "abs" { 1 } else { 2 };
+
+        if kinds.len() != count {
+            self.error(
+                Code::WrongCount,
+                &Code::WrongCount.message(&[&count, &kinds.len()]),
+            );
+
+            return Type::Unknown;
+        }
+
+        let kind = kinds[0].clone();
+
+        if !matches!(kind, Type::Integer | Type::Float | Type::Unknown) {
+            self.error(
+                Code::MismatchedTypes,
+                &format!("Expect type 'i32' or 'f64' but found '{}'.", kind),
+            );
+
+            return Type::Unknown;
+        }
+
+        for other in kinds[1..].iter() {
+            self.expect(&kind, other, false);
+        }
+
+        kind
+    }
+
+    fn field(&mut self, object: &Type, name: &str) -> Type {
+        match object {
+            Type::Named(object) if self.structs.contains_key(object) => {
+                let field = self.structs[object]
+                    .iter()
+                    .find(|(field, _)| field == name)
+                    .map(|(_, kind)| kind.clone());
+
+                match field {
+                    Some(kind) => kind,
+                    None => {
+                        self.error(
+                            Code::UndefinedItem,
+                            &format!(
+                                "Undefined field '{}' on '{}'.",
+                                name, object
+                            ),
+                        );
+
+                        Type::Unknown
+                    }
+                }
+            }
+            Type::Unknown => Type::Unknown,
+            _ => {
+                self.error(
+                    Code::UndefinedItem,
+                    &format!("Type '{}' has no fields.", object),
+                );
+                Type::Unknown
+            }
+        }
+    }
+
+    fn catch(&mut self, error: &Type) -> bool {
+        let caught = match self.catching.last_mut() {
+            Some(caught) => caught,
+            None => return false,
+        };
+
+        match caught.clone() {
+            Some(caught) => self.expect(&caught, error, false),
+            None => *caught = Some(error.clone()),
+        }
+
+        true
+    }
+
+    fn displays(&self, name: &Token) -> bool {
+        match name.lexeme.as_str() {
+            "print" => self.lookup("print") == Type::Unknown,
+            "to_string" => {
+                self.lookup("to_string")
+                    == Type::function(&[Type::Unknown], Type::Str)
+            }
+            _ => false,
+        }
+    }
+
+    fn missing(
+        &self,
+        rows: &[Vec<Option<&pattern::Pattern>>],
+        types: &[Type],
+    ) -> Vec<Vec<String>> {
+        let (kind, rest) = match types.split_first() {
+            Some(split) => split,
+            None if rows.is_empty() => return vec![Vec::new()],
+            None => return Vec::new(),
+        };
+
+        match self.constructors(kind, rows) {
+            Some(constructors) => {
+                let namespace = match kind {
+                    Type::Named(name) => Some(name.clone()),
+                    _ => rows.iter().find_map(|row| match row[0] {
+                        Some(pattern::Pattern::Path(path)) => {
+                            Some(path.namespace.lexeme.clone())
+                        }
+                        _ => None,
+                    }),
+                };
+
+                constructors
+                    .into_iter()
+                    .filter_map(|(name, fields)| {
+                        let arity = fields.len();
+                        let rows: Vec<_> = rows
+                            .iter()
+                            .filter_map(|row| specialize(row, &name, arity))
+                            .collect();
+                        let types: Vec<Type> = fields
+                            .into_iter()
+                            .chain(rest.iter().cloned())
+                            .collect();
+
+                        let mut witness =
+                            self.missing(&rows, &types).into_iter().next()?;
+                        let inner: Vec<String> =
+                            witness.drain(..arity).collect();
+
+                        let mut case = match &namespace {
+                            Some(namespace) => {
+                                format!("{}::{}", namespace, name)
+                            }
+                            None => name,
+                        };
+
+                        if !inner.is_empty() {
+                            case = format!("{}({})", case, inner.join(", "));
+                        }
+
+                        witness.insert(0, case);
+                        Some(witness)
+                    })
+                    .collect()
+            }
+            None => {
+                let rows: Vec<_> = rows
+                    .iter()
+                    .filter(|row| irrefutable(row[0]))
+                    .map(|row| row[1..].to_vec())
+                    .collect();
+
+                self.missing(&rows, rest)
+                    .into_iter()
+                    .map(|mut witness| {
+                        witness.insert(0, "_".to_string());
+                        witness
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    fn constructors(
+        &self,
+        kind: &Type,
+        rows: &[Vec<Option<&pattern::Pattern>>],
+    ) -> Option<Vec<(String, Vec<Type>)>> {
+        let booleans = || {
+            Some(vec![
+                ("true".to_string(), Vec::new()),
+                ("false".to_string(), Vec::new()),
+            ])
+        };
+
+        let variants = |name: &str| self.enums.get(name).cloned();
+
+        match kind {
+            Type::Bool => booleans(),
+            Type::Named(name) => variants(name),
+            Type::Unknown => {
+                let mut patterns = rows.iter().filter_map(|row| row[0]);
+
+                patterns.find_map(|pattern| match pattern {
+                    pattern::Pattern::Path(path) => {
+                        variants(&path.namespace.lexeme)
+                    }
+                    pattern => match constructor(pattern) {
+                        Some("true" | "false") => booleans(),
+                        _ => None,
+                    },
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn error(&mut self, code: Code, message: &str) {
+        self.push(code, message, None);
+    }
+
+    fn undefined_type(&mut self, kind: &str, name: &str) {
+        let structs = self.structs.keys().map(String::as_str);
+        let note = if kind == "struct" {
+            suggest(name, structs)
+        } else {
+            let names = ["i32", "f64", "bool", "str"]
+                .iter()
+                .copied()
+                .chain(structs)
+                .chain(self.enums.keys().map(String::as_str))
+                .chain(self.aliases.keys().map(String::as_str));
+
+            suggest(name, names)
+        };
+
+        self.push(
+            Code::UndefinedItem,
+            &format!("Undefined {} '{}'.", kind, name),
+            note,
+        );
+    }
+
+    fn push(&mut self, code: Code, message: &str, note: Option<String>) {
+        let duplicate = self
+            .errors
+            .iter()
+            .any(|error| error.line == self.line && error.message == message);
+
+        if !duplicate {
+            self.errors.push(TypeError {
+                line: self.line,
+                span: self.span,
+                code,
+                message: message.to_string(),
+                note,
+            });
+        }
+    }
+}
+
+impl Default for Checker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl expr::Visitor for Checker {
+    type Result = Type;
+
+    fn visit_conditional_expr(
+        &mut self,
+        expr: &expr::Conditional,
+    ) -> Self::Result {
+        self.condition(&expr.condition);
+
+        let then_branch = expr.then_branch.accept(self);
+        let else_branch = expr.else_branch.accept(self);
+
+        self.expect(&then_branch, &else_branch, false);
+
+        match then_branch {
+            Type::Unknown => else_branch,
+            _ => then_branch,
+        }
+    }
+
+    fn visit_logical_expr(&mut self, expr: &expr::Logical) -> Self::Result {
+        let left = expr.left.accept(self);
+        let right = expr.right.accept(self);
+
+        self.at(&expr.operator);
+
+        match expr.operator.kind {
+            Kind::QuestionQuestion => match left {
+                Type::Optional(inner) => {
+                    self.expect(&inner, &right, false);
+
+                    match *inner {
+                        Type::Unknown => right,
+                        inner => inner,
+                    }
+                }
+                Type::Unknown => Type::Unknown,
+                _ => {
+                    self.error(
+                        Code::MismatchedTypes,
+                        &format!(
+                            "Left side of '??' must be optional, not '{}'.",
+                            left
+                        ),
+                    );
+
+                    Type::Unknown
+                }
+            },
+            _ => {
+                if !self.accepts(&Type::Bool, &left)
+                    || !self.accepts(&Type::Bool, &right)
+                {
+                    self.error(
+                        Code::MismatchedTypes,
+                        &format!(
+                            "Operands of '{}' must be 'bool'.",
+                            expr.operator.lexeme
+                        ),
+                    );
+                }
+
+                Type::Bool
+            }
+        }
+    }
+
+    fn visit_binary_expr(&mut self, expr: &expr::Binary) -> Self::Result {
+        let left = expr.left.accept(self);
+        let right = expr.right.accept(self);
+
+        self.at(&expr.operator);
+
+        if let (Some(a), Some(b)) = (integer(&expr.left), integer(&expr.right))
+        {
+            if arithmetic(expr.operator.kind, a, b).is_none() {
+                match expr.operator.kind {
+                    Kind::Slash if b == 0 => {
+                        self.error(Code::OutOfRange, "Can't divide by zero.")
+                    }
+                    Kind::Plus
+                    | Kind::Minus
+                    | Kind::Star
+                    | Kind::Slash
+                    | Kind::LessLess
+                    | Kind::GreaterGreater => self.error(
+                        Code::OutOfRange,
+                        &format!(
+                            "'{}' overflows 'i32' here.",
+                            expr.operator.lexeme
+                        ),
+                    ),
+                    _ => (),
+                }
+            }
+        }
+
+        let known = left != Type::Unknown && right != Type::Unknown;
+        let kind = match left {
+            Type::Unknown => right.clone(),
+            _ => left.clone(),
+        };
+
+        match expr.operator.kind {
+            Kind::BangEqual | Kind::EqualEqual => {
+                let function = |kind: &Type| matches!(kind, Type::Function(..));
+                let functions =
+                    self.holds(&kind, &function, true, &mut HashSet::new());
+
+                if functions
+                    || !self.accepts(&left, &right)
+                        && !self.accepts(&right, &left)
+                {
+                    self.error(
+                        Code::MismatchedTypes,
+                        &format!("Can't compare '{}' with '{}'.", left, right),
+                    );
+                }
+
+                Type::Bool
+            }
+            Kind::Less
+            | Kind::LessEqual
+            | Kind::Greater
+            | Kind::GreaterEqual => {
+                if known
+                    && (left != right
+                        || !(left.is_number() || left == Type::Str))
+                {
+                    self.error(
+                        Code::MismatchedTypes,
+                        &format!("Can't compare '{}' with '{}'.", left, right),
+                    );
+                }
+
+                Type::Bool
+            }
+            Kind::Plus | Kind::Minus | Kind::Star | Kind::Slash => {
+                let strings = expr.operator.kind == Kind::Plus
+                    && kind == Type::Str
+                    && self.accepts(&Type::Str, &left)
+                    && self.accepts(&Type::Str, &right);
+
+                if strings {
+                    Type::Str
+                } else if !self.accepts(&left, &right)
+                    || (kind != Type::Unknown && !kind.is_number())
+                {
+                    self.error(
+                        Code::MismatchedTypes,
+                        &format!(
+                            "Can't apply '{}' to '{}' and '{}'.",
+                            expr.operator.lexeme, left, right
+                        ),
+                    );
+
+                    Type::Unknown
+                } else {
+                    kind
+                }
+            }
+            _ => {
+                let bits = matches!(kind, Type::Integer | Type::Unknown)
+                    || (kind == Type::Bool
+                        && !matches!(
+                            expr.operator.kind,
+                            Kind::LessLess | Kind::GreaterGreater
+                        ));
+
+                if !self.accepts(&left, &right) || !bits {
+                    self.error(
+                        Code::MismatchedTypes,
+                        &format!(
+                            "Can't apply '{}' to '{}' and '{}'.",
+                            expr.operator.lexeme, left, right
+                        ),
+                    );
+
+                    Type::Unknown
+                } else {
+                    kind
+                }
+            }
+        }
+    }
+
+    fn visit_loop_expr(&mut self, expr: &expr::Loop) -> Self::Result {
+        self.loops.push((None, true, None));
+        expr.body.accept(self);
+
+        match self.loops.pop() {
+            Some((_, _, Some(kind))) => kind,
+            _ => Type::Unknown,
+        }
+    }
+
+    fn visit_cast_expr(&mut self, expr: &expr::Cast) -> Self::Result {
+        let value = expr.value.accept(self);
+        self.at(&expr.target);
+
+        // Rust only casts a 'bool' to an integer, and other targets besides
+        // 'f64' are reported below.
+        let boolean = value == Type::Bool && expr.target.lexeme != "f64";
+
+        if !boolean
+            && !matches!(value, Type::Integer | Type::Float | Type::Unknown)
+        {
+            self.error(
+                Code::MismatchedTypes,
+                &format!("Can't cast '{}' to '{}'.", value, expr.target.lexeme),
+            );
+        }
+
+        match expr.target.lexeme.as_str() {
+            "i32" => Type::Integer,
+            "f64" => Type::Float,
+            _ => {
+                self.error(
+                    Code::UnsupportedConstruct,
+                    "Can only cast to 'i32' or 'f64'.",
+                );
+                Type::Unknown
+            }
+        }
+    }
+
+    fn visit_unary_expr(&mut self, expr: &expr::Unary) -> Self::Result {
+        let right = expr.right.accept(self);
+        self.at(&expr.operator);
+
+        match expr.operator.kind {
+            Kind::Minus => {
+                if !matches!(right, Type::Integer | Type::Float | Type::Unknown)
+                {
+                    self.error(
+                        Code::MismatchedTypes,
+                        &format!("Can't negate '{}'.", right),
+                    );
+                } else if integer(&expr.right) == Some(i32::MIN) {
+                    self.error(Code::OutOfRange, "'-' overflows 'i32' here.");
+                }
+
+                right
+            }
+            Kind::Bang => {
+                if !matches!(right, Type::Integer | Type::Bool | Type::Unknown)
+                {
+                    self.error(
+                        Code::MismatchedTypes,
+                        &format!("Can't apply '!' to '{}'.", right),
+                    );
+                }
+
+                right
+            }
+            _ if !self.catching.is_empty() => match right {
+                Type::Fallible(value, error) => {
+                    self.catch(&error);
+                    *value
+                }
+                Type::Unknown => {
+                    self.catch(&Type::Unknown);
+                    Type::Unknown
+                }
+                right => {
+                    self.error(
+                        Code::MismatchedTypes,
+                        &format!(
+                            "Can't use 'try' on '{}' inside a 'try' block.",
+                            right
+                        ),
+                    );
+
+                    Type::Unknown
+                }
+            },
+            _ => match (right, self.output.clone()) {
+                (Type::Optional(inner), Some(Type::Optional(_))) => *inner,
+                (
+                    Type::Fallible(value, error),
+                    Some(Type::Fallible(_, output)),
+                ) => {
+                    self.expect(&output, &error, false);
+                    *value
+                }
+                (Type::Unknown, _) => Type::Unknown,
+                (right, output) => {
+                    self.error(
+                        Code::MismatchedTypes,
+                        &format!(
+                        "Can't use 'try' on '{}' in a function returning '{}'.",
+                        right,
+                        output.unwrap_or(Type::Unit)
+                    ),
+                    );
+
+                    Type::Unknown
+                }
+            },
+        }
+    }
+
+    fn visit_call_expr(&mut self, expr: &expr::Call) -> Self::Result {
+        let callee = match &expr.callee {
+            expr::Expr::Get(callee) => {
+                let object = callee.object.accept(self);
+                self.at(&callee.name);
+
+                match &object {
+                    Type::Named(name) => {
+                        let method = self
+                            .methods
+                            .get(&(name.clone(), callee.name.lexeme.clone()))
+                            .cloned();
+
+                        match method {
+                            Some((true, kind)) => kind,
+                            _ => {
+                                self.error(
+                                    Code::UndefinedItem,
+                                    &format!(
+                                        "Undefined method '{}' on '{}'.",
+                                        callee.name.lexeme, name
+                                    ),
+                                );
+
+                                Type::Unknown
+                            }
+                        }
+                    }
+                    Type::Unknown => Type::Unknown,
+                    _ => {
+                        self.error(
+                            Code::UndefinedItem,
+                            &format!("Type '{}' has no methods.", object),
+                        );
+                        Type::Unknown
+                    }
+                }
+            }
+            expr::Expr::Variable(callee) if self.displays(&callee.name) => {
+                for argument in expr.arguments.iter() {
+                    let kind = argument.accept(self);
+                    self.at(&callee.name);
+
+                    if !matches!(
+                        kind,
+                        Type::Integer
+                            | Type::Float
+                            | Type::Bool
+                            | Type::Str
+                            | Type::Unknown
+                    ) {
+                        let message = match callee.name.lexeme.as_str() {
+                            "print" => format!("Can't print '{}'.", kind),
+                            _ => format!("Can't convert '{}' to 'str'.", kind),
+                        };
+
+                        self.push(
+                            Code::MismatchedTypes,
+                            &message,
+                            Some(
+                                "Only numbers, bools, and strings have a \
+                                 text form."
+                                    .to_string(),
+                            ),
+                        );
+                    }
+                }
+
+                return match callee.name.lexeme.as_str() {
+                    "print" => Type::Unit,
+                    _ => Type::Str,
+                };
+            }
+            expr::Expr::Variable(callee)
+                if callee.name.lexeme == "assert"
+                    && self.lookup("assert") == Type::Unknown =>
+            {
+                let arguments: Vec<Type> = expr
+                    .arguments
+                    .iter()
+                    .map(|argument| argument.accept(self))
+                    .collect();
+
+                self.at(&callee.name);
+
+                match arguments.as_slice() {
+                    [condition] => self.expect(&Type::Bool, condition, false),
+                    [condition, message] => {
+                        self.expect(&Type::Bool, condition, false);
+                        self.expect(&Type::Str, message, false);
+                    }
+                    _ => self.error(
+                        Code::WrongCount,
+                        "Expect 1 or 2 arguments to 'assert'.",
+                    ),
+                }
+
+                return Type::Unit;
+            }
+            expr::Expr::Variable(callee)
+                if matches!(
+                    callee.name.lexeme.as_str(),
+                    "add" | "contains" | "insert" | "pop" | "push" | "remove"
+                ) && self.lookup(&callee.name.lexeme) == Type::Unknown =>
+            {
+                return self.collection(&callee.name, &expr.arguments);
+            }
+            expr::Expr::Variable(callee)
+                if matches!(
+                    callee.name.lexeme.as_str(),
+                    "abs" | "div" | "pow"
+                ) && self.lookup(&callee.name.lexeme) == Type::Unknown =>
+            {
+                return self.numeric(&callee.name, &expr.arguments);
+            }
+            callee => callee.accept(self),
+        };
+
+        match callee {
+            Type::Function(parameters, output) => {
+                self.arguments(&parameters, &expr.arguments);
+                *output
+            }
+            Type::Unknown => {
+                for argument in expr.arguments.iter() {
+                    argument.accept(self);
+                }
+
+                Type::Unknown
+            }
+            callee => {
+                self.error(
+                    Code::MismatchedTypes,
+                    &format!("Can't call '{}'.", callee),
+                );
+                Type::Unknown
+            }
+        }
+    }
+
+    fn visit_get_expr(&mut self, expr: &expr::Get) -> Self::Result {
+        let object = expr.object.accept(self);
+        self.at(&expr.name);
+
+        self.field(&object, &expr.name.lexeme)
+    }
+
+    fn visit_set_expr(&mut self, expr: &expr::Set) -> Self::Result {
+        let object = expr.object.accept(self);
+        let value = expr.value.accept(self);
+        self.at(&expr.name);
+
+        let field = self.field(&object, &expr.name.lexeme);
+        self.coerce(&field, &expr.value, &value);
+
+        Type::Unit
+    }
+
+    fn visit_index_expr(&mut self, expr: &expr::Index) -> Self::Result {
+        let object = expr.object.accept(self);
+        let index = expr.index.accept(self);
+
+        match object {
+            Type::Array(element) => {
+                self.expect(&Type::Integer, &index, false);
+                *element
+            }
+            Type::Map(key, value) => {
+                self.expect(&key, &index, false);
+                *value
+            }
+            Type::Str => {
+                self.expect(&Type::Integer, &index, false);
+                Type::Str
+            }
+            Type::Unknown => Type::Unknown,
+            object => {
+                self.error(
+                    Code::MismatchedTypes,
+                    &format!("Can't index '{}'.", object),
+                );
+                Type::Unknown
+            }
+        }
+    }
+
+    fn visit_set_index_expr(&mut self, expr: &expr::SetIndex) -> Self::Result {
+        let object = expr.object.accept(self);
+        let index = expr.index.accept(self);
+        let value = expr.value.accept(self);
+
+        if let Some(token) = expr.object.token() {
+            self.at(token);
+        }
+
+        match object {
+            Type::Array(element) => {
+                self.expect(&Type::Integer, &index, false);
+                self.coerce(&element, &expr.value, &value);
+            }
+            Type::Map(key, element) => {
+                self.expect(&key, &index, false);
+                self.coerce(&element, &expr.value, &value);
+            }
+            Type::Unknown => (),
+            object => {
+                self.error(
+                    Code::MismatchedTypes,
+                    &format!("Can't assign to an index of '{}'.", object),
+                );
+            }
+        }
+
+        Type::Unit
+    }
+
+    fn visit_grouping_expr(&mut self, expr: &expr::Grouping) -> Self::Result {
+        expr.expression.accept(self)
+    }
+
+    fn visit_array_expr(&mut self, expr: &expr::Array) -> Self::Result {
+        let mut element = Type::Unknown;
+
+        for value in expr.elements.iter() {
+            self.element(value, &mut element);
+        }
+
+        for value in expr.elements.iter() {
+            let kind = self.kind(value);
+            self.coerce(&element, value, &kind);
+        }
+
+        Type::Array(Box::new(element))
+    }
+
+    fn visit_map_expr(&mut self, expr: &expr::Map) -> Self::Result {
+        let mut key = Type::Unknown;
+        let mut value = Type::Unknown;
+
+        for (entry_key, entry_value) in expr.entries.iter() {
+            self.element(entry_key, &mut key);
+            self.element(entry_value, &mut value);
+        }
+
+        self.key(&key);
+
+        for (entry_key, entry_value) in expr.entries.iter() {
+            let kind = self.kind(entry_key);
+            self.coerce(&key, entry_key, &kind);
+
+            let kind = self.kind(entry_value);
+            self.coerce(&value, entry_value, &kind);
+        }
+
+        Type::Map(Box::new(key), Box::new(value))
+    }
+
+    fn visit_set_literal_expr(
+        &mut self,
+        expr: &expr::SetLiteral,
+    ) -> Self::Result {
+        let mut element = Type::Unknown;
+
+        for value in expr.elements.iter() {
+            self.element(value, &mut element);
+        }
+
+        self.key(&element);
+
+        for value in expr.elements.iter() {
+            let kind = self.kind(value);
+            self.coerce(&element, value, &kind);
+        }
+
+        Type::Set(Box::new(element))
+    }
+
+    fn visit_struct_expr(&mut self, expr: &expr::Struct) -> Self::Result {
+        self.at(&expr.name);
+
+        let fields = match self.structs.get(&expr.name.lexeme) {
+            Some(fields) => fields.clone(),
+            None => {
+                self.undefined_type("struct", &expr.name.lexeme);
+
+                for (_, value) in expr.fields.iter() {
+                    value.accept(self);
+                }
+
+                if let Some(base) = &expr.base {
+                    base.accept(self);
+                }
+
+                return Type::Unknown;
+            }
+        };
+
+        for (name, value) in expr.fields.iter() {
+            let kind = value.accept(self);
+            self.at(name);
+
+            match fields.iter().find(|(field, _)| *field == name.lexeme) {
+                Some((_, field)) => self.coerce(field, value, &kind),
+                None => self.error(
+                    Code::UndefinedItem,
+                    &format!(
+                        "Undefined field '{}' on '{}'.",
+                        name.lexeme, expr.name.lexeme
+                    ),
+                ),
+            }
+        }
+
+        let kind = Type::Named(expr.name.lexeme.clone());
+
+        if let Some(base) = &expr.base {
+            let actual = base.accept(self);
+            self.at(&expr.name);
+            self.expect(&kind, &actual, false);
+
+            return kind;
+        }
+
+        for (field, _) in fields.iter() {
+            if !expr.fields.iter().any(|(name, _)| name.lexeme == *field) {
+                self.error(
+                    Code::WrongCount,
+                    &format!(
+                        "Missing field '{}' in '{}'.",
+                        field, expr.name.lexeme
+                    ),
+                );
+            }
+        }
+
+        kind
+    }
+
+    fn visit_path_expr(&mut self, expr: &expr::Path) -> Self::Result {
+        self.at(&expr.name);
+
+        let namespace = &expr.namespace.lexeme;
+        let name = &expr.name.lexeme;
+
+        if let Some(variants) = self.enums.get(namespace) {
+            let fields = variants
+                .iter()
+                .find(|(variant, _)| variant == name)
+                .map(|(_, fields)| fields.clone());
+
+            if let Some(fields) = fields {
+                let kind = Type::Named(namespace.clone());
+
+                return if fields.is_empty() {
+                    kind
+                } else {
+                    Type::Function(fields, Box::new(kind))
+                };
+            }
+        }
+
+        match self.methods.get(&(namespace.clone(), name.clone())) {
+            Some((false, kind)) => kind.clone(),
+            _ if self.enums.contains_key(namespace)
+                || self.structs.contains_key(namespace) =>
+            {
+                self.error(
+                    Code::UndefinedItem,
+                    &format!("Undefined item '{}::{}'.", namespace, name),
+                );
+                Type::Unknown
+            }
+            _ => Type::Unknown,
+        }
+    }
+
+    fn visit_variable_expr(&mut self, expr: &expr::Variable) -> Self::Result {
+        self.at(&expr.name);
+        self.lookup(&expr.name.lexeme)
+    }
+
+    fn visit_literal_expr(&mut self, expr: &expr::Literal) -> Self::Result {
+        match &expr.value {
+            Value::False | Value::True => Type::Bool,
+            Value::None => Type::Optional(Box::new(Type::Unknown)),
+            Value::Number(_, Some(Suffix::Integer)) => Type::Integer,
+            Value::Number(_, Some(Suffix::Float)) => Type::Float,
+            Value::Number(digits, None) => {
+                if digits.contains(['.', 'e', 'E']) {
+                    Type::Float
+                } else {
+                    Type::Integer
+                }
+            }
+            Value::String(_) => Type::Str,
+        }
+    }
+}
+
+impl stmt::Visitor for Checker {
+    type Result = ();
+
+    fn visit_if_stmt(&mut self, stmt: &stmt::If) -> Self::Result {
+        self.condition(&stmt.condition);
+        stmt.then_branch.accept(self);
+
+        if let Some(branch) = &stmt.else_branch {
+            branch.accept(self);
+        }
+    }
+
+    fn visit_match_stmt(&mut self, stmt: &stmt::Match) -> Self::Result {
+        let value = stmt.value.accept(self);
+
+        for (pattern, guard, body) in stmt.arms.iter() {
+            self.scopes.push(HashMap::new());
+            self.scrutinee = value.clone();
+            pattern.accept(self);
+
+            if let Some(guard) = guard {
+                self.condition(guard);
+            }
+
+            body.accept(self);
+            self.scopes.pop();
+        }
+
+        let rows: Vec<_> = stmt
+            .arms
+            .iter()
+            .filter(|(_, guard, _)| guard.is_none())
+            .map(|(pattern, _, _)| vec![Some(pattern)])
+            .collect();
+
+        let cases: Vec<String> = self
+            .missing(&rows, slice::from_ref(&value))
+            .into_iter()
+            .map(|mut witness| witness.remove(0))
+            .collect();
+
+        if !cases.is_empty() {
+            let message = match value {
+                Type::Unknown => "Match doesn't cover every value.".to_string(),
+                value => {
+                    format!("Match doesn't cover every value of '{}'.", value)
+                }
+            };
+
+            self.at(&stmt.keyword);
+            self.push(Code::NonExhaustiveMatch, &message, Some(arms(&cases)));
+        }
+    }
+
+    fn visit_function_stmt(&mut self, stmt: &stmt::Function) -> Self::Result {
+        self.at(&stmt.name);
+
+        let mut scope: HashMap<String, Type> = stmt
+            .parameters
+            .iter()
+            .map(|(name, variant)| (name.lexeme.clone(), variant.accept(self)))
+            .collect();
+
+        if stmt.receiver {
+            let receiver = self.receiver.clone().unwrap_or(Type::Unknown);
+            scope.insert("self".to_string(), receiver);
+        }
+
+        let output = self.output(&stmt.output);
+        let output = self.output.replace(output);
+        let catching = mem::take(&mut self.catching);
+        let loops = mem::take(&mut self.loops);
+
+        self.scopes.push(scope);
+        stmt.body.accept(self);
+        self.scopes.pop();
+
+        self.output = output;
+        self.catching = catching;
+        self.loops = loops;
+    }
+
+    fn visit_test_stmt(&mut self, stmt: &stmt::Test) -> Self::Result {
+        self.at(&stmt.keyword);
+
+        let output = self.output.replace(Type::Unit);
+
+        self.scopes.push(HashMap::new());
+        stmt.body.accept(self);
+        self.scopes.pop();
+
+        self.output = output;
+    }
+
+    fn visit_bench_stmt(&mut self, stmt: &stmt::Bench) -> Self::Result {
+        self.at(&stmt.keyword);
+
+        let output = self.output.replace(Type::Unit);
+
+        self.scopes.push(HashMap::new());
+        stmt.body.accept(self);
+        self.scopes.pop();
+
+        self.output = output;
+    }
+
+    fn visit_return_stmt(&mut self, stmt: &stmt::Return) -> Self::Result {
+        self.at(&stmt.keyword);
+
+        let output = self.output.clone().unwrap_or(Type::Unit);
+
+        match &stmt.value {
+            Some(value) => {
+                let kind = value.accept(self);
+                self.at(&stmt.keyword);
+
+                match &output {
+                    Type::Unit => self.error(
+                        Code::MismatchedTypes,
+                        "Can't return a value from this function.",
+                    ),
+                    Type::Fallible(output, _) => {
+                        self.coerce(output, value, &kind)
+                    }
+                    output => self.coerce(output, value, &kind),
+                }
+            }
+            None if output != Type::Unit => self.error(
+                Code::MismatchedTypes,
+                &format!("Expect a return value of type '{}'.", output),
+            ),
+            None => (),
+        }
+    }
+
+    fn visit_raise_stmt(&mut self, stmt: &stmt::Raise) -> Self::Result {
+        let value = stmt.value.accept(self);
+        self.at(&stmt.keyword);
+
+        if self.catch(&value) {
+            return;
+        }
+
+        match self.output.clone() {
+            Some(Type::Fallible(_, error)) => {
+                self.expect(&error, &value, false)
+            }
+            _ => self.error(
+                Code::UnsupportedConstruct,
+                "Can't raise outside of a fallible function.",
+            ),
+        }
+    }
+
+    fn visit_try_stmt(&mut self, stmt: &stmt::Try) -> Self::Result {
+        self.catching.push(None);
+        stmt.body.accept(self);
+
+        let error = match self.catching.pop().flatten() {
+            Some(error) => error,
+            None => {
+                self.at(&stmt.keyword);
+                self.error(
+                    Code::UnsupportedConstruct,
+                    "Nothing in this 'try' block can raise an error.",
+                );
+                Type::Unknown
+            }
+        };
+
+        let mut scope = HashMap::new();
+
+        if let Some(name) = &stmt.error {
+            scope.insert(name.lexeme.clone(), error);
+        }
+
+        self.scopes.push(scope);
+        stmt.handler.accept(self);
+        self.scopes.pop();
+    }
+
+    fn visit_loop_stmt(&mut self, stmt: &stmt::Loop) -> Self::Result {
+        let label = stmt.label.as_ref().map(|label| label.lexeme.clone());
+
+        self.loops.push((label, false, None));
+        stmt.body.accept(self);
+        self.loops.pop();
+    }
+
+    fn visit_for_stmt(&mut self, stmt: &stmt::For) -> Self::Result {
+        let start = stmt.start.accept(self);
+        let end = stmt.end.accept(self);
+        self.at(&stmt.variable);
+
+        self.expect(&Type::Integer, &start, false);
+        self.expect(&Type::Integer, &end, false);
+
+        let label = stmt.label.as_ref().map(|label| label.lexeme.clone());
+
+        self.scopes.push(HashMap::new());
+        self.declare(&stmt.variable.lexeme, Type::Integer);
+        self.loops.push((label, false, None));
+        stmt.body.accept(self);
+        self.loops.pop();
+        self.scopes.pop();
+    }
+
+    // A 'loop' used as a value takes the type its breaks agree on, and a
+    // bare 'break' gives '()'. Other loops can't break with a value.
+    fn visit_break_stmt(&mut self, stmt: &stmt::Break) -> Self::Result {
+        let value = match &stmt.value {
+            Some(value) => value.accept(self),
+            None => Type::Unit,
+        };
+
+        self.at(&stmt.keyword);
+
+        let index = match &stmt.label {
+            Some(label) => self.loops.iter().rposition(|(name, _, _)| {
+                name.as_deref() == Some(label.lexeme.as_str())
+            }),
+            None => self.loops.len().checked_sub(1),
+        };
+
+        let (valued, kind) = match index {
+            Some(index) => {
+                let (_, valued, kind) = &self.loops[index];
+                (*valued, kind.clone())
+            }
+            None => return,
+        };
+
+        if !valued {
+            if stmt.value.is_some() {
+                self.error(
+                    Code::UnsupportedConstruct,
+                    &Code::UnsupportedConstruct
+                        .message(&[&"break with a value out of this loop"]),
+                );
+            }
+
+            return;
+        }
+
+        match kind {
+            Some(kind) => self.expect(&kind, &value, false),
+            None => {
+                if let Some(index) = index {
+                    self.loops[index].2 = Some(value);
+                }
+            }
+        }
+    }
+
+    fn visit_continue_stmt(&mut self, _stmt: &stmt::Continue) -> Self::Result {}
+
+    fn visit_let_stmt(&mut self, stmt: &stmt::Let) -> Self::Result {
+        self.at(&stmt.name);
+
+        let initializer = match &stmt.initializer {
+            Some(initializer) => initializer.accept(self),
+            None => Type::Unknown,
+        };
+
+        self.at(&stmt.name);
+
+        let kind = match (&stmt.variant, &stmt.initializer) {
+            (Some(variant), Some(value)) => {
+                let kind = variant.accept(self);
+                self.coerce(&kind, value, &initializer);
+                kind
+            }
+            (Some(variant), None) => variant.accept(self),
+            (None, _) => initializer,
+        };
+
+        let example = match &stmt.initializer {
+            Some(expr::Expr::Array(array)) if array.elements.is_empty() => {
+                Some("[i32] = []")
+            }
+            Some(expr::Expr::Map(map)) if map.entries.is_empty() => {
+                Some("map[str, i32] = {}")
+            }
+            Some(expr::Expr::Literal(literal))
+                if matches!(literal.value, Value::None) =>
+            {
+                Some("i32? = none")
+            }
+            _ => None,
+        };
+
+        if let Some(example) = example.filter(|_| stmt.variant.is_none()) {
+            if self.scopes.len() > 1 {
+                self.push(
+                    Code::UnknownType,
+                    &Code::UnknownType.message(&[&stmt.name.lexeme]),
+                    Some(format!(
+                        "Give it a type, like 'let {}: {}'.",
+                        stmt.name.lexeme, example
+                    )),
+                );
+            }
+        }
+
+        if self.scopes.len() > 1 {
+            self.declare(&stmt.name.lexeme, kind);
+        } else if stmt.initializer.is_none() {
+            self.error(
+                Code::UnsupportedConstruct,
+                "Global variable needs an initializer.",
+            );
+        } else if stmt.variant.is_none() {
+            self.error(
+                Code::UnsupportedConstruct,
+                "Global variable needs a type.",
+            );
+        }
+    }
+
+    fn visit_const_stmt(&mut self, stmt: &stmt::Const) -> Self::Result {
+        self.at(&stmt.name);
+
+        let initializer = stmt.initializer.accept(self);
+        self.at(&stmt.name);
+
+        let kind = stmt.variant.accept(self);
+        self.coerce(&kind, &stmt.initializer, &initializer);
+
+        if self.scopes.len() > 1 {
+            self.declare(&stmt.name.lexeme, kind);
+        }
+    }
+
+    fn visit_type_stmt(&mut self, stmt: &stmt::Type) -> Self::Result {
+        self.at(&stmt.name);
+        stmt.variant.accept(self);
+    }
+
+    fn visit_struct_stmt(&mut self, _stmt: &stmt::Struct) -> Self::Result {}
+
+    fn visit_enum_stmt(&mut self, _stmt: &stmt::Enum) -> Self::Result {}
+
+    fn visit_impl_stmt(&mut self, stmt: &stmt::Impl) -> Self::Result {
+        self.at(&stmt.name);
+
+        let name = &stmt.name.lexeme;
+
+        if !self.structs.contains_key(name) && !self.enums.contains_key(name) {
+            self.undefined_type("type", name);
+        }
+
+        let receiver =
+            self.receiver.replace(Type::Named(stmt.name.lexeme.clone()));
+
+        for method in stmt.methods.iter() {
+            method.accept(self);
+        }
+
+        self.receiver = receiver;
+    }
+
+    fn visit_block_stmt(&mut self, stmt: &stmt::Block) -> Self::Result {
+        self.scopes.push(HashMap::new());
+        self.hoist(&stmt.statements);
+
+        for statement in stmt.statements.iter() {
+            statement.accept(self);
+        }
+
+        self.scopes.pop();
+    }
+
+    fn visit_assignment_stmt(
+        &mut self,
+        stmt: &stmt::Assignment,
+    ) -> Self::Result {
+        self.at(&stmt.name);
+
+        let value = stmt.value.accept(self);
+        self.at(&stmt.name);
+
+        let variable = self.lookup(&stmt.name.lexeme);
+        self.coerce(&variable, &stmt.value, &value);
+    }
+
+    fn visit_expression_stmt(
+        &mut self,
+        stmt: &stmt::Expression,
+    ) -> Self::Result {
+        stmt.expression.accept(self);
+    }
+}
+
+impl variant::Visitor for Checker {
+    type Result = Type;
+
+    fn visit_literal_variant(
+        &mut self,
+        variant: &variant::Literal,
+    ) -> Self::Result {
+        let name = &variant.name.lexeme;
+
+        match name.as_str() {
+            "i32" => Type::Integer,
+            "f64" => Type::Float,
+            "bool" => Type::Bool,
+            "str" => Type::Str,
+            _ if self.structs.contains_key(name)
+                || self.enums.contains_key(name) =>
+            {
+                Type::Named(name.clone())
+            }
+            _ if self.resolving.contains(name) => {
+                self.error(
+                    Code::RecursiveAlias,
+                    &Code::RecursiveAlias.message(&[name]),
+                );
+                Type::Unknown
+            }
+            _ => match self.aliases.get(name).cloned() {
+                Some(alias) => {
+                    self.resolving.insert(name.clone());
+                    let kind = alias.accept(self);
+                    self.resolving.remove(name);
+
+                    kind
+                }
+                None => {
+                    self.at(&variant.name);
+                    self.undefined_type("type", name);
+
+                    Type::Unknown
+                }
+            },
+        }
+    }
+
+    fn visit_function_variant(
+        &mut self,
+        variant: &variant::Function,
+    ) -> Self::Result {
+        let parameters = variant
+            .parameters
+            .iter()
+            .map(|parameter| parameter.accept(self))
+            .collect();
+
+        Type::Function(parameters, Box::new(self.output(&variant.output)))
+    }
+
+    fn visit_array_variant(
+        &mut self,
+        variant: &variant::Array,
+    ) -> Self::Result {
+        Type::Array(Box::new(variant.element.accept(self)))
+    }
+
+    fn visit_map_variant(&mut self, variant: &variant::Map) -> Self::Result {
+        let key = variant.key.accept(self);
+        self.key(&key);
+
+        Type::Map(Box::new(key), Box::new(variant.value.accept(self)))
+    }
+
+    fn visit_set_variant(&mut self, variant: &variant::Set) -> Self::Result {
+        let element = variant.element.accept(self);
+        self.key(&element);
+
+        Type::Set(Box::new(element))
+    }
+
+    fn visit_optional_variant(
+        &mut self,
+        variant: &variant::Optional,
+    ) -> Self::Result {
+        Type::Optional(Box::new(variant.inner.accept(self)))
+    }
+
+    fn visit_fallible_variant(
+        &mut self,
+        variant: &variant::Fallible,
+    ) -> Self::Result {
+        Type::Fallible(
+            Box::new(variant.value.accept(self)),
+            Box::new(variant.error.accept(self)),
+        )
+    }
+}
+
+impl pattern::Visitor for Checker {
+    type Result = ();
+
+    fn visit_literal_pattern(
+        &mut self,
+        pattern: &pattern::Literal,
+    ) -> Self::Result {
+        let kind = expr::Literal {
+            value: pattern.value.clone(),
+        };
+        let kind = expr::Visitor::visit_literal_expr(self, &kind);
+        let scrutinee = self.scrutinee.clone();
+
+        self.expect(&scrutinee, &kind, false);
+    }
+
+    fn visit_path_pattern(&mut self, pattern: &pattern::Path) -> Self::Result {
+        self.at(&pattern.name);
+
+        let namespace = &pattern.namespace.lexeme;
+        let name = &pattern.name.lexeme;
+        let scrutinee = self.scrutinee.clone();
+
+        self.expect(&scrutinee, &Type::Named(namespace.clone()), false);
+
+        let fields = self.enums.get(namespace).and_then(|variants| {
+            variants
+                .iter()
+                .find(|(variant, _)| variant == name)
+                .map(|(_, fields)| fields.clone())
+        });
+
+        let fields = match fields {
+            Some(fields) => fields,
+            None => {
+                self.error(
+                    Code::UndefinedItem,
+                    &format!("Undefined variant '{}::{}'.", namespace, name),
+                );
+
+                vec![Type::Unknown; pattern.fields.len()]
+            }
+        };
+
+        if fields.len() != pattern.fields.len() {
+            self.error(
+                Code::WrongCount,
+                &format!(
+                    "Expect {} fields but got {}.",
+                    fields.len(),
+                    pattern.fields.len()
+                ),
+            );
+        }
+
+        for (field, kind) in pattern.fields.iter().zip(fields) {
+            if let pattern::Pattern::Literal(literal) = field {
+                if let Value::String(_) = literal.value {
+                    self.error(
+                        Code::UnsupportedConstruct,
+                        "Can't match a string inside a variant.",
+                    );
+                }
+            }
+
+            self.scrutinee = kind;
+            field.accept(self);
+        }
+
+        self.scrutinee = scrutinee;
+    }
+
+    fn visit_binding_pattern(
+        &mut self,
+        pattern: &pattern::Binding,
+    ) -> Self::Result {
+        let scrutinee = self.scrutinee.clone();
+        self.declare(&pattern.name.lexeme, scrutinee);
+    }
+
+    fn visit_wildcard_pattern(
+        &mut self,
+        _pattern: &pattern::Wildcard,
+    ) -> Self::Result {
+    }
+}
@@ -0,0 +1,974 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use blaze::stmt::Stmt;
+use blaze::{
+    Code, Diagnostic, Format, Level, Options, Output, Reporter, Semicolons,
+    Stage,
+};
+
+use crate::backend::Backend;
+use crate::doc;
+use crate::json::Json;
+use crate::lsp::Server;
+use crate::repl::Repl;
+
+const LINTS: &[&str] = &["deprecated", "unreachable", "unused", "warnings"];
+const FORMAT_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn piped(result: io::Result<i32>) -> io::Result<i32> {
+    match result {
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => Ok(0),
+        result => result,
+    }
+}
+
+fn manifest(name: &str) -> String {
+    let mut package: String = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' => c,
+            _ => '_',
+        })
+        .collect();
+
+    if !package.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        package.insert_str(0, "blaze_");
+    }
+
+    format!(
+        concat!(
+            "[package]\n",
+            "name = \"{}\"\n",
+            "version = \"0.1.0\"\n",
+            "edition = \"2018\"\n",
+        ),
+        package
+    )
+}
+
+struct Settings {
+    check: bool,
+    doc: bool,
+    test: bool,
+    bench: bool,
+    emit_tokens: bool,
+    emit_ast: bool,
+    emit_symbols: bool,
+    emit_rust: bool,
+    cargo: bool,
+    pretty: bool,
+    keep: bool,
+    out_dir: Option<PathBuf>,
+    temp_dir: Option<PathBuf>,
+    target: Option<String>,
+    opt_level: u8,
+    debug: bool,
+    rustc: Option<String>,
+    toolchain: Option<String>,
+    rustc_args: Vec<String>,
+    options: Options,
+}
+
+fn pretty(output: String) -> String {
+    let child = Command::new("rustfmt")
+        .arg("--edition")
+        .arg("2018")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+
+    let mut child = match child {
+        Ok(child) => child,
+        Err(_) => return output,
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        if stdin.write_all(output.as_bytes()).is_err() {
+            return output;
+        }
+    }
+
+    let reader = child.stdout.take().map(|mut stdout| {
+        thread::spawn(move || {
+            let mut formatted = String::new();
+            stdout.read_to_string(&mut formatted).map(|_| formatted)
+        })
+    });
+
+    // rustfmt can take minutes on long chains of operators, so past a
+    // deadline the code is left as it was generated.
+    let mut waited = Duration::ZERO;
+
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if waited < FORMAT_TIMEOUT => {
+                thread::sleep(Duration::from_millis(10));
+                waited += Duration::from_millis(10);
+            }
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return output;
+            }
+        }
+    };
+
+    match reader.map(|reader| reader.join()) {
+        Some(Ok(Ok(formatted))) if status.success() => formatted,
+        _ => output,
+    }
+}
+
+fn codegen(settings: &Settings) -> Vec<String> {
+    let level = if settings.debug {
+        0
+    } else {
+        settings.opt_level
+    };
+    let mut flags = vec!["-C".to_string(), format!("opt-level={}", level)];
+
+    if settings.debug {
+        flags.push("-C".to_string());
+        flags.push("debuginfo=2".to_string());
+    }
+
+    flags
+}
+
+fn toolchain(program: &str, settings: &Settings) -> Command {
+    let program = match (program, &settings.rustc) {
+        ("rustc", Some(rustc)) => rustc.as_str(),
+        _ => program,
+    };
+
+    let mut command = Command::new(program);
+
+    if let Some(toolchain) = &settings.toolchain {
+        command.arg(format!("+{}", toolchain));
+    }
+
+    if program == "cargo" {
+        if let Some(rustc) = &settings.rustc {
+            command.env("RUSTC", rustc);
+        }
+    }
+
+    command
+}
+
+fn missing(command: &Command, error: io::Error) -> ! {
+    eprintln!(
+        "failed to run '{}': {}",
+        command.get_program().to_string_lossy(),
+        error
+    );
+    eprintln!(
+        "install Rust from https://rustup.rs, or pass the path to rustc with \
+         --rustc or BLAZE_RUSTC"
+    );
+
+    process::exit(1)
+}
+
+fn installed(target: &str, settings: &Settings) -> Result<(), String> {
+    let mut command = toolchain("rustc", settings);
+    command
+        .arg("--print")
+        .arg("target-libdir")
+        .arg("--target")
+        .arg(target);
+
+    let output = command
+        .output()
+        .unwrap_or_else(|error| missing(&command, error));
+
+    if !output.status.success() {
+        return Err(format!("unknown target '{}'", target));
+    }
+
+    let directory = String::from_utf8_lossy(&output.stdout);
+
+    if Path::new(directory.trim()).exists() {
+        Ok(())
+    } else {
+        Err(format!(
+            "target '{}' is not installed, try 'rustup target add {}'",
+            target, target
+        ))
+    }
+}
+
+fn build(args: &[String], settings: &Settings) -> io::Result<i32> {
+    let (file, source) = if args[0] == "-" {
+        let mut source = String::new();
+        io::stdin().read_to_string(&mut source)?;
+
+        ("<stdin>", source)
+    } else {
+        (args[0].as_str(), fs::read_to_string(&args[0])?)
+    };
+
+    let stage = if settings.emit_tokens {
+        Stage::Scan
+    } else if settings.emit_ast || settings.doc {
+        Stage::Parse
+    } else if settings.emit_symbols {
+        Stage::Resolve
+    } else if settings.check {
+        Stage::Check
+    } else {
+        Stage::Generate
+    };
+
+    let reporter = Reporter::new(
+        file,
+        settings.options.format,
+        &source,
+        settings.options.lints.clone(),
+        settings.options.max_errors,
+    );
+
+    let output =
+        match blaze::compile_to(&reporter, &source, &settings.options, stage) {
+            Some(Output::Tokens(tokens)) => {
+                let mut stdout = io::stdout().lock();
+
+                for token in tokens.iter() {
+                    writeln!(
+                        stdout,
+                        "[line {}, column {}] {} ({}..{})",
+                        token.line,
+                        token.span.column,
+                        token,
+                        token.span.start,
+                        token.span.end
+                    )?;
+                }
+
+                return Ok(0);
+            }
+            Some(Output::Ast(statements)) if settings.doc => {
+                return document(args, &statements);
+            }
+            Some(Output::Ast(statements)) => {
+                writeln!(io::stdout().lock(), "{:#?}", statements)?;
+                return Ok(0);
+            }
+            Some(Output::Symbols(symbols)) => {
+                let mut stdout = io::stdout().lock();
+
+                for symbol in symbols.iter() {
+                    writeln!(
+                        stdout,
+                        "[line {}, column {}] {} {} ({}..{})",
+                        symbol.name.line,
+                        symbol.name.span.column,
+                        symbol.kind,
+                        symbol.name.lexeme,
+                        symbol.name.span.start,
+                        symbol.name.span.end
+                    )?;
+
+                    for reference in symbol.references.iter() {
+                        writeln!(
+                            stdout,
+                            "    [line {}, column {}] ({}..{})",
+                            reference.line,
+                            reference.span.column,
+                            reference.span.start,
+                            reference.span.end
+                        )?;
+                    }
+                }
+
+                return Ok(0);
+            }
+            Some(Output::Checked) => return Ok(0),
+            Some(Output::Rust(output)) => output,
+            None => return Ok(1),
+        };
+
+    if settings.test {
+        return harness(output, &["--test"], file, &source, settings);
+    }
+
+    if settings.bench {
+        return harness(
+            output,
+            &["-O", "--cfg", "blaze_bench"],
+            file,
+            &source,
+            settings,
+        );
+    }
+
+    let output = if settings.pretty {
+        pretty(output)
+    } else {
+        output
+    };
+
+    if args
+        .get(1)
+        .map_or(settings.emit_rust, |output| output == "-")
+    {
+        writeln!(io::stdout().lock(), "{}", output)?;
+        return Ok(0);
+    }
+
+    let path = match &settings.out_dir {
+        Some(directory) => directory.join(&args[1]),
+        None => PathBuf::from(&args[1]),
+    };
+
+    if let Some(directory) = &settings.out_dir {
+        fs::create_dir_all(directory)?;
+    }
+
+    if !settings.emit_rust {
+        if let Some(target) = &settings.target {
+            if let Err(message) = installed(target, settings) {
+                eprintln!("{}", message);
+                return Ok(1);
+            }
+        }
+    }
+
+    if settings.cargo {
+        let project = path.as_path();
+        let name = project.file_name().unwrap_or_default().to_string_lossy();
+
+        fs::create_dir_all(project.join("src"))?;
+        fs::write(project.join("Cargo.toml"), manifest(&name))?;
+        fs::write(project.join("src").join("main.rs"), output)?;
+
+        if settings.emit_rust {
+            return Ok(0);
+        }
+
+        let mut command = toolchain("cargo", settings);
+        command
+            .arg("rustc")
+            .args(
+                Some("--release")
+                    .filter(|_| settings.opt_level > 0 && !settings.debug),
+            )
+            .arg("--manifest-path")
+            .arg(project.join("Cargo.toml"))
+            .args(
+                settings
+                    .target
+                    .iter()
+                    .flat_map(|target| ["--target", target]),
+            )
+            .arg("--")
+            .args(codegen(settings))
+            .args(&settings.rustc_args);
+
+        let status = command
+            .status()
+            .unwrap_or_else(|error| missing(&command, error));
+
+        return Ok(status.code().unwrap_or(0));
+    }
+
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    let directory = match &settings.temp_dir {
+        Some(directory) => directory.clone(),
+        None => env::temp_dir().join(format!("blaze-{}", process::id())),
+    };
+
+    let destination = if settings.emit_rust
+        || (settings.keep && settings.temp_dir.is_none())
+    {
+        path.with_file_name(format!("{}.rs", name))
+    } else {
+        fs::create_dir_all(&directory)?;
+        directory.join(format!("{}.rs", name))
+    };
+
+    fs::write(&destination, &output)?;
+
+    if settings.emit_rust {
+        return Ok(0);
+    }
+
+    let binary = if path.extension().is_none() {
+        path.with_file_name(format!("{}{}", name, env::consts::EXE_SUFFIX))
+    } else {
+        path.clone()
+    };
+
+    let code = rustc(
+        toolchain("rustc", settings)
+            .args(codegen(settings))
+            .arg(&destination)
+            .arg("-o")
+            .arg(&binary)
+            .args(
+                settings
+                    .target
+                    .iter()
+                    .flat_map(|target| ["--target", target]),
+            )
+            .args(&settings.rustc_args),
+        &output,
+        file,
+        &source,
+        settings,
+    );
+
+    if !settings.keep {
+        fs::remove_file(&destination)?;
+
+        if settings.temp_dir.is_none() {
+            fs::remove_dir(&directory)?;
+        }
+    }
+
+    Ok(code)
+}
+
+fn document(args: &[String], statements: &[Stmt]) -> io::Result<i32> {
+    let title = match args[0].as_str() {
+        "-" => "stdin".to_string(),
+        path => Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    };
+
+    match args.get(1) {
+        Some(output) if output == "-" => write!(
+            io::stdout().lock(),
+            "{}",
+            doc::markdown(&title, statements)
+        )?,
+        Some(output) if output.ends_with(".html") => {
+            fs::write(output, doc::html(&title, statements))?
+        }
+        Some(output) => fs::write(output, doc::markdown(&title, statements))?,
+        None => write!(
+            io::stdout().lock(),
+            "{}",
+            doc::markdown(&title, statements)
+        )?,
+    }
+
+    Ok(0)
+}
+
+fn rustc(
+    command: &mut Command,
+    rust: &str,
+    file: &str,
+    source: &str,
+    settings: &Settings,
+) -> i32 {
+    let output = command
+        .arg("--error-format=json")
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::piped())
+        .output()
+        .unwrap_or_else(|error| missing(command, error));
+
+    let backend = Backend::new(rust, source);
+    let reporter = Reporter::new(
+        file,
+        settings.options.format,
+        source,
+        settings.options.lints.clone(),
+        settings.options.max_errors,
+    );
+
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    for line in String::from_utf8_lossy(&output.stderr).lines() {
+        let diagnostic = match Json::parse(line) {
+            Some(message) => backend.convert(&message),
+            None => {
+                eprintln!("{}", line);
+                continue;
+            }
+        };
+
+        if let Some(diagnostic) = diagnostic {
+            let duplicate = diagnostics.iter().any(|seen| {
+                seen.message == diagnostic.message
+                    && seen.span == diagnostic.span
+            });
+
+            if !duplicate {
+                diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    let failed = reporter.report(diagnostics);
+
+    match output.status.code() {
+        Some(0) if failed => 1,
+        Some(code) => code,
+        None => 1,
+    }
+}
+
+fn harness(
+    output: String,
+    flags: &[&str],
+    file: &str,
+    source: &str,
+    settings: &Settings,
+) -> io::Result<i32> {
+    let directory = settings
+        .temp_dir
+        .clone()
+        .unwrap_or_else(env::temp_dir)
+        .join(format!("blaze-harness-{}", process::id()));
+    let destination = directory.join("harness.rs");
+    let binary = directory.join(format!("harness{}", env::consts::EXE_SUFFIX));
+
+    fs::create_dir_all(&directory)?;
+    fs::write(&destination, &output)?;
+
+    let code = rustc(
+        toolchain("rustc", settings)
+            .args(flags)
+            .arg(&destination)
+            .arg("-o")
+            .arg(&binary)
+            .args(&settings.rustc_args),
+        &output,
+        file,
+        source,
+        settings,
+    );
+
+    let code = if code == 0 {
+        Command::new(&binary).status()?.code().unwrap_or(1)
+    } else {
+        code
+    };
+
+    fs::remove_dir_all(&directory)?;
+
+    Ok(code)
+}
+
+fn watch(args: &[String], settings: &Settings) {
+    let mut modified = None;
+
+    loop {
+        let current = fs::metadata(&args[0])
+            .and_then(|metadata| metadata.modified())
+            .ok();
+
+        if current.is_some() && current != modified {
+            modified = current;
+
+            if let Err(error) = build(args, settings) {
+                eprintln!("{}", error);
+            }
+
+            eprintln!("Watching '{}' for changes...", args[0]);
+        }
+
+        thread::sleep(Duration::from_millis(500));
+    }
+}
+
+fn parse(
+    mut arguments: impl Iterator<Item = String>,
+) -> Result<(Settings, Vec<String>, Option<String>), String> {
+    let mut args = Vec::new();
+    let mut settings = Settings {
+        check: false,
+        doc: false,
+        test: false,
+        bench: false,
+        emit_tokens: false,
+        emit_ast: false,
+        emit_symbols: false,
+        emit_rust: false,
+        cargo: false,
+        pretty: true,
+        keep: false,
+        out_dir: None,
+        temp_dir: None,
+        target: None,
+        opt_level: 0,
+        debug: false,
+        rustc: None,
+        toolchain: None,
+        rustc_args: Vec::new(),
+        options: Options::new(),
+    };
+    let mut output = None;
+
+    while let Some(arg) = arguments.next() {
+        match arg.as_str() {
+            "--" => settings.rustc_args.extend(arguments.by_ref()),
+            "--explicit-semicolons" => {
+                settings.options.semicolons = Semicolons::Explicit
+            }
+            "--optimize" => settings.options.optimize = true,
+            "--emit-tokens" => settings.emit_tokens = true,
+            "--emit-ast" => settings.emit_ast = true,
+            "--emit-symbols" => settings.emit_symbols = true,
+            "--emit-rust" => settings.emit_rust = true,
+            "--cargo" => settings.cargo = true,
+            "--no-format" => settings.pretty = false,
+            "--keep-intermediate" => settings.keep = true,
+            "--no-intermediate" => settings.keep = false,
+            "-A" | "-W" | "-D" => {
+                let level = match arg.as_str() {
+                    "-A" => Level::Allow,
+                    "-W" => Level::Warn,
+                    _ => Level::Deny,
+                };
+
+                match arguments.next() {
+                    Some(lint) if LINTS.contains(&lint.as_str()) => {
+                        settings.options.lints.push((lint, level))
+                    }
+                    Some(lint) => {
+                        return Err(format!("unknown lint '{}'", lint))
+                    }
+                    None => {
+                        return Err(format!("missing lint after '{}'", arg))
+                    }
+                }
+            }
+            "--max-errors" => match arguments.next() {
+                Some(count) => match count.parse() {
+                    Ok(count) if count > 0 => {
+                        settings.options.max_errors = Some(count)
+                    }
+                    _ => {
+                        return Err(format!("invalid error count '{}'", count))
+                    }
+                },
+                None => {
+                    return Err(format!("missing error count after '{}'", arg))
+                }
+            },
+            "--no-fail-fast" => settings.options.fail_fast = false,
+            "-g" => settings.debug = true,
+            "-O0" => settings.opt_level = 0,
+            "-O1" => settings.opt_level = 1,
+            "-O" | "-O2" => settings.opt_level = 2,
+            "-O3" | "--release" => settings.opt_level = 3,
+            "--rustc" => match arguments.next() {
+                Some(path) => settings.rustc = Some(path),
+                None => return Err(format!("missing path after '{}'", arg)),
+            },
+            "--target" => match arguments.next() {
+                Some(target) => settings.target = Some(target),
+                None => return Err(format!("missing target after '{}'", arg)),
+            },
+            "--out-dir" | "--temp-dir" => match arguments.next() {
+                Some(path) if arg == "--out-dir" => {
+                    settings.out_dir = Some(PathBuf::from(path))
+                }
+                Some(path) => settings.temp_dir = Some(PathBuf::from(path)),
+                None => {
+                    return Err(format!("missing directory after '{}'", arg))
+                }
+            },
+            "-o" => match arguments.next() {
+                Some(path) => output = Some(path),
+                None => return Err(format!("missing output after '{}'", arg)),
+            },
+            "--error-format=human" => settings.options.format = Format::Human,
+            "--error-format=json" => settings.options.format = Format::Json,
+            _ if arg.starts_with('+') && arg.len() > 1 => {
+                settings.toolchain = Some(arg[1..].to_string())
+            }
+            _ if arg.starts_with("--") => {
+                return Err(format!("unknown option '{}'", arg))
+            }
+            _ => args.push(arg),
+        }
+    }
+
+    Ok((settings, args, output))
+}
+
+pub fn run() -> io::Result<()> {
+    let (mut settings, mut args, output) = match parse(env::args().skip(1)) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    };
+
+    if settings.rustc.is_none() {
+        settings.rustc = env::var("BLAZE_RUSTC").ok();
+    }
+
+    if args.len() == 1 && args[0] == "lsp" {
+        return Server::new().run();
+    }
+
+    if args.len() == 1 && args[0] == "repl" {
+        let mut rustc = toolchain("rustc", &settings);
+        rustc.args(&settings.rustc_args);
+
+        return Repl::new(rustc, settings.options).run();
+    }
+
+    if args.len() == 2 && args[0] == "explain" {
+        match Code::find(&args[1]) {
+            Some(code) => {
+                let written = writeln!(
+                    io::stdout().lock(),
+                    "{}\n\n{}",
+                    code.name(),
+                    code.explanation()
+                );
+
+                process::exit(piped(written.map(|_| 0))?);
+            }
+            None => {
+                eprintln!("unknown error code '{}'", args[1]);
+                process::exit(1);
+            }
+        }
+    }
+
+    let watching = matches!(args.len(), 2 | 3) && args[0] == "watch";
+    settings.check = matches!(args.len(), 2) && args[0] == "check";
+    settings.doc = matches!(args.len(), 2 | 3) && args[0] == "doc";
+    settings.test = matches!(args.len(), 2) && args[0] == "test";
+    settings.bench = matches!(args.len(), 2) && args[0] == "bench";
+
+    if watching
+        || settings.check
+        || settings.doc
+        || settings.test
+        || settings.bench
+    {
+        args.remove(0);
+    }
+
+    if let Some(output) = output {
+        args.push(output);
+    }
+
+    if watching && args.len() == 1 {
+        settings.check = true;
+    }
+
+    let single = settings.check
+        || settings.doc
+        || settings.test
+        || settings.bench
+        || settings.emit_tokens
+        || settings.emit_ast
+        || settings.emit_symbols
+        || settings.emit_rust;
+
+    if args.len() != 2 && !(single && args.len() == 1) {
+        println!("usage: blaze [options] [script] [output] [-- rustc options]");
+        println!("       blaze check [options] [script]");
+        println!("       blaze doc [options] [script] [output]");
+        println!("       blaze test [options] [script]");
+        println!("       blaze bench [options] [script]");
+        println!("       blaze watch [options] [script] [output]");
+        println!("       blaze explain [code]");
+        println!("       blaze lsp");
+        println!("       blaze repl");
+        process::exit(1);
+    }
+
+    if watching && args[0] == "-" {
+        eprintln!("can't watch standard input");
+        process::exit(1);
+    }
+
+    if watching {
+        watch(&args, &settings);
+    }
+
+    process::exit(piped(build(&args, &settings))?);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{manifest, parse, Level, Semicolons, Settings};
+
+    fn settings(args: &[&str]) -> Settings {
+        match parse(args.iter().map(|arg| arg.to_string())) {
+            Ok((settings, _, _)) => settings,
+            Err(message) => panic!("{}", message),
+        }
+    }
+
+    fn error(args: &[&str]) -> String {
+        match parse(args.iter().map(|arg| arg.to_string())) {
+            Ok(_) => panic!("expected {:?} to fail", args),
+            Err(message) => message,
+        }
+    }
+
+    fn package(name: &str) -> String {
+        manifest(name).lines().nth(1).unwrap().to_string()
+    }
+
+    #[test]
+    fn keeps_valid_names() {
+        assert_eq!(package("hello-world_2"), "name = \"hello-world_2\"");
+    }
+
+    #[test]
+    fn replaces_invalid_characters() {
+        assert_eq!(package("my app.v2"), "name = \"my_app_v2\"");
+    }
+
+    #[test]
+    fn prefixes_leading_digits() {
+        assert_eq!(package("2048"), "name = \"blaze_2048\"");
+        assert_eq!(package("-x"), "name = \"blaze_-x\"");
+        assert_eq!(package(""), "name = \"blaze_\"");
+    }
+
+    #[test]
+    fn bare_optimize_flag_is_level_two() {
+        assert_eq!(settings(&["-O"]).opt_level, 2);
+        assert_eq!(settings(&["-O3", "-O"]).opt_level, 2);
+        assert_eq!(settings(&["-O", "-O1"]).opt_level, 1);
+    }
+
+    #[test]
+    fn rejects_unknown_options() {
+        assert_eq!(error(&["--fast"]), "unknown option '--fast'");
+        assert_eq!(error(&["--explicit"]), "unknown option '--explicit'");
+        assert!(
+            settings(&["--explicit-semicolons"]).options.semicolons
+                == Semicolons::Explicit
+        );
+    }
+
+    #[test]
+    fn keeps_rustc_arguments_after_the_separator() {
+        let (parsed, args, _) = parse(
+            ["run.blz", "run", "--", "--cfg", "x"]
+                .iter()
+                .map(|arg| arg.to_string()),
+        )
+        .unwrap();
+
+        assert_eq!(args, vec!["run.blz", "run"]);
+        assert_eq!(parsed.rustc_args, vec!["--cfg", "x"]);
+    }
+
+    #[test]
+    fn cargo_flag_builds_a_project() {
+        assert!(!settings(&[]).cargo);
+        assert!(settings(&["--cargo"]).cargo);
+    }
+
+    #[test]
+    fn lint_flags_take_a_known_lint() {
+        assert_eq!(
+            settings(&["-A", "unused", "-D", "warnings"]).options.lints,
+            vec![
+                ("unused".to_string(), Level::Allow),
+                ("warnings".to_string(), Level::Deny),
+            ]
+        );
+
+        assert_eq!(error(&["-W", "bogus"]), "unknown lint 'bogus'");
+        assert_eq!(error(&["-D"]), "missing lint after '-D'");
+    }
+
+    #[test]
+    fn output_can_follow_dash_o() {
+        let (_, args, output) =
+            parse(["run.blz", "-o", "-"].iter().map(|arg| arg.to_string()))
+                .unwrap();
+
+        assert_eq!(args, vec!["run.blz"]);
+        assert_eq!(output.as_deref(), Some("-"));
+
+        assert_eq!(error(&["-o"]), "missing output after '-o'");
+    }
+
+    #[test]
+    fn directories_take_a_path() {
+        let parsed = settings(&["--out-dir", "out", "--temp-dir", "tmp"]);
+        assert_eq!(parsed.out_dir, Some(PathBuf::from("out")));
+        assert_eq!(parsed.temp_dir, Some(PathBuf::from("tmp")));
+
+        assert_eq!(
+            error(&["--out-dir"]),
+            "missing directory after '--out-dir'"
+        );
+        assert_eq!(
+            error(&["--temp-dir"]),
+            "missing directory after '--temp-dir'"
+        );
+    }
+
+    #[test]
+    fn target_takes_a_triple() {
+        let parsed = settings(&["--target", "wasm32-wasi"]);
+        assert_eq!(parsed.target.as_deref(), Some("wasm32-wasi"));
+
+        assert_eq!(error(&["--target"]), "missing target after '--target'");
+    }
+
+    #[test]
+    fn release_is_level_three() {
+        assert_eq!(settings(&[]).opt_level, 0);
+        assert_eq!(settings(&["--release"]).opt_level, 3);
+        assert_eq!(settings(&["--release", "-O1"]).opt_level, 1);
+    }
+
+    #[test]
+    fn debug_flag_turns_on_debug_info() {
+        assert!(!settings(&[]).debug);
+        assert!(settings(&["-g"]).debug);
+    }
+
+    #[test]
+    fn no_format_skips_rustfmt() {
+        assert!(settings(&[]).pretty);
+        assert!(!settings(&["--no-format"]).pretty);
+    }
+
+    #[test]
+    fn intermediate_flags_toggle_keeping_the_rust() {
+        assert!(!settings(&[]).keep);
+        assert!(settings(&["--keep-intermediate"]).keep);
+        assert!(!settings(&["--keep-intermediate", "--no-intermediate"]).keep);
+    }
+
+    #[test]
+    fn toolchain_can_be_chosen() {
+        let (parsed, args, _) = parse(
+            ["+nightly", "run.blz", "--rustc", "/opt/rustc"]
+                .iter()
+                .map(|arg| arg.to_string()),
+        )
+        .unwrap();
+
+        assert_eq!(parsed.toolchain.as_deref(), Some("nightly"));
+        assert_eq!(parsed.rustc.as_deref(), Some("/opt/rustc"));
+        assert_eq!(args, vec!["run.blz"]);
+
+        assert_eq!(error(&["--rustc"]), "missing path after '--rustc'");
+    }
+}
@@ -0,0 +1,542 @@
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Code {
+    UnexpectedCharacter,
+    UnterminatedString,
+    InvalidSyntax,
+    UndefinedName,
+    InvalidAssignment,
+    CapturedLocal,
+    MismatchedTypes,
+    UndefinedItem,
+    WrongCount,
+    RecursiveAlias,
+    MissingReturn,
+    UnsupportedConstruct,
+    UnreachableStatement,
+    UnterminatedComment,
+    DeprecatedItem,
+    OutOfRange,
+    UnknownType,
+    JumpOutsideLoop,
+    NonExhaustiveMatch,
+    ReservedName,
+    MissingToken,
+    MissingName,
+    TooDeep,
+    InvalidAnnotation,
+    InvalidLiteral,
+    UnusedItem,
+    UnhashableKey,
+    RecursiveType,
+    DuplicateName,
+    CyclicInitializer,
+}
+
+// Every code with its name and the message its errors are built from, where
+// each `{}` is filled in with the names and types involved. Some checks word
+// their message differently, but they all describe the same problem.
+pub const CODES: &[(Code, &str, &str)] = &[
+    (Code::UnexpectedCharacter, "B0001", "Unexpected character."),
+    (Code::UnterminatedString, "B0002", "Unterminated string."),
+    (Code::InvalidSyntax, "B0003", "Expect {}."),
+    (Code::UndefinedName, "B0004", "Undefined {} '{}'."),
+    (Code::InvalidAssignment, "B0005", "Can't assign to {} '{}'."),
+    (
+        Code::CapturedLocal,
+        "B0006",
+        "Can't use a local variable in a nested function.",
+    ),
+    (
+        Code::MismatchedTypes,
+        "B0007",
+        "Expect type '{}' but found '{}'.",
+    ),
+    (Code::UndefinedItem, "B0008", "Undefined {} '{}' on '{}'."),
+    (Code::WrongCount, "B0009", "Expect {} arguments but got {}."),
+    (
+        Code::RecursiveAlias,
+        "B0010",
+        "Type alias '{}' refers to itself.",
+    ),
+    (
+        Code::MissingReturn,
+        "B0011",
+        "Function '{}' doesn't return a value on every path.",
+    ),
+    (Code::UnsupportedConstruct, "B0012", "Can't {}."),
+    (
+        Code::UnreachableStatement,
+        "B0013",
+        "Unreachable code after '{}'.",
+    ),
+    (
+        Code::UnterminatedComment,
+        "B0014",
+        "Unterminated block comment.",
+    ),
+    (Code::DeprecatedItem, "B0015", "{} '{}' is deprecated."),
+    (
+        Code::OutOfRange,
+        "B0016",
+        "Literal is out of range for '{}'.",
+    ),
+    (Code::UnknownType, "B0017", "Can't infer the type of '{}'."),
+    (
+        Code::JumpOutsideLoop,
+        "B0018",
+        "Can't use '{}' outside of a loop.",
+    ),
+    (
+        Code::NonExhaustiveMatch,
+        "B0019",
+        "Match doesn't cover every value.",
+    ),
+    (Code::ReservedName, "B0020", "Name '{}' is reserved."),
+    (Code::MissingToken, "B0021", "Expect '{}' {}."),
+    (Code::MissingName, "B0022", "Expect {} name."),
+    (Code::TooDeep, "B0023", "Too much nesting."),
+    (Code::InvalidAnnotation, "B0024", "Unknown annotation."),
+    (Code::InvalidLiteral, "B0025", "Invalid {} literal."),
+    (Code::UnusedItem, "B0026", "{} '{}' is never used."),
+    (Code::UnhashableKey, "B0027", "Can't use '{}' as a key."),
+    (Code::RecursiveType, "B0028", "Type '{}' contains itself."),
+    (Code::DuplicateName, "B0029", "{} '{}' is already declared."),
+    (
+        Code::CyclicInitializer,
+        "B0030",
+        "Initializer of '{}' depends on itself.",
+    ),
+];
+
+impl Code {
+    pub fn find(name: &str) -> Option<Self> {
+        CODES
+            .iter()
+            .find(|(_, code, _)| code.eq_ignore_ascii_case(name))
+            .map(|(code, _, _)| *code)
+    }
+
+    fn entry(self) -> &'static (Code, &'static str, &'static str) {
+        CODES
+            .iter()
+            .find(|(code, _, _)| *code == self)
+            .expect("every code is in the table")
+    }
+
+    pub fn name(self) -> &'static str {
+        self.entry().1
+    }
+
+    pub fn template(self) -> &'static str {
+        self.entry().2
+    }
+
+    pub fn message(self, args: &[&dyn fmt::Display]) -> String {
+        let mut pieces = self.template().split("{}");
+        let mut message = pieces.next().unwrap_or_default().to_string();
+
+        for (index, piece) in pieces.enumerate() {
+            if let Some(arg) = args.get(index) {
+                message.push_str(&arg.to_string());
+            }
+
+            message.push_str(piece);
+        }
+
+        message
+    }
+
+    pub fn explanation(self) -> &'static str {
+        match self {
+            Self::UnexpectedCharacter => {
+                r#"A character that isn't part of any token was found.
+
+Names can only use ASCII letters, digits, and underscores, and characters like
+`$` or `#` have no meaning outside of strings and comments.
+
+    let price$ = 10
+
+Rename the variable to something like `price_in_dollars`."#
+            }
+            Self::UnterminatedString => {
+                r#"A string was opened with `"` but never closed.
+
+    print("hello)
+
+Add the closing quote, like `print("hello")`."#
+            }
+            Self::InvalidSyntax => {
+                r#"The code doesn't match the grammar of Blaze.
+
+The message says what the parser expected where it stopped, like an
+expression after an operator or a block after `if`.
+
+    fn main() {
+        print(1 + )
+    }
+
+Finish the expression, like `print(1 + 2)`."#
+            }
+            Self::UndefinedName => {
+                r#"A variable or function was used but never declared.
+
+    fn main() {
+        print(count)
+    }
+
+Declare it before using it, like `let count = 0`, or check the spelling."#
+            }
+            Self::InvalidAssignment => {
+                r#"Something that can't be reassigned was assigned to.
+
+Constants and functions keep the value they were declared with.
+
+    const LIMIT: i32 = 10
+
+    fn main() {
+        LIMIT = 20
+    }
+
+Use `let` instead of `const` if the value needs to change."#
+            }
+            Self::CapturedLocal => {
+                r#"A nested function used a local variable of the function around it.
+
+Nested functions can't capture variables, so they only see globals, their
+parameters, and their own locals.
+
+    fn main() {
+        let step = 2
+
+        fn next(n: i32): i32 {
+            return n + step
+        }
+    }
+
+Pass the value in as a parameter instead, like `fn next(n: i32, step: i32)`."#
+            }
+            Self::MismatchedTypes => {
+                r#"A value has a different type than the place it's used in.
+
+    fn main() {
+        let count: i32 = 1.5
+    }
+
+Numbers aren't converted automatically, so use a value of the right type or
+convert it with `as`, like `1.5 as i32`."#
+            }
+            Self::UndefinedItem => {
+                r#"A type, field, method, variant, or path refers to something that
+doesn't exist.
+
+    struct Point {
+        x: i32
+        y: i32
+    }
+
+    fn main() {
+        let p = Point { x: 1, y: 2 }
+        print(p.z)
+    }
+
+Check the spelling against the declaration of the type."#
+            }
+            Self::WrongCount => {
+                r#"A call or pattern has the wrong number of parts, or a struct is
+missing a field.
+
+    fn add(a: i32, b: i32): i32 {
+        return a + b
+    }
+
+    fn main() {
+        print(add(1))
+    }
+
+Pass one argument for each parameter, like `add(1, 2)`."#
+            }
+            Self::RecursiveAlias => {
+                r#"A type alias refers to itself, so it never names a real type.
+
+    type List = [List]
+
+Give the alias a type that doesn't depend on it, or use a struct instead."#
+            }
+            Self::MissingReturn => {
+                r#"A function with a return type can finish without returning a value.
+
+    fn sign(n: i32): i32 {
+        if n < 0 {
+            return -1
+        }
+    }
+
+Make sure every path ends with `return` or `raise`, like adding `return 1`
+after the `if`."#
+            }
+            Self::UnsupportedConstruct => {
+                r#"The code is valid Blaze, but it can't be turned into Rust where it
+appears.
+
+This covers things like casting to a type other than `i32` or `f64`, using
+`raise` outside of a fallible function, or declaring a global without a type
+and an initializer.
+
+    fn check(n: i32): i32 {
+        if n < 0 {
+            raise "negative"
+        }
+
+        return n
+    }
+
+Give the function a fallible return type, like `fn check(n: i32): i32 ! str`,
+or handle the problem another way."#
+            }
+            Self::UnreachableStatement => {
+                r#"A statement comes after `return`, `raise`, `break`, `continue`, or a
+`loop` that never ends, so it can never run.
+
+    fn main() {
+        return
+        print("never printed")
+    }
+
+Remove the statement or move it before the jump. This is a warning, and it
+can be allowed with `-A unreachable`."#
+            }
+            Self::UnterminatedComment => {
+                r#"A block comment was opened with `/*` but never closed.
+
+Block comments nest, so every `/*` inside one needs its own `*/`.
+
+    /* outer /* inner */
+    fn main() {}
+
+Add the missing `*/`, like `/* outer /* inner */ */`."#
+            }
+            Self::DeprecatedItem => {
+                r#"A function, struct, enum, or type alias marked `@deprecated` is
+used.
+
+    @deprecated("use total instead")
+    fn sum(a: i32, b: i32): i32 {
+        return a + b
+    }
+
+    fn main() {
+        print(sum(1, 2))
+    }
+
+Use the replacement named in the note. This is a warning, and it can be
+allowed with `-A deprecated`."#
+            }
+            Self::OutOfRange => {
+                r#"A number doesn't fit in its type, either because the literal is too
+big or because arithmetic on constants overflows or divides by zero.
+
+    fn main() {
+        print(2147483647 + 1)
+    }
+
+An `i32` holds values from `-2147483648` to `2147483647`. Use an `f64` for
+bigger numbers, or `wrapping_add` and friends when wrapping is intended."#
+            }
+            Self::UnknownType => {
+                r#"The type of a variable can't be worked out from its initializer.
+
+    fn main() {
+        let names = []
+    }
+
+An empty array or map doesn't say what it holds. Give the variable a type,
+like `let names: [str] = []`."#
+            }
+            Self::JumpOutsideLoop => {
+                r#"A `break` or `continue` isn't inside a `loop` or `for`.
+
+    fn main() {
+        break
+    }
+
+A loop inside another function doesn't count, since `break` can only leave
+the function it's written in. Use `return` to leave a function early."#
+            }
+            Self::NonExhaustiveMatch => {
+                r#"The arms of a `match` don't cover every possible value.
+
+    fn name(n: i32): str {
+        match n {
+            0 => return "zero"
+            1 => return "one"
+        }
+    }
+
+Numbers and strings have too many values to list, so add a `_` arm, or one
+that binds the value to a name, to handle the rest. For a `bool` or an enum,
+the note names the cases that no arm matches, like `Shape::Empty`."#
+            }
+            Self::ReservedName => {
+                r#"A name starts with two underscores.
+
+Names like `__value` are reserved for the code Blaze generates, so using one
+could clash with it.
+
+    fn main() {
+        let __count = 0
+    }
+
+Pick a name that doesn't start with `__`, like `count`."#
+            }
+            Self::MissingToken => {
+                r#"A piece of punctuation or a keyword is missing.
+
+The message names the token the parser expected, like a closing `)` or the
+`=>` between a pattern and its arm.
+
+    fn main() {
+        print(1 + 2
+    }
+
+Add the missing token, like `print(1 + 2)`."#
+            }
+            Self::MissingName => {
+                r#"A declaration or field access is missing its name.
+
+Things like `fn`, `struct` and `let` have to be followed by the name they
+declare, and a `.` by the name of a field or method.
+
+    fn (a: i32): i32 {
+        return a
+    }
+
+Give it a name, like `fn double(a: i32): i32`."#
+            }
+            Self::TooDeep => {
+                r#"Expressions or blocks are nested too deeply.
+
+The parser stops after 128 levels of blocks, parentheses, and other nesting,
+or after 1024 operators, calls, or indexes chained together. Either usually
+comes from generated code.
+
+    print(((((((((((((((((((((((((((((((((1)))))))))))))))))))))))))))))))))
+
+Split the expression up with variables in between."#
+            }
+            Self::InvalidAnnotation => {
+                r#"An annotation is unknown or doesn't fit where it's used.
+
+Only `@deprecated` exists, and it can go on functions, structs, enums,
+variants, type aliases, and constants, but not on variables or impls.
+
+    @deprecated
+    let count = 0
+
+Remove the annotation, or move it to a declaration that takes one."#
+            }
+            Self::InvalidLiteral => {
+                r#"A number literal can't be read.
+
+Suffixes like `i` or `u8` only go on whole numbers, and the digits have to fit
+the form the literal uses.
+
+    let half = 0.5i
+
+Drop the suffix on a float, like `let half = 0.5`."#
+            }
+            Self::UnusedItem => {
+                r#"A variable, constant, or function is declared but never used.
+
+This is a warning from the `unused` lint, and it usually means a name was
+misspelled or some code was left behind.
+
+    fn main() {
+        let total = 0
+        print("done")
+    }
+
+Use the value or remove it. Names starting with `_`, like `_total`, are never
+reported, and `-A unused` turns the lint off."#
+            }
+            Self::UnhashableKey => {
+                r#"A map key or set element has a type that can't be hashed.
+
+Keys can be `i32`, `bool`, `str`, or arrays and optionals of those. An `f64`
+has no exact equality, and structs, enums, functions, maps, and sets can't be
+keys either.
+
+    fn main() {
+        let prices: map[f64, str] = {}
+    }
+
+Use a key that can be compared exactly, like an `i32` count of cents."#
+            }
+            Self::RecursiveType => {
+                r#"A struct or enum holds a value of its own type, so it would be
+infinitely big.
+
+This also covers two types that hold each other, and holding one through an
+optional, since an optional still keeps its value inline.
+
+    struct Node {
+        value: i32
+        next: Node?
+    }
+
+Keep the values in an array instead, like `next: [Node]`, which can be empty
+to end the chain."#
+            }
+            Self::DuplicateName => {
+                r#"Two declarations in the same place have the same name.
+
+This covers functions in the same scope, globals and constants, parameters of
+one function, fields of a struct, variants of an enum, and methods of a type,
+even when they're in different `impl` blocks. Variables inside a function can
+still shadow each other.
+
+    fn area(width: i32, width: i32): i32 {
+        return width * width
+    }
+
+Rename one of them, like `fn area(width: i32, height: i32)`."#
+            }
+            Self::CyclicInitializer => {
+                r#"A global or constant needs its own value to be initialized.
+
+Globals and constants are set up the first time they're read, so an
+initializer that reads itself, directly or through other globals and the
+functions it calls, could never finish.
+
+    let a: i32 = b + 1
+    let b: i32 = a * 2
+
+Give one of them a value that doesn't depend on the other, like
+`let a: i32 = 1`."#
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_unique() {
+        for (index, (code, name, _)) in CODES.iter().enumerate() {
+            assert_eq!(Code::find(name), Some(*code));
+            assert!(CODES[..index].iter().all(|(_, other, _)| other != name));
+        }
+    }
+
+    #[test]
+    fn fills_in_the_template() {
+        assert_eq!(
+            Code::MismatchedTypes.message(&[&"i32", &"str"]),
+            "Expect type 'i32' but found 'str'."
+        );
+        assert_eq!(Code::TooDeep.message(&[]), "Too much nesting.");
+    }
+}
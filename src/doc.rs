@@ -0,0 +1,370 @@
+use blaze::stmt::{self, Stmt};
+use blaze::variant::{self, Variant};
+
+struct Item {
+    name: String,
+    signature: String,
+    doc: Option<String>,
+    deprecated: Option<stmt::Deprecated>,
+}
+
+struct Signature;
+
+impl Signature {
+    fn variant(&mut self, variant: &Variant) -> String {
+        variant.accept(self)
+    }
+
+    fn function(&mut self, function: &stmt::Function) -> String {
+        let mut parameters = Vec::new();
+
+        if function.receiver {
+            parameters.push("self".to_string());
+        }
+
+        for (name, variant) in function.parameters.iter() {
+            parameters.push(format!(
+                "{}: {}",
+                name.lexeme,
+                self.variant(variant)
+            ));
+        }
+
+        match &function.output {
+            Some(output) => format!(
+                "fn {}({}): {}",
+                function.name.lexeme,
+                parameters.join(", "),
+                self.variant(output)
+            ),
+            None => {
+                format!(
+                    "fn {}({})",
+                    function.name.lexeme,
+                    parameters.join(", ")
+                )
+            }
+        }
+    }
+
+    fn body(&mut self, lines: &[String]) -> String {
+        lines
+            .iter()
+            .map(|line| format!("    {},\n", line))
+            .collect()
+    }
+}
+
+impl variant::Visitor for Signature {
+    type Result = String;
+
+    fn visit_literal_variant(
+        &mut self,
+        variant: &variant::Literal,
+    ) -> Self::Result {
+        variant.name.lexeme.clone()
+    }
+
+    fn visit_function_variant(
+        &mut self,
+        variant: &variant::Function,
+    ) -> Self::Result {
+        let parameters: Vec<String> = variant
+            .parameters
+            .iter()
+            .map(|parameter| self.variant(parameter))
+            .collect();
+
+        match &variant.output {
+            Some(output) => {
+                format!(
+                    "fn({}): {}",
+                    parameters.join(", "),
+                    self.variant(output)
+                )
+            }
+            None => format!("fn({})", parameters.join(", ")),
+        }
+    }
+
+    fn visit_array_variant(
+        &mut self,
+        variant: &variant::Array,
+    ) -> Self::Result {
+        format!("[{}]", self.variant(&variant.element))
+    }
+
+    fn visit_map_variant(&mut self, variant: &variant::Map) -> Self::Result {
+        format!(
+            "map[{}, {}]",
+            self.variant(&variant.key),
+            self.variant(&variant.value)
+        )
+    }
+
+    fn visit_set_variant(&mut self, variant: &variant::Set) -> Self::Result {
+        format!("set[{}]", self.variant(&variant.element))
+    }
+
+    fn visit_optional_variant(
+        &mut self,
+        variant: &variant::Optional,
+    ) -> Self::Result {
+        format!("{}?", self.variant(&variant.inner))
+    }
+
+    fn visit_fallible_variant(
+        &mut self,
+        variant: &variant::Fallible,
+    ) -> Self::Result {
+        format!(
+            "{} ! {}",
+            self.variant(&variant.value),
+            self.variant(&variant.error)
+        )
+    }
+}
+
+fn items(statements: &[Stmt]) -> Vec<Item> {
+    let mut signature = Signature;
+    let mut items = Vec::new();
+
+    for statement in statements.iter() {
+        match statement {
+            Stmt::Function(function) => items.push(Item {
+                name: function.name.lexeme.clone(),
+                signature: signature.function(function),
+                doc: function.doc.clone(),
+                deprecated: function.deprecated.clone(),
+            }),
+            Stmt::Const(constant) => items.push(Item {
+                name: constant.name.lexeme.clone(),
+                signature: format!(
+                    "const {}: {}",
+                    constant.name.lexeme,
+                    signature.variant(&constant.variant)
+                ),
+                doc: constant.doc.clone(),
+                deprecated: constant.deprecated.clone(),
+            }),
+            Stmt::Type(alias) => items.push(Item {
+                name: alias.name.lexeme.clone(),
+                signature: format!(
+                    "type {} = {}",
+                    alias.name.lexeme,
+                    signature.variant(&alias.variant)
+                ),
+                doc: alias.doc.clone(),
+                deprecated: alias.deprecated.clone(),
+            }),
+            Stmt::Struct(structure) => {
+                let fields: Vec<String> = structure
+                    .fields
+                    .iter()
+                    .map(|(name, variant)| {
+                        format!(
+                            "{}: {}",
+                            name.lexeme,
+                            signature.variant(variant)
+                        )
+                    })
+                    .collect();
+
+                items.push(Item {
+                    name: structure.name.lexeme.clone(),
+                    signature: format!(
+                        "struct {} {{\n{}}}",
+                        structure.name.lexeme,
+                        signature.body(&fields)
+                    ),
+                    doc: structure.doc.clone(),
+                    deprecated: structure.deprecated.clone(),
+                })
+            }
+            Stmt::Enum(enumeration) => {
+                let variants: Vec<String> = enumeration
+                    .variants
+                    .iter()
+                    .map(|(name, fields)| {
+                        if fields.is_empty() {
+                            return name.lexeme.clone();
+                        }
+
+                        let fields: Vec<String> = fields
+                            .iter()
+                            .map(|field| signature.variant(field))
+                            .collect();
+
+                        format!("{}({})", name.lexeme, fields.join(", "))
+                    })
+                    .collect();
+
+                items.push(Item {
+                    name: enumeration.name.lexeme.clone(),
+                    signature: format!(
+                        "enum {} {{\n{}}}",
+                        enumeration.name.lexeme,
+                        signature.body(&variants)
+                    ),
+                    doc: enumeration.doc.clone(),
+                    deprecated: enumeration.deprecated.clone(),
+                })
+            }
+            Stmt::Impl(implementation) => {
+                for method in implementation.methods.iter() {
+                    if let Stmt::Function(function) = method {
+                        items.push(Item {
+                            name: format!(
+                                "{}.{}",
+                                implementation.name.lexeme,
+                                function.name.lexeme
+                            ),
+                            signature: signature.function(function),
+                            doc: function.doc.clone(),
+                            deprecated: function.deprecated.clone(),
+                        })
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    items
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn markdown(title: &str, statements: &[Stmt]) -> String {
+    let mut output = format!("# {}\n", title);
+
+    for item in items(statements) {
+        output.push_str(&format!(
+            "\n## {}\n\n```\n{}\n```\n",
+            item.name, item.signature
+        ));
+
+        if let Some(deprecated) = &item.deprecated {
+            match &deprecated.note {
+                Some(note) => {
+                    output.push_str(&format!("\n**Deprecated:** {}\n", note))
+                }
+                None => output.push_str("\n**Deprecated.**\n"),
+            }
+        }
+
+        if let Some(doc) = &item.doc {
+            output.push_str(&format!("\n{}\n", doc));
+        }
+    }
+
+    output
+}
+
+pub fn html(title: &str, statements: &[Stmt]) -> String {
+    let mut output = format!(
+        concat!(
+            "<!DOCTYPE html>\n",
+            "<html>\n",
+            "<head>\n",
+            "<meta charset=\"utf-8\">\n",
+            "<title>{0}</title>\n",
+            "</head>\n",
+            "<body>\n",
+            "<h1>{0}</h1>\n",
+        ),
+        escape(title)
+    );
+
+    for item in items(statements) {
+        output.push_str(&format!(
+            "<h2 id=\"{0}\">{0}</h2>\n<pre><code>{1}</code></pre>\n",
+            escape(&item.name),
+            escape(&item.signature)
+        ));
+
+        if let Some(deprecated) = &item.deprecated {
+            match &deprecated.note {
+                Some(note) => output.push_str(&format!(
+                    "<p><strong>Deprecated:</strong> {}</p>\n",
+                    escape(note)
+                )),
+                None => {
+                    output.push_str("<p><strong>Deprecated.</strong></p>\n")
+                }
+            }
+        }
+
+        if let Some(doc) = &item.doc {
+            for paragraph in doc.split("\n\n") {
+                if !paragraph.trim().is_empty() {
+                    output.push_str(&format!("<p>{}</p>\n", escape(paragraph)));
+                }
+            }
+        }
+    }
+
+    output.push_str("</body>\n</html>\n");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blaze::{Parser, Scanner, Semicolons};
+
+    fn parse(source: &str) -> Vec<Stmt> {
+        let (tokens, _) = Scanner::new(source, Semicolons::Automatic).scan();
+        let (statements, errors) = Parser::new(tokens).parse();
+        assert!(errors.is_empty());
+
+        statements
+    }
+
+    const SOURCE: &str = r#"
+/// Adds two numbers.
+fn add(a: i32, b: i32): i32 {
+    return a + b
+}
+
+@deprecated("use add")
+fn plus(a: i32): i32 {
+    return a
+}
+
+/// A point on a <grid>.
+struct Point {
+    x: f64
+}
+"#;
+
+    #[test]
+    fn markdown_lists_each_item() {
+        assert_eq!(
+            markdown("shapes", &parse(SOURCE)),
+            concat!(
+                "# shapes\n",
+                "\n## add\n\n```\nfn add(a: i32, b: i32): i32\n```\n",
+                "\nAdds two numbers.\n",
+                "\n## plus\n\n```\nfn plus(a: i32): i32\n```\n",
+                "\n**Deprecated:** use add\n",
+                "\n## Point\n\n```\nstruct Point {\n    x: f64,\n}\n```\n",
+                "\nA point on a <grid>.\n",
+            )
+        );
+    }
+
+    #[test]
+    fn html_escapes_docs() {
+        let output = html("shapes", &parse(SOURCE));
+
+        assert!(output.contains("<h2 id=\"Point\">Point</h2>\n"));
+        assert!(output.contains("<p>A point on a &lt;grid&gt;.</p>\n"));
+        assert!(output.contains("<p><strong>Deprecated:</strong> use add</p>"));
+    }
+}
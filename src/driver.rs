@@ -0,0 +1,145 @@
+use crate::checker::Checker;
+use crate::error::{Diagnostic, Level};
+use crate::flow::Flow;
+use crate::generator::Generator;
+use crate::optimizer::Optimizer;
+use crate::parser::Parser;
+use crate::report::{Format, Reporter};
+use crate::resolver::{Definition, Resolver};
+use crate::scanner::{Scanner, Semicolons};
+use crate::stmt::Stmt;
+use crate::token::Token;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stage {
+    Scan,
+    Parse,
+    Resolve,
+    Check,
+    Generate,
+}
+
+pub enum Output {
+    Tokens(Vec<Token>),
+    Ast(Vec<Stmt>),
+    Symbols(Vec<Definition>),
+    Checked,
+    Rust(String),
+}
+
+pub struct Options {
+    pub semicolons: Semicolons,
+    pub optimize: bool,
+    pub fail_fast: bool,
+    pub lints: Vec<(String, Level)>,
+    pub format: Format,
+    pub max_errors: Option<usize>,
+}
+
+impl Options {
+    pub fn new() -> Self {
+        Self {
+            semicolons: Semicolons::Automatic,
+            optimize: false,
+            fail_fast: true,
+            lints: Vec::new(),
+            format: Format::Human,
+            max_errors: None,
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn compile(
+    reporter: &Reporter,
+    source: &str,
+    options: &Options,
+    stage: Stage,
+) -> Option<Output> {
+    let mut scanner = Scanner::new(source, options.semicolons);
+    let (tokens, errors) = scanner.scan();
+    let mut failed = reporter.report(errors);
+
+    if failed && (options.fail_fast || stage == Stage::Scan) {
+        return None;
+    }
+
+    if stage == Stage::Scan {
+        return Some(Output::Tokens(tokens));
+    }
+
+    let mut parser = Parser::new(tokens);
+    let (statements, errors) = parser.parse();
+
+    if reporter.report(errors) {
+        return None;
+    }
+
+    if stage == Stage::Parse {
+        return Some(Output::Ast(statements));
+    }
+
+    let mut resolver = Resolver::new();
+    let (warnings, errors) = resolver.resolve(&statements);
+
+    let mut diagnostics: Vec<Diagnostic> =
+        warnings.into_iter().map(Diagnostic::from).collect();
+    diagnostics.extend(errors.into_iter().map(Diagnostic::from));
+
+    failed |= reporter.report(diagnostics);
+
+    if failed && (options.fail_fast || stage == Stage::Resolve) {
+        return None;
+    }
+
+    if stage == Stage::Resolve {
+        return Some(Output::Symbols(resolver.symbols()));
+    }
+
+    let mut checker = Checker::new();
+    let (program, errors) = checker.check(&statements);
+    failed |= reporter.report(errors);
+
+    if failed && options.fail_fast {
+        return None;
+    }
+
+    let mut flow = Flow::new();
+    let (warnings, errors) = flow.analyze(&statements);
+
+    let mut diagnostics: Vec<Diagnostic> =
+        warnings.into_iter().map(Diagnostic::from).collect();
+    diagnostics.extend(errors.into_iter().map(Diagnostic::from));
+
+    failed |= reporter.report(diagnostics);
+
+    if failed {
+        return None;
+    }
+
+    if stage == Stage::Check {
+        return Some(Output::Checked);
+    }
+
+    let optimized;
+    let program = if options.optimize {
+        optimized = Optimizer::new().optimize(&statements);
+        Checker::new().check(&optimized).0
+    } else {
+        program
+    };
+
+    let mut generator = Generator::new();
+    let (output, errors) = generator.generate(&program);
+
+    if reporter.report(errors) {
+        None
+    } else {
+        Some(Output::Rust(output))
+    }
+}
@@ -1,11 +1,17 @@
 use std::error::Error;
 use std::fmt;
 
+use crate::code::Code;
+use crate::token::Span;
+
 #[derive(Debug)]
 pub struct SyntaxError {
     pub line: usize,
+    pub span: Span,
+    pub code: Code,
     pub location: String,
     pub message: String,
+    pub note: Option<String>,
 }
 
 impl Error for SyntaxError {}
@@ -23,6 +29,8 @@ impl fmt::Display for SyntaxError {
 #[derive(Debug)]
 pub struct GenerateError {
     pub line: usize,
+    pub span: Span,
+    pub code: Code,
     pub message: String,
 }
 
@@ -33,3 +41,207 @@ impl fmt::Display for GenerateError {
         write!(formatter, "[line {}] Error: {}", self.line, self.message)
     }
 }
+
+#[derive(Debug)]
+pub struct TypeError {
+    pub line: usize,
+    pub span: Span,
+    pub code: Code,
+    pub message: String,
+    pub note: Option<String>,
+}
+
+impl Error for TypeError {}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "[line {}] Error: {}", self.line, self.message)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ResolveError {
+    pub line: usize,
+    pub span: Span,
+    pub code: Code,
+    pub message: String,
+    pub note: Option<String>,
+}
+
+impl Error for ResolveError {}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "[line {}] Error: {}", self.line, self.message)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct FlowError {
+    pub line: usize,
+    pub span: Span,
+    pub code: Code,
+    pub message: String,
+}
+
+impl Error for FlowError {}
+
+impl fmt::Display for FlowError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "[line {}] Error: {}", self.line, self.message)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Warning {
+    pub line: usize,
+    pub span: Span,
+    pub code: Code,
+    pub lint: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "[line {}] Warning: {}", self.line, self.message)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub line: usize,
+    pub span: Option<Span>,
+    pub code: Option<Code>,
+    pub location: String,
+    pub message: String,
+    pub note: Option<String>,
+    pub lint: Option<&'static str>,
+}
+
+impl Diagnostic {
+    fn error(line: usize, code: Code, message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            line,
+            span: None,
+            code: Some(code),
+            location: String::new(),
+            message,
+            note: None,
+            lint: None,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "Error",
+            Severity::Warning => "Warning",
+            Severity::Note => "Note",
+        };
+
+        let code = match self.code {
+            Some(code) => format!("[{}]", code.name()),
+            None => String::new(),
+        };
+
+        match self.span {
+            Some(span) => write!(
+                formatter,
+                "[line {}, column {}] {}{}{}: {}",
+                self.line,
+                span.column,
+                severity,
+                code,
+                self.location,
+                self.message
+            ),
+            None if self.line == 0 => write!(
+                formatter,
+                "{}{}{}: {}",
+                severity, code, self.location, self.message
+            ),
+            None => write!(
+                formatter,
+                "[line {}] {}{}{}: {}",
+                self.line, severity, code, self.location, self.message
+            ),
+        }
+    }
+}
+
+impl From<SyntaxError> for Diagnostic {
+    fn from(error: SyntaxError) -> Self {
+        Self {
+            span: Some(error.span),
+            location: error.location,
+            note: error.note,
+            ..Self::error(error.line, error.code, error.message)
+        }
+    }
+}
+
+impl From<GenerateError> for Diagnostic {
+    fn from(error: GenerateError) -> Self {
+        Self {
+            span: Some(error.span),
+            ..Self::error(error.line, error.code, error.message)
+        }
+    }
+}
+
+impl From<TypeError> for Diagnostic {
+    fn from(error: TypeError) -> Self {
+        Self {
+            span: Some(error.span),
+            note: error.note,
+            ..Self::error(error.line, error.code, error.message)
+        }
+    }
+}
+
+impl From<ResolveError> for Diagnostic {
+    fn from(error: ResolveError) -> Self {
+        Self {
+            span: Some(error.span),
+            note: error.note,
+            ..Self::error(error.line, error.code, error.message)
+        }
+    }
+}
+
+impl From<FlowError> for Diagnostic {
+    fn from(error: FlowError) -> Self {
+        Self {
+            span: Some(error.span),
+            ..Self::error(error.line, error.code, error.message)
+        }
+    }
+}
+
+impl From<Warning> for Diagnostic {
+    fn from(warning: Warning) -> Self {
+        Self {
+            severity: Severity::Warning,
+            span: Some(warning.span),
+            lint: Some(warning.lint),
+            ..Self::error(warning.line, warning.code, warning.message)
+        }
+    }
+}
@@ -1,6 +1,14 @@
+use crate::stmt::Stmt;
 use crate::token::Token;
 use crate::value::Value;
 
+#[derive(Clone, Debug)]
+pub struct Conditional {
+    pub condition: Expr,
+    pub then_branch: Expr,
+    pub else_branch: Expr,
+}
+
 #[derive(Clone, Debug)]
 pub struct Logical {
     pub left: Expr,
@@ -15,6 +23,17 @@ pub struct Binary {
     pub right: Expr,
 }
 
+#[derive(Clone, Debug)]
+pub struct Loop {
+    pub body: Stmt,
+}
+
+#[derive(Clone, Debug)]
+pub struct Cast {
+    pub value: Expr,
+    pub target: Token,
+}
+
 #[derive(Clone, Debug)]
 pub struct Unary {
     pub operator: Token,
@@ -27,11 +46,65 @@ pub struct Call {
     pub arguments: Vec<Expr>,
 }
 
+#[derive(Clone, Debug)]
+pub struct Get {
+    pub object: Expr,
+    pub name: Token,
+}
+
+#[derive(Clone, Debug)]
+pub struct Set {
+    pub object: Expr,
+    pub name: Token,
+    pub value: Expr,
+}
+
+#[derive(Clone, Debug)]
+pub struct Index {
+    pub object: Expr,
+    pub index: Expr,
+}
+
+#[derive(Clone, Debug)]
+pub struct SetIndex {
+    pub object: Expr,
+    pub index: Expr,
+    pub value: Expr,
+}
+
 #[derive(Clone, Debug)]
 pub struct Grouping {
     pub expression: Expr,
 }
 
+#[derive(Clone, Debug)]
+pub struct Array {
+    pub elements: Vec<Expr>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Map {
+    pub entries: Vec<(Expr, Expr)>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SetLiteral {
+    pub elements: Vec<Expr>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Struct {
+    pub name: Token,
+    pub fields: Vec<(Token, Expr)>,
+    pub base: Option<Expr>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Path {
+    pub namespace: Token,
+    pub name: Token,
+}
+
 #[derive(Clone, Debug)]
 pub struct Variable {
     pub name: Token,
@@ -44,16 +117,40 @@ pub struct Literal {
 
 #[derive(Clone, Debug)]
 pub enum Expr {
+    Conditional(Box<Conditional>),
     Logical(Box<Logical>),
     Binary(Box<Binary>),
+    Loop(Box<Loop>),
+    Cast(Box<Cast>),
     Unary(Box<Unary>),
     Call(Box<Call>),
+    Get(Box<Get>),
+    Set(Box<Set>),
+    Index(Box<Index>),
+    SetIndex(Box<SetIndex>),
     Grouping(Box<Grouping>),
+    Array(Box<Array>),
+    Map(Box<Map>),
+    SetLiteral(Box<SetLiteral>),
+    Struct(Box<Struct>),
+    Path(Box<Path>),
     Variable(Box<Variable>),
     Literal(Box<Literal>),
 }
 
 impl Expr {
+    pub fn new_conditional(
+        condition: Expr,
+        then_branch: Expr,
+        else_branch: Expr,
+    ) -> Self {
+        Self::Conditional(Box::new(Conditional {
+            condition,
+            then_branch,
+            else_branch,
+        }))
+    }
+
     pub fn new_logical(left: Expr, operator: Token, right: Expr) -> Self {
         Self::Logical(Box::new(Logical {
             left,
@@ -70,6 +167,14 @@ impl Expr {
         }))
     }
 
+    pub fn new_loop(body: Stmt) -> Self {
+        Self::Loop(Box::new(Loop { body }))
+    }
+
+    pub fn new_cast(value: Expr, target: Token) -> Self {
+        Self::Cast(Box::new(Cast { value, target }))
+    }
+
     pub fn new_unary(operator: Token, right: Expr) -> Self {
         Self::Unary(Box::new(Unary { operator, right }))
     }
@@ -78,10 +183,58 @@ impl Expr {
         Self::Call(Box::new(Call { callee, arguments }))
     }
 
+    pub fn new_get(object: Expr, name: Token) -> Self {
+        Self::Get(Box::new(Get { object, name }))
+    }
+
+    pub fn new_set(object: Expr, name: Token, value: Expr) -> Self {
+        Self::Set(Box::new(Set {
+            object,
+            name,
+            value,
+        }))
+    }
+
+    pub fn new_index(object: Expr, index: Expr) -> Self {
+        Self::Index(Box::new(Index { object, index }))
+    }
+
+    pub fn new_set_index(object: Expr, index: Expr, value: Expr) -> Self {
+        Self::SetIndex(Box::new(SetIndex {
+            object,
+            index,
+            value,
+        }))
+    }
+
     pub fn new_grouping(expression: Expr) -> Self {
         Self::Grouping(Box::new(Grouping { expression }))
     }
 
+    pub fn new_array(elements: Vec<Expr>) -> Self {
+        Self::Array(Box::new(Array { elements }))
+    }
+
+    pub fn new_map(entries: Vec<(Expr, Expr)>) -> Self {
+        Self::Map(Box::new(Map { entries }))
+    }
+
+    pub fn new_set_literal(elements: Vec<Expr>) -> Self {
+        Self::SetLiteral(Box::new(SetLiteral { elements }))
+    }
+
+    pub fn new_struct(
+        name: Token,
+        fields: Vec<(Token, Expr)>,
+        base: Option<Expr>,
+    ) -> Self {
+        Self::Struct(Box::new(Struct { name, fields, base }))
+    }
+
+    pub fn new_path(namespace: Token, name: Token) -> Self {
+        Self::Path(Box::new(Path { namespace, name }))
+    }
+
     pub fn new_variable(name: Token) -> Self {
         Self::Variable(Box::new(Variable { name }))
     }
@@ -90,13 +243,83 @@ impl Expr {
         Self::Literal(Box::new(Literal { value }))
     }
 
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::Conditional(expr) => expr.condition.line(),
+            Self::Logical(expr) => Some(expr.operator.line),
+            Self::Binary(expr) => Some(expr.operator.line),
+            Self::Loop(expr) => expr.body.line(),
+            Self::Cast(expr) => Some(expr.target.line),
+            Self::Unary(expr) => Some(expr.operator.line),
+            Self::Call(expr) => expr.callee.line(),
+            Self::Get(expr) => Some(expr.name.line),
+            Self::Set(expr) => Some(expr.name.line),
+            Self::Index(expr) => expr.object.line(),
+            Self::SetIndex(expr) => expr.object.line(),
+            Self::Grouping(expr) => expr.expression.line(),
+            Self::Array(expr) => expr.elements.iter().find_map(Self::line),
+            Self::Map(expr) => {
+                expr.entries.iter().find_map(|(key, _)| key.line())
+            }
+            Self::SetLiteral(expr) => expr.elements.iter().find_map(Self::line),
+            Self::Struct(expr) => Some(expr.name.line),
+            Self::Path(expr) => Some(expr.name.line),
+            Self::Variable(expr) => Some(expr.name.line),
+            Self::Literal(_) => None,
+        }
+    }
+
+    pub fn token(&self) -> Option<&Token> {
+        match self {
+            Self::Conditional(expr) => expr.condition.token(),
+            Self::Logical(expr) => expr.left.token().or(Some(&expr.operator)),
+            Self::Binary(expr) => expr.left.token().or(Some(&expr.operator)),
+            Self::Loop(expr) => expr.body.token(),
+            Self::Cast(expr) => expr.value.token(),
+            Self::Unary(expr) => Some(&expr.operator),
+            Self::Call(expr) => expr.callee.token(),
+            Self::Get(expr) => expr.object.token(),
+            Self::Set(expr) => expr.object.token(),
+            Self::Index(expr) => expr.object.token(),
+            Self::SetIndex(expr) => expr.object.token(),
+            Self::Grouping(expr) => expr.expression.token(),
+            Self::Array(expr) => expr.elements.iter().find_map(Self::token),
+            Self::Map(expr) => {
+                expr.entries.iter().find_map(|(key, _)| key.token())
+            }
+            Self::SetLiteral(expr) => {
+                expr.elements.iter().find_map(Self::token)
+            }
+            Self::Struct(expr) => Some(&expr.name),
+            Self::Path(expr) => Some(&expr.namespace),
+            Self::Variable(expr) => Some(&expr.name),
+            Self::Literal(_) => None,
+        }
+    }
+
+    pub fn id(&self) -> usize {
+        self as *const Self as usize
+    }
+
     pub fn accept<V: Visitor>(&self, visitor: &mut V) -> V::Result {
         match self {
+            Self::Conditional(expr) => visitor.visit_conditional_expr(expr),
             Self::Logical(expr) => visitor.visit_logical_expr(expr),
             Self::Binary(expr) => visitor.visit_binary_expr(expr),
+            Self::Loop(expr) => visitor.visit_loop_expr(expr),
+            Self::Cast(expr) => visitor.visit_cast_expr(expr),
             Self::Unary(expr) => visitor.visit_unary_expr(expr),
             Self::Call(expr) => visitor.visit_call_expr(expr),
+            Self::Get(expr) => visitor.visit_get_expr(expr),
+            Self::Set(expr) => visitor.visit_set_expr(expr),
+            Self::Index(expr) => visitor.visit_index_expr(expr),
+            Self::SetIndex(expr) => visitor.visit_set_index_expr(expr),
             Self::Grouping(expr) => visitor.visit_grouping_expr(expr),
+            Self::Array(expr) => visitor.visit_array_expr(expr),
+            Self::Map(expr) => visitor.visit_map_expr(expr),
+            Self::SetLiteral(expr) => visitor.visit_set_literal_expr(expr),
+            Self::Struct(expr) => visitor.visit_struct_expr(expr),
+            Self::Path(expr) => visitor.visit_path_expr(expr),
             Self::Variable(expr) => visitor.visit_variable_expr(expr),
             Self::Literal(expr) => visitor.visit_literal_expr(expr),
         }
@@ -106,11 +329,23 @@ impl Expr {
 pub trait Visitor {
     type Result;
 
+    fn visit_conditional_expr(&mut self, expr: &Conditional) -> Self::Result;
     fn visit_logical_expr(&mut self, expr: &Logical) -> Self::Result;
     fn visit_binary_expr(&mut self, expr: &Binary) -> Self::Result;
+    fn visit_loop_expr(&mut self, expr: &Loop) -> Self::Result;
+    fn visit_cast_expr(&mut self, expr: &Cast) -> Self::Result;
     fn visit_unary_expr(&mut self, expr: &Unary) -> Self::Result;
     fn visit_call_expr(&mut self, expr: &Call) -> Self::Result;
+    fn visit_get_expr(&mut self, expr: &Get) -> Self::Result;
+    fn visit_set_expr(&mut self, expr: &Set) -> Self::Result;
+    fn visit_index_expr(&mut self, expr: &Index) -> Self::Result;
+    fn visit_set_index_expr(&mut self, expr: &SetIndex) -> Self::Result;
     fn visit_grouping_expr(&mut self, expr: &Grouping) -> Self::Result;
+    fn visit_array_expr(&mut self, expr: &Array) -> Self::Result;
+    fn visit_map_expr(&mut self, expr: &Map) -> Self::Result;
+    fn visit_set_literal_expr(&mut self, expr: &SetLiteral) -> Self::Result;
+    fn visit_struct_expr(&mut self, expr: &Struct) -> Self::Result;
+    fn visit_path_expr(&mut self, expr: &Path) -> Self::Result;
     fn visit_variable_expr(&mut self, expr: &Variable) -> Self::Result;
     fn visit_literal_expr(&mut self, expr: &Literal) -> Self::Result;
 }
@@ -0,0 +1,396 @@
+use std::mem;
+
+use crate::code::Code;
+use crate::error::{FlowError, Warning};
+use crate::expr;
+use crate::stmt;
+use crate::token::Token;
+
+pub struct Flow {
+    warnings: Vec<Warning>,
+    errors: Vec<FlowError>,
+    loops: Vec<(Option<String>, bool)>,
+}
+
+impl Flow {
+    pub fn new() -> Self {
+        Self {
+            warnings: Vec::new(),
+            errors: Vec::new(),
+            loops: Vec::new(),
+        }
+    }
+
+    pub fn analyze(
+        &mut self,
+        statements: &[stmt::Stmt],
+    ) -> (Vec<Warning>, Vec<FlowError>) {
+        for statement in statements {
+            statement.accept(self);
+        }
+
+        (mem::take(&mut self.warnings), mem::take(&mut self.errors))
+    }
+
+    fn body(&mut self, label: &Option<Token>, body: &stmt::Stmt) -> bool {
+        self.loops
+            .push((label.as_ref().map(|label| label.lexeme.clone()), false));
+
+        body.accept(self);
+
+        match self.loops.pop() {
+            Some((_, broken)) => broken,
+            None => false,
+        }
+    }
+
+    fn outside(&mut self, keyword: &Token) {
+        if self.loops.is_empty() {
+            self.errors.push(FlowError {
+                line: keyword.line,
+                span: keyword.span,
+                code: Code::JumpOutsideLoop,
+                message: Code::JumpOutsideLoop.message(&[&keyword.lexeme]),
+            });
+        }
+    }
+
+    fn warning(
+        &mut self,
+        token: &Token,
+        code: Code,
+        lint: &'static str,
+        message: &str,
+    ) {
+        self.warnings.push(Warning {
+            line: token.line,
+            span: token.span,
+            code,
+            lint,
+            message: message.to_string(),
+        });
+    }
+}
+
+impl Default for Flow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl expr::Visitor for Flow {
+    type Result = ();
+
+    fn visit_conditional_expr(
+        &mut self,
+        expr: &expr::Conditional,
+    ) -> Self::Result {
+        expr.condition.accept(self);
+        expr.then_branch.accept(self);
+        expr.else_branch.accept(self);
+    }
+
+    fn visit_logical_expr(&mut self, expr: &expr::Logical) -> Self::Result {
+        expr.left.accept(self);
+        expr.right.accept(self);
+    }
+
+    fn visit_binary_expr(&mut self, expr: &expr::Binary) -> Self::Result {
+        expr.left.accept(self);
+        expr.right.accept(self);
+    }
+
+    fn visit_loop_expr(&mut self, expr: &expr::Loop) -> Self::Result {
+        self.body(&None, &expr.body);
+    }
+
+    fn visit_cast_expr(&mut self, expr: &expr::Cast) -> Self::Result {
+        expr.value.accept(self);
+    }
+
+    fn visit_unary_expr(&mut self, expr: &expr::Unary) -> Self::Result {
+        expr.right.accept(self);
+    }
+
+    fn visit_call_expr(&mut self, expr: &expr::Call) -> Self::Result {
+        expr.callee.accept(self);
+
+        for argument in expr.arguments.iter() {
+            argument.accept(self);
+        }
+    }
+
+    fn visit_get_expr(&mut self, expr: &expr::Get) -> Self::Result {
+        expr.object.accept(self);
+    }
+
+    fn visit_set_expr(&mut self, expr: &expr::Set) -> Self::Result {
+        expr.object.accept(self);
+        expr.value.accept(self);
+    }
+
+    fn visit_index_expr(&mut self, expr: &expr::Index) -> Self::Result {
+        expr.object.accept(self);
+        expr.index.accept(self);
+    }
+
+    fn visit_set_index_expr(&mut self, expr: &expr::SetIndex) -> Self::Result {
+        expr.object.accept(self);
+        expr.index.accept(self);
+        expr.value.accept(self);
+    }
+
+    fn visit_grouping_expr(&mut self, expr: &expr::Grouping) -> Self::Result {
+        expr.expression.accept(self);
+    }
+
+    fn visit_array_expr(&mut self, expr: &expr::Array) -> Self::Result {
+        for element in expr.elements.iter() {
+            element.accept(self);
+        }
+    }
+
+    fn visit_map_expr(&mut self, expr: &expr::Map) -> Self::Result {
+        for (key, value) in expr.entries.iter() {
+            key.accept(self);
+            value.accept(self);
+        }
+    }
+
+    fn visit_set_literal_expr(
+        &mut self,
+        expr: &expr::SetLiteral,
+    ) -> Self::Result {
+        for element in expr.elements.iter() {
+            element.accept(self);
+        }
+    }
+
+    fn visit_struct_expr(&mut self, expr: &expr::Struct) -> Self::Result {
+        for (_, value) in expr.fields.iter() {
+            value.accept(self);
+        }
+
+        if let Some(base) = &expr.base {
+            base.accept(self);
+        }
+    }
+
+    fn visit_path_expr(&mut self, _expr: &expr::Path) -> Self::Result {}
+
+    fn visit_variable_expr(&mut self, _expr: &expr::Variable) -> Self::Result {}
+
+    fn visit_literal_expr(&mut self, _expr: &expr::Literal) -> Self::Result {}
+}
+
+impl stmt::Visitor for Flow {
+    type Result = Option<Token>;
+
+    fn visit_if_stmt(&mut self, stmt: &stmt::If) -> Self::Result {
+        stmt.condition.accept(self);
+
+        let then_branch = stmt.then_branch.accept(self);
+        let else_branch = match &stmt.else_branch {
+            Some(branch) => branch.accept(self),
+            None => None,
+        };
+
+        then_branch.and(else_branch)
+    }
+
+    fn visit_match_stmt(&mut self, stmt: &stmt::Match) -> Self::Result {
+        stmt.value.accept(self);
+
+        let mut terminator = None;
+        let mut terminates = !stmt.arms.is_empty();
+
+        for (_, guard, body) in stmt.arms.iter() {
+            if let Some(guard) = guard {
+                guard.accept(self);
+            }
+
+            match body.accept(self) {
+                Some(token) => terminator = Some(token),
+                None => terminates = false,
+            }
+        }
+
+        terminator.filter(|_| terminates)
+    }
+
+    fn visit_function_stmt(&mut self, stmt: &stmt::Function) -> Self::Result {
+        let loops = mem::take(&mut self.loops);
+        let terminates = stmt.body.accept(self).is_some();
+
+        self.loops = loops;
+
+        if stmt.output.is_some() && !terminates {
+            self.errors.push(FlowError {
+                line: stmt.name.line,
+                span: stmt.name.span,
+                code: Code::MissingReturn,
+                message: Code::MissingReturn.message(&[&stmt.name.lexeme]),
+            });
+        }
+
+        None
+    }
+
+    fn visit_test_stmt(&mut self, stmt: &stmt::Test) -> Self::Result {
+        let loops = mem::take(&mut self.loops);
+        stmt.body.accept(self);
+        self.loops = loops;
+
+        None
+    }
+
+    fn visit_bench_stmt(&mut self, stmt: &stmt::Bench) -> Self::Result {
+        let loops = mem::take(&mut self.loops);
+        stmt.body.accept(self);
+        self.loops = loops;
+
+        None
+    }
+
+    fn visit_return_stmt(&mut self, stmt: &stmt::Return) -> Self::Result {
+        if let Some(value) = &stmt.value {
+            value.accept(self);
+        }
+
+        Some(stmt.keyword.clone())
+    }
+
+    fn visit_raise_stmt(&mut self, stmt: &stmt::Raise) -> Self::Result {
+        stmt.value.accept(self);
+        Some(stmt.keyword.clone())
+    }
+
+    fn visit_try_stmt(&mut self, stmt: &stmt::Try) -> Self::Result {
+        let body = stmt.body.accept(self);
+        let handler = stmt.handler.accept(self);
+
+        body.and(handler)
+    }
+
+    fn visit_loop_stmt(&mut self, stmt: &stmt::Loop) -> Self::Result {
+        if self.body(&stmt.label, &stmt.body) {
+            None
+        } else {
+            Some(stmt.keyword.clone())
+        }
+    }
+
+    fn visit_for_stmt(&mut self, stmt: &stmt::For) -> Self::Result {
+        stmt.start.accept(self);
+        stmt.end.accept(self);
+        self.body(&stmt.label, &stmt.body);
+
+        None
+    }
+
+    fn visit_break_stmt(&mut self, stmt: &stmt::Break) -> Self::Result {
+        if let Some(value) = &stmt.value {
+            value.accept(self);
+        }
+
+        self.outside(&stmt.keyword);
+
+        let target = match &stmt.label {
+            Some(label) => self
+                .loops
+                .iter_mut()
+                .rev()
+                .find(|(name, _)| name.as_ref() == Some(&label.lexeme)),
+            None => self.loops.last_mut(),
+        };
+
+        if let Some((_, broken)) = target {
+            *broken = true;
+        }
+
+        Some(stmt.keyword.clone())
+    }
+
+    fn visit_continue_stmt(&mut self, stmt: &stmt::Continue) -> Self::Result {
+        self.outside(&stmt.keyword);
+        Some(stmt.keyword.clone())
+    }
+
+    fn visit_let_stmt(&mut self, stmt: &stmt::Let) -> Self::Result {
+        if let Some(initializer) = &stmt.initializer {
+            initializer.accept(self);
+        }
+
+        None
+    }
+
+    fn visit_const_stmt(&mut self, stmt: &stmt::Const) -> Self::Result {
+        stmt.initializer.accept(self);
+        None
+    }
+
+    fn visit_type_stmt(&mut self, _stmt: &stmt::Type) -> Self::Result {
+        None
+    }
+
+    fn visit_struct_stmt(&mut self, _stmt: &stmt::Struct) -> Self::Result {
+        None
+    }
+
+    fn visit_enum_stmt(&mut self, _stmt: &stmt::Enum) -> Self::Result {
+        None
+    }
+
+    fn visit_impl_stmt(&mut self, stmt: &stmt::Impl) -> Self::Result {
+        for method in stmt.methods.iter() {
+            method.accept(self);
+        }
+
+        None
+    }
+
+    fn visit_block_stmt(&mut self, stmt: &stmt::Block) -> Self::Result {
+        let mut terminator: Option<Token> = None;
+        let mut warned = false;
+
+        for statement in stmt.statements.iter() {
+            let item = matches!(statement, stmt::Stmt::Function(_));
+
+            if let Some(token) = terminator.clone().filter(|_| !warned && !item)
+            {
+                self.warning(
+                    statement.token().unwrap_or(&token),
+                    Code::UnreachableStatement,
+                    "unreachable",
+                    &Code::UnreachableStatement.message(&[&token.lexeme]),
+                );
+
+                warned = true;
+            }
+
+            let terminates = statement.accept(self);
+
+            if terminator.is_none() {
+                terminator = terminates;
+            }
+        }
+
+        terminator
+    }
+
+    fn visit_assignment_stmt(
+        &mut self,
+        stmt: &stmt::Assignment,
+    ) -> Self::Result {
+        stmt.value.accept(self);
+        None
+    }
+
+    fn visit_expression_stmt(
+        &mut self,
+        stmt: &stmt::Expression,
+    ) -> Self::Result {
+        stmt.expression.accept(self);
+        None
+    }
+}
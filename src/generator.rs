@@ -1,57 +1,955 @@
+use std::collections::{HashMap, HashSet};
 use std::mem;
 
+use crate::checker::Program;
+use crate::code::Code;
 use crate::error::GenerateError;
 use crate::expr;
 use crate::kind::Kind;
+use crate::pattern;
 use crate::stmt;
-use crate::value::Value;
+use crate::token::Token;
+use crate::value::{Suffix, Value};
 use crate::variant;
 
 static RUNTIME: &str = r#"
-    #![allow(dead_code, unused_mut, unused_parens)]
+    #![allow(dead_code, non_upper_case_globals, unused_imports)]
+    #![allow(unreachable_code, unused_mut, unused_parens, unused_variables)]
+    #![allow(unused_assignments, non_snake_case, non_camel_case_types)]
 
-    use std::fmt::Display;
-    use std::time::{SystemTime, UNIX_EPOCH};
+    use self::__blaze::*;
+    use self::__blaze::{Subscript as _, SubscriptMut as _};
 
-    fn clock() -> f64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs_f64()
+    mod __blaze {
+        pub use std::borrow::Cow;
+        pub use std::cell::{Cell, RefCell};
+        pub use std::collections::{HashMap, HashSet};
+        use std::convert::TryFrom;
+        use std::env;
+        use std::fmt::Display;
+        use std::hash::Hash;
+        use std::io;
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        pub trait FloorDiv {
+            fn floor_div(self, other: Self) -> Self;
+        }
+
+        impl FloorDiv for i32 {
+            fn floor_div(self, other: Self) -> Self {
+                let quotient = self / other;
+
+                if self % other != 0 && (self < 0) != (other < 0) {
+                    quotient - 1
+                } else {
+                    quotient
+                }
+            }
+        }
+
+        impl FloorDiv for f64 {
+            fn floor_div(self, other: Self) -> Self {
+                (self / other).floor()
+            }
+        }
+
+        pub trait Power: Sized {
+            fn absolute(self) -> Self;
+            fn power(self, exponent: Self) -> Option<Self>;
+        }
+
+        impl Power for i32 {
+            fn absolute(self) -> Self {
+                self.abs()
+            }
+
+            fn power(self, exponent: Self) -> Option<Self> {
+                u32::try_from(exponent).ok().map(|exponent| self.pow(exponent))
+            }
+        }
+
+        impl Power for f64 {
+            fn absolute(self) -> Self {
+                self.abs()
+            }
+
+            fn power(self, exponent: Self) -> Option<Self> {
+                Some(self.powf(exponent))
+            }
+        }
+
+        pub trait Subscript<K> {
+            type Output;
+
+            fn subscript(&self, key: K) -> Self::Output;
+            fn checked(&self, key: K) -> Option<Self::Output>;
+        }
+
+        impl<T: Clone> Subscript<i32> for Vec<T> {
+            type Output = T;
+
+            fn subscript(&self, key: i32) -> T {
+                self[key as usize].clone()
+            }
+
+            fn checked(&self, key: i32) -> Option<T> {
+                usize::try_from(key).ok().and_then(|key| self.get(key)).cloned()
+            }
+        }
+
+        impl<K: Eq + Hash, V: Clone> Subscript<K> for HashMap<K, V> {
+            type Output = V;
+
+            fn subscript(&self, key: K) -> V {
+                self[&key].clone()
+            }
+
+            fn checked(&self, key: K) -> Option<V> {
+                self.get(&key).cloned()
+            }
+        }
+
+        impl Subscript<i32> for Cow<'static, str> {
+            type Output = Cow<'static, str>;
+
+            fn subscript(&self, key: i32) -> Cow<'static, str> {
+                self.checked(key).expect("index out of bounds")
+            }
+
+            fn checked(&self, key: i32) -> Option<Cow<'static, str>> {
+                usize::try_from(key)
+                    .ok()
+                    .and_then(|key| self.chars().nth(key))
+                    .map(|c| Cow::Owned(c.to_string()))
+            }
+        }
+
+        pub trait SubscriptMut<K>: Subscript<K> {
+            fn subscript_mut(&mut self, key: K) -> &mut Self::Output;
+            fn store(&mut self, key: K, value: Self::Output);
+        }
+
+        impl<T: Clone> SubscriptMut<i32> for Vec<T> {
+            fn subscript_mut(&mut self, key: i32) -> &mut T {
+                &mut self[key as usize]
+            }
+
+            fn store(&mut self, key: i32, value: T) {
+                self[key as usize] = value;
+            }
+        }
+
+        impl<K: Eq + Hash, V: Clone> SubscriptMut<K> for HashMap<K, V> {
+            fn subscript_mut(&mut self, key: K) -> &mut V {
+                self.get_mut(&key).expect("key not found")
+            }
+
+            fn store(&mut self, key: K, value: V) {
+                self.insert(key, value);
+            }
+        }
+
+        pub trait Length {
+            fn length(&self) -> usize;
+        }
+
+        impl<T> Length for Vec<T> {
+            fn length(&self) -> usize {
+                self.len()
+            }
+        }
+
+        impl<K, V> Length for HashMap<K, V> {
+            fn length(&self) -> usize {
+                self.len()
+            }
+        }
+
+        impl<T> Length for HashSet<T> {
+            fn length(&self) -> usize {
+                self.len()
+            }
+        }
+
+        impl Length for Cow<'static, str> {
+            fn length(&self) -> usize {
+                self.chars().count()
+            }
+        }
+
+        pub trait Keyed<K> {
+            type Removed;
+
+            fn has(&self, key: &K) -> bool;
+            fn take(&mut self, key: K) -> Self::Removed;
+        }
+
+        impl<K: Eq + Hash, V> Keyed<K> for HashMap<K, V> {
+            type Removed = Option<V>;
+
+            fn has(&self, key: &K) -> bool {
+                self.contains_key(key)
+            }
+
+            fn take(&mut self, key: K) -> Option<V> {
+                self.remove(&key)
+            }
+        }
+
+        impl<T: Eq + Hash> Keyed<T> for HashSet<T> {
+            type Removed = bool;
+
+            fn has(&self, value: &T) -> bool {
+                self.contains(value)
+            }
+
+            fn take(&mut self, value: T) -> bool {
+                self.remove(&value)
+            }
+        }
+
+        pub fn abs<T: Power>(value: T) -> T {
+            value.absolute()
+        }
+
+        pub fn add<T: Eq + Hash>(set: &mut HashSet<T>, value: T) {
+            set.insert(value);
+        }
+
+        pub fn args() -> Vec<Cow<'static, str>> {
+            env::args().skip(1).map(Cow::Owned).collect()
+        }
+
+        pub fn assert(
+            condition: bool,
+            message: Cow<'static, str>,
+            line: usize,
+        ) {
+            if !condition {
+                panic!("[line {}] Assertion failed: {}", line, message);
+            }
+        }
+
+        pub fn bit(value: i32, index: i32) -> bool {
+            value
+                .checked_shr(index as u32)
+                .map_or(false, |value| value & 1 == 1)
+        }
+
+        pub fn checked_add(left: i32, right: i32) -> Option<i32> {
+            left.checked_add(right)
+        }
+
+        pub fn checked_div(left: i32, right: i32) -> Option<i32> {
+            left.checked_div(right)
+        }
+
+        pub fn checked_mul(left: i32, right: i32) -> Option<i32> {
+            left.checked_mul(right)
+        }
+
+        pub fn checked_sub(left: i32, right: i32) -> Option<i32> {
+            left.checked_sub(right)
+        }
+
+        pub fn clock() -> f64 {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs_f64()
+        }
+
+        pub fn contains<C: Keyed<K>, K>(collection: &C, key: K) -> bool {
+            collection.has(&key)
+        }
+
+        pub fn cos(value: f64) -> f64 {
+            value.cos()
+        }
+
+        pub fn count_ones(value: i32) -> i32 {
+            value.count_ones() as i32
+        }
+
+        pub fn count_zeros(value: i32) -> i32 {
+            value.count_zeros() as i32
+        }
+
+        pub fn div<T: FloorDiv>(left: T, right: T) -> T {
+            left.floor_div(right)
+        }
+
+        pub fn env(name: Cow<'static, str>) -> Option<Cow<'static, str>> {
+            env::var(name.as_ref()).ok().map(Cow::Owned)
+        }
+
+        pub fn floor(value: f64) -> f64 {
+            value.floor()
+        }
+
+        pub fn get<C: Subscript<K>, K>(
+            collection: &C,
+            key: K,
+        ) -> Option<C::Output> {
+            collection.checked(key)
+        }
+
+        pub fn input() -> Cow<'static, str> {
+            let mut line = String::new();
+            io::stdin().read_line(&mut line).expect("failed to read input");
+
+            Cow::Owned(line.trim().to_string())
+        }
+
+        pub fn input_num() -> Option<f64> {
+            parse_num(input())
+        }
+
+        pub fn insert<K: Eq + Hash, V>(
+            map: &mut HashMap<K, V>,
+            key: K,
+            value: V,
+        ) {
+            map.insert(key, value);
+        }
+
+        pub fn leading_zeros(value: i32) -> i32 {
+            value.leading_zeros() as i32
+        }
+
+        pub fn len(value: &impl Length) -> i32 {
+            value.length() as i32
+        }
+
+        pub fn lower(value: Cow<'static, str>) -> Cow<'static, str> {
+            Cow::Owned(value.to_lowercase())
+        }
+
+        pub fn parse_num(text: Cow<'static, str>) -> Option<f64> {
+            text.trim().parse().ok()
+        }
+
+        pub fn pop<T>(array: &mut Vec<T>) -> Option<T> {
+            array.pop()
+        }
+
+        pub fn pow<T: Power>(base: T, exponent: T, line: usize) -> T {
+            base.power(exponent).unwrap_or_else(|| {
+                panic!(
+                    "[line {}] Can't raise an 'i32' to a negative power.",
+                    line
+                )
+            })
+        }
+
+        pub fn print(value: impl Display) {
+            println!("{}", value);
+        }
+
+        pub fn push<T>(array: &mut Vec<T>, value: T) {
+            array.push(value);
+        }
+
+        thread_local! {
+            static random_state: Cell<u64> = Cell::new(0);
+        }
+
+        pub fn random() -> f64 {
+            random_state.with(|state| {
+                let mut value = state.get();
+
+                if value == 0 {
+                    value = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap()
+                        .as_nanos() as u64
+                        | 1;
+                }
+
+                value ^= value << 13;
+                value ^= value >> 7;
+                value ^= value << 17;
+                state.set(value);
+
+                (value >> 11) as f64 / (1u64 << 53) as f64
+            })
+        }
+
+        pub fn remove<C: Keyed<K>, K>(
+            collection: &mut C,
+            key: K,
+        ) -> C::Removed {
+            collection.take(key)
+        }
+
+        pub fn rotate_left(value: i32, count: i32) -> i32 {
+            value.rotate_left(count as u32)
+        }
+
+        pub fn rotate_right(value: i32, count: i32) -> i32 {
+            value.rotate_right(count as u32)
+        }
+
+        pub fn saturating_add(left: i32, right: i32) -> i32 {
+            left.saturating_add(right)
+        }
+
+        pub fn saturating_mul(left: i32, right: i32) -> i32 {
+            left.saturating_mul(right)
+        }
+
+        pub fn saturating_sub(left: i32, right: i32) -> i32 {
+            left.saturating_sub(right)
+        }
+
+        pub fn sin(value: f64) -> f64 {
+            value.sin()
+        }
+
+        pub fn sqrt(value: f64) -> f64 {
+            value.sqrt()
+        }
+
+        pub fn substring(
+            value: Cow<'static, str>,
+            start: i32,
+            end: i32,
+        ) -> Cow<'static, str> {
+            let start = start.max(0) as usize;
+            let end = end.max(0) as usize;
+
+            Cow::Owned(value.chars().take(end).skip(start).collect())
+        }
+
+        pub fn to_string(value: impl Display) -> Cow<'static, str> {
+            Cow::Owned(value.to_string())
+        }
+
+        pub fn trailing_zeros(value: i32) -> i32 {
+            value.trailing_zeros() as i32
+        }
+
+        pub fn upper(value: Cow<'static, str>) -> Cow<'static, str> {
+            Cow::Owned(value.to_uppercase())
+        }
+
+        pub fn wrapping_add(left: i32, right: i32) -> i32 {
+            left.wrapping_add(right)
+        }
+
+        pub fn wrapping_div(left: i32, right: i32) -> i32 {
+            left.wrapping_div(right)
+        }
+
+        pub fn wrapping_mul(left: i32, right: i32) -> i32 {
+            left.wrapping_mul(right)
+        }
+
+        pub fn wrapping_neg(value: i32) -> i32 {
+            value.wrapping_neg()
+        }
+
+        pub fn wrapping_sub(left: i32, right: i32) -> i32 {
+            left.wrapping_sub(right)
+        }
+
+        pub fn duration(nanos: f64) -> String {
+            if nanos < 1e3 {
+                format!("{:.2} ns", nanos)
+            } else if nanos < 1e6 {
+                format!("{:.2} µs", nanos / 1e3)
+            } else if nanos < 1e9 {
+                format!("{:.2} ms", nanos / 1e6)
+            } else {
+                format!("{:.2} s", nanos / 1e9)
+            }
+        }
+
+        pub fn bench(name: &str, body: fn()) {
+            use std::time::{Duration, Instant};
+
+            let warmup = Instant::now();
+            let mut runs = 0u32;
+
+            while runs == 0 || warmup.elapsed() < Duration::from_millis(100) {
+                body();
+                runs += 1;
+            }
+
+            let estimate = warmup.elapsed().as_nanos() as f64 / runs as f64;
+            let iterations = (1e7 / estimate).max(1.0) as u32;
+            let mut samples = Vec::new();
+
+            for _ in 0..30 {
+                let start = Instant::now();
+
+                for _ in 0..iterations {
+                    std::hint::black_box(body)();
+                }
+
+                let elapsed = start.elapsed().as_nanos() as f64;
+                samples.push(elapsed / iterations as f64);
+            }
+
+            let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+            let variance = samples
+                .iter()
+                .map(|sample| (sample - mean).powi(2))
+                .sum::<f64>()
+                / samples.len() as f64;
+
+            println!(
+                "bench {} ... {} ± {} ({} × {} iterations)",
+                name,
+                duration(mean),
+                duration(variance.sqrt()),
+                samples.len(),
+                iterations
+            );
+        }
+    }
+"#;
+
+const MUTATORS: &[&str] = &["add", "insert", "pop", "push", "remove"];
+const READERS: &[&str] = &["contains", "get", "len"];
+
+pub struct Generator {
+    errors: Vec<GenerateError>,
+    globals: HashSet<String>,
+    functions: HashSet<String>,
+    types: HashMap<String, Vec<variant::Variant>>,
+    scopes: Vec<HashSet<String>>,
+    enclosing: Vec<HashSet<String>>,
+    output: Option<variant::Variant>,
+    catching: usize,
+    harnesses: HashSet<String>,
+    benches: Vec<(String, String)>,
+    benching: bool,
+    coercions: HashSet<usize>,
+}
+
+impl Generator {
+    pub fn new() -> Self {
+        Self {
+            errors: Vec::new(),
+            globals: HashSet::new(),
+            functions: HashSet::new(),
+            types: HashMap::new(),
+            scopes: Vec::new(),
+            enclosing: Vec::new(),
+            output: None,
+            catching: 0,
+            harnesses: HashSet::new(),
+            benches: Vec::new(),
+            benching: false,
+            coercions: HashSet::new(),
+        }
+    }
+
+    pub fn generate(
+        &mut self,
+        program: &Program,
+    ) -> (String, Vec<GenerateError>) {
+        let statements = program.statements;
+        self.coercions = program.coercions.clone();
+
+        self.globals = statements
+            .iter()
+            .filter_map(|statement| match statement {
+                stmt::Stmt::Let(stmt) => Some(stmt.name.lexeme.clone()),
+                stmt::Stmt::Const(stmt) => Some(stmt.name.lexeme.clone()),
+                _ => None,
+            })
+            .collect();
+
+        self.functions = statements
+            .iter()
+            .filter_map(|statement| match statement {
+                stmt::Stmt::Function(stmt) => Some(stmt.name.lexeme.clone()),
+                _ => None,
+            })
+            .collect();
+
+        self.types = statements
+            .iter()
+            .filter_map(|statement| match statement {
+                stmt::Stmt::Struct(stmt) => Some((
+                    stmt.name.lexeme.clone(),
+                    stmt.fields
+                        .iter()
+                        .map(|(_, variant)| variant.clone())
+                        .collect(),
+                )),
+                stmt::Stmt::Enum(stmt) => Some((
+                    stmt.name.lexeme.clone(),
+                    stmt.variants
+                        .iter()
+                        .flat_map(|(_, fields)| fields.iter().cloned())
+                        .collect(),
+                )),
+                stmt::Stmt::Type(stmt) => {
+                    Some((stmt.name.lexeme.clone(), vec![stmt.variant.clone()]))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let benchmarked = statements
+            .iter()
+            .any(|statement| matches!(statement, stmt::Stmt::Bench(_)));
+
+        let mut generated: Vec<String> = statements
+            .iter()
+            .map(|statement| {
+                let generated = match statement {
+                    stmt::Stmt::Function(stmt)
+                        if benchmarked && stmt.name.lexeme == "main" =>
+                    {
+                        format!(
+                            "#[cfg(not(blaze_bench))] {}",
+                            statement.accept(self)
+                        )
+                    }
+                    _ => statement.accept(self),
+                };
+
+                self.mark(statement, generated)
+            })
+            .collect();
+
+        if benchmarked {
+            let benches: Vec<String> = mem::take(&mut self.benches)
+                .into_iter()
+                .map(|(name, function)| {
+                    format!("__blaze::bench({}, {});", name, function)
+                })
+                .collect();
+
+            generated.push(format!(
+                "#[cfg(blaze_bench)] fn main() {{ {} }}",
+                benches.join(" ")
+            ));
+        }
+
+        let output = format!("{}{}", RUNTIME, generated.join(" "));
+        let errors = mem::take(&mut self.errors);
+
+        (output, errors)
+    }
+
+    fn mark(&self, statement: &stmt::Stmt, generated: String) -> String {
+        match statement.line() {
+            Some(line) => format!("\n// line {}\n{}", line, generated),
+            None => generated,
+        }
+    }
+
+    fn declare(&mut self, name: &str) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string());
+        }
+    }
+
+    fn global(&self, name: &str) -> String {
+        format!("__global_{}", name)
+    }
+
+    fn is_local(&self, name: &str) -> bool {
+        self.scopes.iter().any(|scope| scope.contains(name))
+    }
+
+    fn is_builtin(&self, name: &str) -> bool {
+        !self.functions.contains(name) && !self.is_local(name)
+    }
+
+    fn is_global(&self, name: &str) -> bool {
+        self.globals.contains(name) && !self.is_local(name)
+    }
+
+    fn is_captured(&self, name: &str) -> bool {
+        !self.is_local(name)
+            && self.enclosing.iter().any(|scope| scope.contains(name))
+    }
+
+    fn coerce(&mut self, expression: &expr::Expr) -> String {
+        let value = expression.accept(self);
+
+        match expression {
+            _ if !self.coercions.contains(&expression.id()) => value,
+            expr::Expr::Array(_)
+            | expr::Expr::Map(_)
+            | expr::Expr::SetLiteral(_) => format!("Some({})", value),
+            expr::Expr::Literal(literal) => match literal.value {
+                Value::None => value,
+                _ => format!("Some({})", value),
+            },
+            _ => format!("Option::from({})", value),
+        }
+    }
+
+    fn mutate(&mut self, name: &str, arguments: &[expr::Expr]) -> String {
+        let (collection, rest) = match arguments.split_first() {
+            Some(split) => split,
+            None => return format!("{}()", name),
+        };
+
+        let values: String = rest
+            .iter()
+            .map(|argument| format!("{}, ", self.coerce(argument)))
+            .collect();
+        let names: String = (0..rest.len())
+            .map(|index| format!(", __arguments.{}", index))
+            .collect();
+
+        let target = self.place(collection, "(*global.borrow_mut())");
+        let call = format!("{}(&mut {}{})", name, target, names);
+
+        match self.root(collection) {
+            Some(root) if self.is_global(root) => format!(
+                "{{ let __arguments = ({}); {}.with(|global| {}) }}",
+                values,
+                self.global(root),
+                call
+            ),
+            _ => format!("{{ let __arguments = ({}); {} }}", values, call),
+        }
+    }
+
+    fn read(&mut self, name: &str, arguments: &[expr::Expr]) -> String {
+        let (collection, rest) = match arguments.split_first() {
+            Some(split) => split,
+            None => return format!("{}()", name),
+        };
+
+        let values: String = rest
+            .iter()
+            .map(|argument| format!("{}, ", self.coerce(argument)))
+            .collect();
+        let names: String = (0..rest.len())
+            .map(|index| format!(", __arguments.{}", index))
+            .collect();
+
+        let target = self.view(collection, "(*global.borrow())");
+        let call = format!(
+            "{}(&{}{})",
+            name,
+            target.clone().unwrap_or_else(|| collection.accept(self)),
+            names
+        );
+
+        match self.root(collection) {
+            Some(root) if target.is_some() && self.is_global(root) => format!(
+                "{{ let __arguments = ({}); {}.with(|global| {}) }}",
+                values,
+                self.global(root),
+                call
+            ),
+            _ => format!("{{ let __arguments = ({}); {} }}", values, call),
+        }
+    }
+
+    // Like place, but for reads, so it only follows variables and fields and
+    // leaves anything else to be evaluated on its own.
+    fn view(
+        &mut self,
+        expression: &expr::Expr,
+        global: &str,
+    ) -> Option<String> {
+        match expression {
+            expr::Expr::Variable(variable)
+                if self.is_global(&variable.name.lexeme) =>
+            {
+                Some(global.to_string())
+            }
+            expr::Expr::Variable(variable) => Some(self.name(&variable.name)),
+            expr::Expr::Get(get) => self
+                .view(&get.object, global)
+                .map(|object| format!("{}.{}", object, get.name.lexeme)),
+            _ => None,
+        }
+    }
+
+    fn root<'a>(&self, expression: &'a expr::Expr) -> Option<&'a str> {
+        match expression {
+            expr::Expr::Variable(variable) => Some(&variable.name.lexeme),
+            expr::Expr::Get(get) => self.root(&get.object),
+            expr::Expr::Index(index) => self.root(&index.object),
+            _ => None,
+        }
+    }
+
+    fn place(&mut self, expression: &expr::Expr, global: &str) -> String {
+        match expression {
+            expr::Expr::Variable(variable)
+                if self.is_global(&variable.name.lexeme) =>
+            {
+                global.to_string()
+            }
+            expr::Expr::Get(get) => format!(
+                "{}.{}",
+                self.place(&get.object, global),
+                get.name.lexeme
+            ),
+            expr::Expr::Index(index) => format!(
+                "{}.subscript_mut({})",
+                self.place(&index.object, global),
+                index.index.accept(self)
+            ),
+            _ => self.borrow(expression),
+        }
+    }
+
+    fn borrow(&mut self, expression: &expr::Expr) -> String {
+        match expression {
+            expr::Expr::Variable(variable)
+                if !self.is_global(&variable.name.lexeme) =>
+            {
+                self.name(&variable.name)
+            }
+            _ => expression.accept(self),
+        }
+    }
+
+    fn name(&mut self, name: &Token) -> String {
+        if self.is_captured(&name.lexeme) {
+            self.error(
+                name,
+                Code::CapturedLocal,
+                Code::CapturedLocal.template(),
+            )
+        } else {
+            name.lexeme.clone()
+        }
+    }
+
+    fn label(&self, label: &Option<Token>) -> String {
+        match label {
+            Some(label) => format!("'{}: ", label.lexeme),
+            None => "".to_string(),
+        }
+    }
+
+    // Structs and enums can be compared with `==` unless they hold a
+    // function, since Rust warns about comparing function pointers and the
+    // checker rejects those comparisons anyway.
+    fn comparable(
+        &self,
+        variant: &variant::Variant,
+        seen: &mut HashSet<String>,
+    ) -> bool {
+        match variant {
+            variant::Variant::Literal(literal) => {
+                let name = &literal.name.lexeme;
+
+                !seen.insert(name.clone())
+                    || self.types.get(name).is_none_or(|fields| {
+                        fields.iter().all(|field| self.comparable(field, seen))
+                    })
+            }
+            variant::Variant::Function(_) => false,
+            variant::Variant::Array(array) => {
+                self.comparable(&array.element, seen)
+            }
+            variant::Variant::Map(map) => {
+                self.comparable(&map.key, seen)
+                    && self.comparable(&map.value, seen)
+            }
+            variant::Variant::Set(set) => self.comparable(&set.element, seen),
+            variant::Variant::Optional(optional) => {
+                self.comparable(&optional.inner, seen)
+            }
+            variant::Variant::Fallible(fallible) => {
+                self.comparable(&fallible.value, seen)
+                    && self.comparable(&fallible.error, seen)
+            }
+        }
+    }
+
+    fn derive(&self, name: &Token) -> &'static str {
+        let mut seen = HashSet::new();
+        let comparable = self.types.get(&name.lexeme).is_none_or(|fields| {
+            seen.insert(name.lexeme.clone());
+            fields.iter().all(|field| self.comparable(field, &mut seen))
+        });
+
+        if comparable {
+            "#[derive(Clone, Debug, PartialEq)] "
+        } else {
+            "#[derive(Clone, Debug)] "
+        }
     }
 
-    fn print(value: impl Display) {
-        println!("{}", value);
+    fn deprecation(&self, deprecated: &Option<stmt::Deprecated>) -> String {
+        match deprecated {
+            Some(stmt::Deprecated { note: Some(note) }) => {
+                format!("#[deprecated(note = \"{}\")] ", note)
+            }
+            Some(stmt::Deprecated { note: None }) => {
+                "#[deprecated] ".to_string()
+            }
+            None => "".to_string(),
+        }
     }
-"#;
-
-pub struct Generator {
-    errors: Vec<GenerateError>,
-}
 
-impl Generator {
-    pub fn new() -> Self {
-        Self { errors: Vec::new() }
+    fn value(&self, value: &Value) -> String {
+        match value {
+            Value::False => "false".to_string(),
+            Value::True => "true".to_string(),
+            Value::None => "None".to_string(),
+            Value::Number(number, None) => number.to_string(),
+            Value::Number(number, Some(Suffix::Integer)) => {
+                format!("{}i32", number)
+            }
+            Value::Number(number, Some(Suffix::Float)) => {
+                format!("{}f64", number)
+            }
+            Value::String(string) => format!("\"{}\"", string),
+        }
     }
 
-    pub fn generate(
+    fn harness(
         &mut self,
-        statements: &[stmt::Stmt],
-    ) -> (String, Vec<GenerateError>) {
-        let generated: Vec<String> = statements
-            .iter()
-            .map(|statement| statement.accept(self))
+        prefix: &str,
+        name: &Token,
+        body: &stmt::Stmt,
+    ) -> (String, String) {
+        let lexeme = &name.lexeme;
+        let base: String = lexeme[1..lexeme.len() - 1]
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
             .collect();
 
-        let output = format!("{}{}", RUNTIME, generated.join(" "));
-        let errors = mem::take(&mut self.errors);
+        let mut name = format!("{}_{}", prefix, base);
+        let mut count = 1;
 
-        (output, errors)
+        while self.harnesses.contains(&name) {
+            count += 1;
+            name = format!("{}_{}_{}", prefix, base, count);
+        }
+
+        self.harnesses.insert(name.clone());
+
+        let enclosing = mem::take(&mut self.scopes);
+        self.scopes.push(HashSet::new());
+        let enclosing_output = self.output.take();
+        let body = body.accept(self);
+        self.output = enclosing_output;
+        self.scopes = enclosing;
+
+        (name, body)
     }
 
-    fn error(&mut self, line: usize, message: &str) -> String {
+    fn error(&mut self, token: &Token, code: Code, message: &str) -> String {
         self.errors.push(GenerateError {
-            line,
+            line: token.line,
+            span: token.span,
+            code,
             message: message.to_string(),
         });
 
@@ -59,14 +957,45 @@ impl Generator {
     }
 }
 
+impl Default for Generator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl expr::Visitor for Generator {
     type Result = String;
 
+    fn visit_conditional_expr(
+        &mut self,
+        expr: &expr::Conditional,
+    ) -> Self::Result {
+        format!(
+            "(if {} {{ {} }} else {{ {} }})",
+            expr.condition.accept(self),
+            expr.then_branch.accept(self),
+            expr.else_branch.accept(self)
+        )
+    }
+
     fn visit_logical_expr(&mut self, expr: &expr::Logical) -> Self::Result {
         let operator = match expr.operator.kind {
             Kind::AmpAmp => "&&",
             Kind::BarBar => "||",
-            _ => return self.error(expr.operator.line, "Unexpected operator."),
+            Kind::QuestionQuestion => {
+                return format!(
+                    "(match {} {{ Some(value) => value, None => {} }})",
+                    expr.left.accept(self),
+                    expr.right.accept(self)
+                )
+            }
+            _ => {
+                return self.error(
+                    &expr.operator,
+                    Code::UnsupportedConstruct,
+                    "Unexpected operator.",
+                )
+            }
         };
 
         format!(
@@ -85,11 +1014,22 @@ impl expr::Visitor for Generator {
             Kind::Less => "<",
             Kind::GreaterEqual => ">=",
             Kind::Greater => ">",
+            Kind::Bar => "|",
+            Kind::Caret => "^",
+            Kind::Amp => "&",
+            Kind::LessLess => "<<",
+            Kind::GreaterGreater => ">>",
             Kind::Plus => "+",
             Kind::Minus => "-",
             Kind::Star => "*",
             Kind::Slash => "/",
-            _ => return self.error(expr.operator.line, "Unexpected operator."),
+            _ => {
+                return self.error(
+                    &expr.operator,
+                    Code::UnsupportedConstruct,
+                    "Unexpected operator.",
+                )
+            }
         };
 
         format!(
@@ -100,40 +1040,295 @@ impl expr::Visitor for Generator {
         )
     }
 
+    fn visit_loop_expr(&mut self, expr: &expr::Loop) -> Self::Result {
+        format!("(loop {})", expr.body.accept(self))
+    }
+
+    fn visit_cast_expr(&mut self, expr: &expr::Cast) -> Self::Result {
+        format!("({} as {})", expr.value.accept(self), expr.target.lexeme)
+    }
+
     fn visit_unary_expr(&mut self, expr: &expr::Unary) -> Self::Result {
         let operator = match expr.operator.kind {
             Kind::Minus => "-",
             Kind::Bang => "!",
-            _ => return self.error(expr.operator.line, "Unexpected operator."),
+            Kind::Try if self.catching > 0 => {
+                return format!(
+                    "(match {} {{ Ok(__value) => __value, Err(__error) => \
+                     break '__try{} Some(__error) }})",
+                    expr.right.accept(self),
+                    self.catching
+                );
+            }
+            Kind::Try => {
+                return format!("({}?)", expr.right.accept(self));
+            }
+            _ => {
+                return self.error(
+                    &expr.operator,
+                    Code::UnsupportedConstruct,
+                    "Unexpected operator.",
+                )
+            }
         };
 
         format!("({}{})", operator, expr.right.accept(self))
     }
 
     fn visit_call_expr(&mut self, expr: &expr::Call) -> Self::Result {
+        if let expr::Expr::Variable(callee) = &expr.callee {
+            let name = callee.name.lexeme.as_str();
+
+            if MUTATORS.contains(&name) && self.is_builtin(name) {
+                return self.mutate(name, &expr.arguments);
+            }
+
+            if READERS.contains(&name) && self.is_builtin(name) {
+                return self.read(name, &expr.arguments);
+            }
+        }
+
         let arguments: Vec<String> = expr
             .arguments
             .iter()
-            .map(|argument| argument.accept(self))
+            .map(|argument| self.coerce(argument))
             .collect();
 
+        if let expr::Expr::Variable(callee) = &expr.callee {
+            if callee.name.lexeme == "assert" && self.is_builtin("assert") {
+                let mut arguments = arguments;
+
+                if arguments.len() == 1 {
+                    arguments.push(
+                        "__blaze::Cow::Borrowed(\"assertion failed\")"
+                            .to_string(),
+                    );
+                }
+
+                arguments.push(callee.name.line.to_string());
+
+                return format!("(assert)({})", arguments.join(", "));
+            }
+
+            if callee.name.lexeme == "pow" && self.is_builtin("pow") {
+                let mut arguments = arguments;
+                arguments.push(callee.name.line.to_string());
+
+                return format!("(pow)({})", arguments.join(", "));
+            }
+
+            if callee.name.lexeme == "print"
+                && arguments.len() != 1
+                && self.is_builtin("print")
+            {
+                return format!(
+                    "println!(\"{}\"{})",
+                    vec!["{}"; arguments.len()].join(" "),
+                    arguments
+                        .iter()
+                        .map(|argument| format!(", {}", argument))
+                        .collect::<String>()
+                );
+            }
+        }
+
+        if let expr::Expr::Get(callee) = &expr.callee {
+            let receiver = self.place(&callee.object, "__receiver");
+
+            return match self.root(&callee.object) {
+                Some(name) if self.is_global(name) => {
+                    let global = self.global(name);
+
+                    let (arguments, names) = if arguments.is_empty() {
+                        ("".to_string(), Vec::new())
+                    } else {
+                        let names = (0..arguments.len())
+                            .map(|index| format!("arguments.{}", index))
+                            .collect();
+
+                        let arguments = format!(
+                            "let arguments = ({}); ",
+                            arguments
+                                .iter()
+                                .map(|argument| format!("{},", argument))
+                                .collect::<String>()
+                        );
+
+                        (arguments, names)
+                    };
+
+                    format!(
+                        concat!(
+                            "{{ {}",
+                            "let mut __receiver = ",
+                            "{}.with(|global| global.borrow().clone()); ",
+                            "let result = {}.{}({}); ",
+                            "{}.with(|global| *global.borrow_mut() = __receiver); ",
+                            "result }}"
+                        ),
+                        arguments,
+                        global,
+                        receiver,
+                        callee.name.lexeme,
+                        names.join(", "),
+                        global
+                    )
+                }
+                _ => format!(
+                    "{}.{}({})",
+                    receiver,
+                    callee.name.lexeme,
+                    arguments.join(", ")
+                ),
+            };
+        }
+
         format!("({})({})", expr.callee.accept(self), arguments.join(", "))
     }
 
+    fn visit_get_expr(&mut self, expr: &expr::Get) -> Self::Result {
+        format!("{}.{}.clone()", self.borrow(&expr.object), expr.name.lexeme)
+    }
+
+    fn visit_set_expr(&mut self, expr: &expr::Set) -> Self::Result {
+        let value = self.coerce(&expr.value);
+        let target = format!(
+            "{}.{}",
+            self.place(&expr.object, "(*global.borrow_mut())"),
+            expr.name.lexeme
+        );
+
+        match self.root(&expr.object) {
+            Some(name) if self.is_global(name) => format!(
+                "{{ let value = {}; {}.with(|global| {} = value); }}",
+                value,
+                self.global(name),
+                target
+            ),
+            _ => format!("{} = {}", target, value),
+        }
+    }
+
+    fn visit_index_expr(&mut self, expr: &expr::Index) -> Self::Result {
+        format!(
+            "{}.subscript({})",
+            self.borrow(&expr.object),
+            expr.index.accept(self)
+        )
+    }
+
+    fn visit_set_index_expr(&mut self, expr: &expr::SetIndex) -> Self::Result {
+        let index = expr.index.accept(self);
+        let value = self.coerce(&expr.value);
+        let target = self.place(&expr.object, "(*global.borrow_mut())");
+
+        match self.root(&expr.object) {
+            Some(name) if self.is_global(name) => format!(
+                "{{ let __index = {}; let __value = {}; \
+                 {}.with(|global| {}.store(__index, __value)); }}",
+                index,
+                value,
+                self.global(name),
+                target
+            ),
+            _ => format!(
+                "{{ let __index = {}; let __value = {}; \
+                 {}.store(__index, __value); }}",
+                index, value, target
+            ),
+        }
+    }
+
     fn visit_grouping_expr(&mut self, expr: &expr::Grouping) -> Self::Result {
         format!("({})", expr.expression.accept(self))
     }
 
+    fn visit_array_expr(&mut self, expr: &expr::Array) -> Self::Result {
+        let elements: Vec<String> = expr
+            .elements
+            .iter()
+            .map(|element| self.coerce(element))
+            .collect();
+
+        format!("vec![{}]", elements.join(", "))
+    }
+
+    fn visit_map_expr(&mut self, expr: &expr::Map) -> Self::Result {
+        // An empty '{}' can be a map or a set, so Rust infers which from
+        // where it's used.
+        if expr.entries.is_empty() {
+            return "std::default::Default::default()".to_string();
+        }
+
+        let entries: Vec<String> = expr
+            .entries
+            .iter()
+            .map(|(key, value)| {
+                format!("({}, {})", self.coerce(key), self.coerce(value))
+            })
+            .collect();
+
+        format!(
+            "vec![{}].into_iter().collect::<__blaze::HashMap<_, _>>()",
+            entries.join(", ")
+        )
+    }
+
+    fn visit_set_literal_expr(
+        &mut self,
+        expr: &expr::SetLiteral,
+    ) -> Self::Result {
+        let elements: Vec<String> = expr
+            .elements
+            .iter()
+            .map(|element| self.coerce(element))
+            .collect();
+
+        format!(
+            "vec![{}].into_iter().collect::<__blaze::HashSet<_>>()",
+            elements.join(", ")
+        )
+    }
+
+    fn visit_struct_expr(&mut self, expr: &expr::Struct) -> Self::Result {
+        let mut fields: Vec<String> = expr
+            .fields
+            .iter()
+            .map(|(name, value)| {
+                format!("{}: {}", name.lexeme, self.coerce(value))
+            })
+            .collect();
+
+        if let Some(base) = &expr.base {
+            fields.push(format!("..{}", base.accept(self)));
+        }
+
+        format!("{} {{ {} }}", expr.name.lexeme, fields.join(", "))
+    }
+
+    fn visit_path_expr(&mut self, expr: &expr::Path) -> Self::Result {
+        format!("{}::{}", expr.namespace.lexeme, expr.name.lexeme)
+    }
+
     fn visit_variable_expr(&mut self, expr: &expr::Variable) -> Self::Result {
-        expr.name.lexeme.clone()
+        if self.is_global(&expr.name.lexeme) {
+            format!(
+                "{}.with(|value| value.borrow().clone())",
+                self.global(&expr.name.lexeme)
+            )
+        } else if self.is_local(&expr.name.lexeme) {
+            format!("{}.clone()", self.name(&expr.name))
+        } else {
+            self.name(&expr.name)
+        }
     }
 
     fn visit_literal_expr(&mut self, expr: &expr::Literal) -> Self::Result {
-        match &expr.value {
-            Value::False => "false".to_string(),
-            Value::True => "true".to_string(),
-            Value::Number(number) => number.to_string(),
-            Value::String(string) => format!("\"{}\"", string),
+        match expr.value {
+            Value::String(_) => {
+                format!("__blaze::Cow::Borrowed({})", self.value(&expr.value))
+            }
+            _ => self.value(&expr.value),
         }
     }
 }
@@ -156,76 +1351,396 @@ impl stmt::Visitor for Generator {
         )
     }
 
+    fn visit_match_stmt(&mut self, stmt: &stmt::Match) -> Self::Result {
+        let strings = stmt.arms.iter().any(|(pattern, _, _)| {
+            matches!(pattern, pattern::Pattern::Literal(literal)
+                if matches!(literal.value, Value::String(_)))
+        });
+
+        let arms: Vec<String> = stmt
+            .arms
+            .iter()
+            .map(|(pattern, guard, body)| {
+                self.scopes.push(HashSet::new());
+                let arm = match pattern {
+                    pattern::Pattern::Binding(binding) if strings => {
+                        self.declare(&binding.name.lexeme);
+
+                        let bind = format!(
+                            "let mut {} = __value.clone();",
+                            binding.name.lexeme
+                        );
+                        let guard = match guard {
+                            Some(guard) => format!(
+                                " if {{ {} {} }}",
+                                bind,
+                                guard.accept(self)
+                            ),
+                            None => String::new(),
+                        };
+
+                        format!(
+                            "_{} => {{ {} {} }}",
+                            guard,
+                            bind,
+                            body.accept(self)
+                        )
+                    }
+                    _ => {
+                        let pattern = pattern.accept(self);
+                        let guard = match guard {
+                            Some(guard) => {
+                                format!(" if {}", guard.accept(self))
+                            }
+                            None => String::new(),
+                        };
+
+                        format!(
+                            "{}{} => {{ {} }}",
+                            pattern,
+                            guard,
+                            body.accept(self)
+                        )
+                    }
+                };
+                self.scopes.pop();
+
+                arm
+            })
+            .collect();
+
+        if strings {
+            format!(
+                "{{ let __value = {}; match &*__value {{ {} }} }}",
+                stmt.value.accept(self),
+                arms.join(" ")
+            )
+        } else {
+            format!(
+                "match {} {{ {} }}",
+                stmt.value.accept(self),
+                arms.join(" ")
+            )
+        }
+    }
+
     fn visit_function_stmt(&mut self, stmt: &stmt::Function) -> Self::Result {
-        let parameters: Vec<String> = stmt
+        let mut parameters: Vec<String> = stmt
             .parameters
             .iter()
             .map(|parameter| {
-                format!("{}: {}", parameter.0.lexeme, parameter.1.accept(self))
+                format!(
+                    "mut {}: {}",
+                    parameter.0.lexeme,
+                    parameter.1.accept(self)
+                )
             })
             .collect();
 
+        if stmt.receiver {
+            parameters.insert(0, "&mut self".to_string());
+        }
+
         let output = if let Some(variant) = &stmt.output {
             variant.accept(self)
         } else {
             "()".to_string()
         };
 
+        let enclosing = mem::take(&mut self.scopes);
+        let depth = self.enclosing.len();
+        self.enclosing.extend(enclosing.iter().cloned());
+
+        let mut scope: HashSet<String> = stmt
+            .parameters
+            .iter()
+            .map(|parameter| parameter.0.lexeme.clone())
+            .collect();
+
+        if stmt.receiver {
+            scope.insert("self".to_string());
+        }
+
+        self.scopes.push(scope);
+        let enclosing_output =
+            mem::replace(&mut self.output, stmt.output.clone());
+        let catching = mem::take(&mut self.catching);
+        let body = stmt.body.accept(self);
+        self.output = enclosing_output;
+        self.catching = catching;
+
+        self.enclosing.truncate(depth);
+        self.scopes = enclosing;
+
         format!(
-            "fn {}({}) -> {} {}",
+            "{}fn {}({}) -> {} {}",
+            self.deprecation(&stmt.deprecated),
             stmt.name.lexeme,
             parameters.join(", "),
             output,
-            stmt.body.accept(self)
+            body
         )
     }
 
+    fn visit_test_stmt(&mut self, stmt: &stmt::Test) -> Self::Result {
+        let (name, body) = self.harness("test", &stmt.name, &stmt.body);
+        format!("#[cfg(test)] #[test] fn {}() -> () {}", name, body)
+    }
+
+    fn visit_bench_stmt(&mut self, stmt: &stmt::Bench) -> Self::Result {
+        self.benching = true;
+        let (name, body) = self.harness("bench", &stmt.name, &stmt.body);
+        self.benching = false;
+        self.benches.push((stmt.name.lexeme.clone(), name.clone()));
+
+        format!("#[cfg(blaze_bench)] fn {}() -> () {}", name, body)
+    }
+
     fn visit_return_stmt(&mut self, stmt: &stmt::Return) -> Self::Result {
         if let Some(expression) = &stmt.value {
-            format!("return {};", expression.accept(self))
+            match &self.output {
+                Some(variant::Variant::Fallible(_)) => {
+                    format!("return Ok({});", self.coerce(expression))
+                }
+                _ => format!("return {};", self.coerce(expression)),
+            }
         } else {
             "return;".to_string()
         }
     }
 
+    fn visit_raise_stmt(&mut self, stmt: &stmt::Raise) -> Self::Result {
+        let value = stmt.value.accept(self);
+
+        if self.catching > 0 {
+            format!("break '__try{} Some({});", self.catching, value)
+        } else {
+            format!("return Err({});", value)
+        }
+    }
+
+    fn visit_try_stmt(&mut self, stmt: &stmt::Try) -> Self::Result {
+        self.catching += 1;
+        let label = format!("'__try{}", self.catching);
+        let body = stmt.body.accept(self);
+        self.catching -= 1;
+
+        self.scopes.push(HashSet::new());
+
+        let error = match &stmt.error {
+            Some(error) => {
+                self.declare(&error.lexeme);
+                format!("mut {}", error.lexeme)
+            }
+            None => "_".to_string(),
+        };
+
+        let handler = stmt.handler.accept(self);
+        self.scopes.pop();
+
+        format!(
+            "{{ let __caught = {}: {{ {} None }}; \
+             if let Some({}) = __caught {} }}",
+            label, body, error, handler
+        )
+    }
+
     fn visit_loop_stmt(&mut self, stmt: &stmt::Loop) -> Self::Result {
-        format!("loop {}", stmt.body.accept(self))
+        format!("{}loop {}", self.label(&stmt.label), stmt.body.accept(self))
+    }
+
+    fn visit_for_stmt(&mut self, stmt: &stmt::For) -> Self::Result {
+        let start = stmt.start.accept(self);
+        let end = stmt.end.accept(self);
+
+        self.scopes.push(HashSet::new());
+        self.declare(&stmt.variable.lexeme);
+        let body = stmt.body.accept(self);
+        self.scopes.pop();
+
+        format!(
+            "{}for mut {} in ({})..({}) {}",
+            self.label(&stmt.label),
+            stmt.variable.lexeme,
+            start,
+            end,
+            body
+        )
     }
 
-    fn visit_break_stmt(&mut self, _stmt: &stmt::Break) -> Self::Result {
-        "break;".to_string()
+    fn visit_break_stmt(&mut self, stmt: &stmt::Break) -> Self::Result {
+        let label = match &stmt.label {
+            Some(label) => format!(" '{}", label.lexeme),
+            None => "".to_string(),
+        };
+
+        let value = match &stmt.value {
+            Some(value) => format!(" {}", value.accept(self)),
+            None => "".to_string(),
+        };
+
+        format!("break{}{};", label, value)
     }
 
-    fn visit_continue_stmt(&mut self, _stmt: &stmt::Continue) -> Self::Result {
-        "continue;".to_string()
+    fn visit_continue_stmt(&mut self, stmt: &stmt::Continue) -> Self::Result {
+        match &stmt.label {
+            Some(label) => format!("continue '{};", label.lexeme),
+            None => "continue;".to_string(),
+        }
     }
 
     fn visit_let_stmt(&mut self, stmt: &stmt::Let) -> Self::Result {
+        if self.scopes.is_empty() {
+            let initializer = match &stmt.initializer {
+                Some(expression) => self.coerce(expression),
+                None => {
+                    return self.error(
+                        &stmt.name,
+                        Code::UnsupportedConstruct,
+                        "Global variable needs an initializer.",
+                    )
+                }
+            };
+
+            let variant = match &stmt.variant {
+                Some(variant) => variant.accept(self),
+                None => {
+                    return self.error(
+                        &stmt.name,
+                        Code::UnsupportedConstruct,
+                        "Global variable needs a type.",
+                    )
+                }
+            };
+
+            return format!(
+                "thread_local! {{ static {}: __blaze::RefCell<{}> = __blaze::RefCell::new({}); }}",
+                self.global(&stmt.name.lexeme),
+                variant,
+                initializer
+            );
+        }
+
+        let variant = if let Some(variant) = &stmt.variant {
+            format!(": {}", variant.accept(self))
+        } else {
+            "".to_string()
+        };
+
         let initializer = if let Some(expression) = &stmt.initializer {
-            format!(" = {}", expression.accept(self))
+            format!(" = {}", self.coerce(expression))
         } else {
             "".to_string()
         };
 
+        self.declare(&stmt.name.lexeme);
+
+        format!("let mut {}{}{};", stmt.name.lexeme, variant, initializer)
+    }
+
+    // Top-level constants are initialized lazily like globals, so any
+    // expression can compute them; the resolver keeps them read-only.
+    fn visit_const_stmt(&mut self, stmt: &stmt::Const) -> Self::Result {
+        let initializer = self.coerce(&stmt.initializer);
+        let variant = stmt.variant.accept(self);
+
+        if self.scopes.is_empty() {
+            return format!(
+                "thread_local! {{ {}static {}: __blaze::RefCell<{}> = __blaze::RefCell::new({}); }}",
+                self.deprecation(&stmt.deprecated),
+                self.global(&stmt.name.lexeme),
+                variant,
+                initializer
+            );
+        }
+
+        self.declare(&stmt.name.lexeme);
+
         format!(
-            "let mut {}: {}{};",
+            "{}let {}: {} = {};",
+            self.deprecation(&stmt.deprecated),
             stmt.name.lexeme,
-            stmt.variant.accept(self),
+            variant,
             initializer
         )
     }
 
     fn visit_type_stmt(&mut self, stmt: &stmt::Type) -> Self::Result {
-        format!("type {} = {};", stmt.name.lexeme, stmt.variant.accept(self))
+        format!(
+            "{}type {} = {};",
+            self.deprecation(&stmt.deprecated),
+            stmt.name.lexeme,
+            stmt.variant.accept(self)
+        )
+    }
+
+    fn visit_struct_stmt(&mut self, stmt: &stmt::Struct) -> Self::Result {
+        let fields: Vec<String> = stmt
+            .fields
+            .iter()
+            .map(|(name, variant)| {
+                format!("{}: {}", name.lexeme, variant.accept(self))
+            })
+            .collect();
+
+        format!(
+            "{}{}struct {} {{ {} }}",
+            self.deprecation(&stmt.deprecated),
+            self.derive(&stmt.name),
+            stmt.name.lexeme,
+            fields.join(", ")
+        )
+    }
+
+    fn visit_enum_stmt(&mut self, stmt: &stmt::Enum) -> Self::Result {
+        let variants: Vec<String> = stmt
+            .variants
+            .iter()
+            .map(|(name, fields)| {
+                if fields.is_empty() {
+                    name.lexeme.clone()
+                } else {
+                    let fields: Vec<String> =
+                        fields.iter().map(|field| field.accept(self)).collect();
+
+                    format!("{}({})", name.lexeme, fields.join(", "))
+                }
+            })
+            .collect();
+
+        format!(
+            "{}{}enum {} {{ {} }}",
+            self.deprecation(&stmt.deprecated),
+            self.derive(&stmt.name),
+            stmt.name.lexeme,
+            variants.join(", ")
+        )
+    }
+
+    fn visit_impl_stmt(&mut self, stmt: &stmt::Impl) -> Self::Result {
+        let methods: Vec<String> = stmt
+            .methods
+            .iter()
+            .map(|method| method.accept(self))
+            .collect();
+
+        format!("impl {} {{ {} }}", stmt.name.lexeme, methods.join(" "))
     }
 
     fn visit_block_stmt(&mut self, stmt: &stmt::Block) -> Self::Result {
+        self.scopes.push(HashSet::new());
+
         let statements: Vec<String> = stmt
             .statements
             .iter()
-            .map(|statement| statement.accept(self))
+            .map(|statement| {
+                let generated = statement.accept(self);
+                self.mark(statement, generated)
+            })
             .collect();
 
+        self.scopes.pop();
+
         format!("{{ {} }}", statements.join(" "))
     }
 
@@ -233,14 +1748,34 @@ impl stmt::Visitor for Generator {
         &mut self,
         stmt: &stmt::Assignment,
     ) -> Self::Result {
-        format!("{} = {};", stmt.name.lexeme, stmt.value.accept(self))
+        let value = self.coerce(&stmt.value);
+
+        if self.is_captured(&stmt.name.lexeme) {
+            self.error(
+                &stmt.name,
+                Code::CapturedLocal,
+                Code::CapturedLocal.template(),
+            )
+        } else if self.is_global(&stmt.name.lexeme) {
+            format!(
+                "{{ let value = {}; {}.with(|global| *global.borrow_mut() = value); }}",
+                value,
+                self.global(&stmt.name.lexeme)
+            )
+        } else {
+            format!("{} = {};", stmt.name.lexeme, value)
+        }
     }
 
     fn visit_expression_stmt(
         &mut self,
         stmt: &stmt::Expression,
     ) -> Self::Result {
-        format!("{};", stmt.expression.accept(self))
+        if self.benching {
+            format!("std::hint::black_box({});", stmt.expression.accept(self))
+        } else {
+            format!("{};", stmt.expression.accept(self))
+        }
     }
 }
 
@@ -251,7 +1786,10 @@ impl variant::Visitor for Generator {
         &mut self,
         variant: &variant::Literal,
     ) -> Self::Result {
-        variant.name.lexeme.clone()
+        match variant.name.lexeme.as_str() {
+            "str" => "__blaze::Cow<'static, str>".to_string(),
+            name => name.to_string(),
+        }
     }
 
     fn visit_function_variant(
@@ -272,4 +1810,84 @@ impl variant::Visitor for Generator {
 
         format!("fn({}) -> {}", parameters.join("\n"), output)
     }
+
+    fn visit_array_variant(
+        &mut self,
+        variant: &variant::Array,
+    ) -> Self::Result {
+        format!("Vec<{}>", variant.element.accept(self))
+    }
+
+    fn visit_map_variant(&mut self, variant: &variant::Map) -> Self::Result {
+        format!(
+            "__blaze::HashMap<{}, {}>",
+            variant.key.accept(self),
+            variant.value.accept(self)
+        )
+    }
+
+    fn visit_set_variant(&mut self, variant: &variant::Set) -> Self::Result {
+        format!("__blaze::HashSet<{}>", variant.element.accept(self))
+    }
+
+    fn visit_optional_variant(
+        &mut self,
+        variant: &variant::Optional,
+    ) -> Self::Result {
+        format!("Option<{}>", variant.inner.accept(self))
+    }
+
+    fn visit_fallible_variant(
+        &mut self,
+        variant: &variant::Fallible,
+    ) -> Self::Result {
+        format!(
+            "Result<{}, {}>",
+            variant.value.accept(self),
+            variant.error.accept(self)
+        )
+    }
+}
+
+impl pattern::Visitor for Generator {
+    type Result = String;
+
+    fn visit_literal_pattern(
+        &mut self,
+        pattern: &pattern::Literal,
+    ) -> Self::Result {
+        self.value(&pattern.value)
+    }
+
+    fn visit_path_pattern(&mut self, pattern: &pattern::Path) -> Self::Result {
+        let path =
+            format!("{}::{}", pattern.namespace.lexeme, pattern.name.lexeme);
+
+        if pattern.fields.is_empty() {
+            path
+        } else {
+            let fields: Vec<String> = pattern
+                .fields
+                .iter()
+                .map(|field| field.accept(self))
+                .collect();
+
+            format!("{}({})", path, fields.join(", "))
+        }
+    }
+
+    fn visit_binding_pattern(
+        &mut self,
+        pattern: &pattern::Binding,
+    ) -> Self::Result {
+        self.declare(&pattern.name.lexeme);
+        format!("mut {}", pattern.name.lexeme)
+    }
+
+    fn visit_wildcard_pattern(
+        &mut self,
+        _pattern: &pattern::Wildcard,
+    ) -> Self::Result {
+        "_".to_string()
+    }
 }
@@ -0,0 +1,401 @@
+use std::fmt;
+
+const MAX_DEPTH: usize = 128;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                escaped.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    format!("\"{}\"", escaped)
+}
+
+impl Json {
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut reader = Reader {
+            source: text.chars().collect(),
+            current: 0,
+            depth: 0,
+        };
+
+        let value = reader.value()?;
+        reader.whitespace();
+
+        if reader.current == reader.source.len() {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    pub fn object(entries: Vec<(&str, Self)>) -> Self {
+        Self::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Object(entries) => entries
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Self]> {
+        match self {
+            Self::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Null => write!(formatter, "null"),
+            Self::Bool(value) => write!(formatter, "{}", value),
+            Self::Number(value) if value.fract() == 0.0 => {
+                write!(formatter, "{}", *value as i64)
+            }
+            Self::Number(value) => write!(formatter, "{}", value),
+            Self::String(string) => write!(formatter, "{}", escape(string)),
+            Self::Array(elements) => {
+                write!(formatter, "[")?;
+
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        write!(formatter, ",")?;
+                    }
+
+                    write!(formatter, "{}", element)?;
+                }
+
+                write!(formatter, "]")
+            }
+            Self::Object(entries) => {
+                write!(formatter, "{{")?;
+
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        write!(formatter, ",")?;
+                    }
+
+                    write!(formatter, "{}:{}", escape(key), value)?;
+                }
+
+                write!(formatter, "}}")
+            }
+        }
+    }
+}
+
+struct Reader {
+    source: Vec<char>,
+    current: usize,
+    depth: usize,
+}
+
+impl Reader {
+    fn value(&mut self) -> Option<Json> {
+        self.whitespace();
+
+        match self.peek()? {
+            'n' => self.keyword("null", Json::Null),
+            't' => self.keyword("true", Json::Bool(true)),
+            'f' => self.keyword("false", Json::Bool(false)),
+            '"' => self.string().map(Json::String),
+            '[' => self.nested(Self::array),
+            '{' => self.nested(Self::object),
+            _ => self.number(),
+        }
+    }
+
+    fn nested(&mut self, parse: fn(&mut Self) -> Option<Json>) -> Option<Json> {
+        if self.depth >= MAX_DEPTH {
+            return None;
+        }
+
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+
+        value
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Json) -> Option<Json> {
+        for expected in keyword.chars() {
+            if self.advance()? != expected {
+                return None;
+            }
+        }
+
+        Some(value)
+    }
+
+    fn number(&mut self) -> Option<Json> {
+        let start = self.current;
+
+        while let Some(c) = self.peek() {
+            if c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E') {
+                self.current += 1;
+            } else {
+                break;
+            }
+        }
+
+        let text: String = self.source[start..self.current].iter().collect();
+        text.parse().ok().map(Json::Number)
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.advance();
+
+        let mut string = String::new();
+
+        loop {
+            match self.advance()? {
+                '"' => return Some(string),
+                '\\' => match self.advance()? {
+                    'n' => string.push('\n'),
+                    't' => string.push('\t'),
+                    'r' => string.push('\r'),
+                    'b' => string.push('\u{8}'),
+                    'f' => string.push('\u{c}'),
+                    'u' => string.push(self.unicode()?),
+                    c => string.push(c),
+                },
+                c => string.push(c),
+            }
+        }
+    }
+
+    fn unicode(&mut self) -> Option<char> {
+        let high = self.hex()?;
+
+        if (0xd800..0xdc00).contains(&high) {
+            if self.advance()? != '\\' || self.advance()? != 'u' {
+                return None;
+            }
+
+            let low = self.hex()?;
+
+            if !(0xdc00..0xe000).contains(&low) {
+                return None;
+            }
+
+            let code = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+
+            char::from_u32(code)
+        } else {
+            char::from_u32(high)
+        }
+    }
+
+    fn hex(&mut self) -> Option<u32> {
+        let mut code = 0;
+
+        for _ in 0..4 {
+            code = code * 16 + self.advance()?.to_digit(16)?;
+        }
+
+        Some(code)
+    }
+
+    fn array(&mut self) -> Option<Json> {
+        self.advance();
+
+        let mut elements = Vec::new();
+        self.whitespace();
+
+        if self.peek()? == ']' {
+            self.advance();
+            return Some(Json::Array(elements));
+        }
+
+        loop {
+            elements.push(self.value()?);
+            self.whitespace();
+
+            match self.advance()? {
+                ',' => (),
+                ']' => return Some(Json::Array(elements)),
+                _ => return None,
+            }
+        }
+    }
+
+    fn object(&mut self) -> Option<Json> {
+        self.advance();
+
+        let mut entries = Vec::new();
+        self.whitespace();
+
+        if self.peek()? == '}' {
+            self.advance();
+            return Some(Json::Object(entries));
+        }
+
+        loop {
+            self.whitespace();
+
+            if self.peek()? != '"' {
+                return None;
+            }
+
+            let key = self.string()?;
+            self.whitespace();
+
+            if self.advance()? != ':' {
+                return None;
+            }
+
+            entries.push((key, self.value()?));
+            self.whitespace();
+
+            match self.advance()? {
+                ',' => (),
+                '}' => return Some(Json::Object(entries)),
+                _ => return None,
+            }
+        }
+    }
+
+    fn whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.current += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source.get(self.current).copied()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.current += 1;
+
+        Some(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Json;
+
+    #[test]
+    fn parses_values() {
+        assert_eq!(Json::parse("null"), Some(Json::Null));
+        assert_eq!(Json::parse(" true "), Some(Json::Bool(true)));
+        assert_eq!(Json::parse("-1.5e2"), Some(Json::Number(-150.0)));
+        assert_eq!(
+            Json::parse(r#"{"a": [1, "b"], "c": {}}"#),
+            Some(Json::object(vec![
+                (
+                    "a",
+                    Json::Array(vec![
+                        Json::Number(1.0),
+                        Json::String("b".to_string()),
+                    ])
+                ),
+                ("c", Json::Object(Vec::new())),
+            ]))
+        );
+    }
+
+    #[test]
+    fn parses_escapes() {
+        assert_eq!(
+            Json::parse(r#""a\n\"\\\u00e9""#),
+            Some(Json::String("a\n\"\\\u{e9}".to_string()))
+        );
+    }
+
+    #[test]
+    fn parses_surrogate_pairs() {
+        assert_eq!(
+            Json::parse(r#""\ud83d\ude00""#),
+            Some(Json::String("\u{1f600}".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_surrogates() {
+        assert_eq!(Json::parse(r#""\ud800\u0041""#), None);
+        assert_eq!(Json::parse(r#""\ud800\ud800""#), None);
+        assert_eq!(Json::parse(r#""\ud800""#), None);
+        assert_eq!(Json::parse(r#""\udc00""#), None);
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        for text in ["", "nul", "[1,", "[1 2]", r#"{"a" 1}"#, "1 2", r#""a"#] {
+            assert_eq!(Json::parse(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn rejects_deep_nesting() {
+        let shallow = format!("{}{}", "[".repeat(128), "]".repeat(128));
+        assert!(Json::parse(&shallow).is_some());
+
+        let deep = "[".repeat(100_000);
+        assert_eq!(Json::parse(&deep), None);
+        assert_eq!(Json::parse(&r#"{"a":"#.repeat(100_000)), None);
+    }
+
+    #[test]
+    fn prints_and_parses_back() {
+        let value = Json::object(vec![
+            ("id", Json::Number(3.0)),
+            ("text", Json::String("line\n\t\"quoted\"".to_string())),
+            ("items", Json::Array(vec![Json::Null, Json::Bool(false)])),
+        ]);
+
+        assert_eq!(
+            value.to_string(),
+            r#"{"id":3,"text":"line\n\t\"quoted\"","items":[null,false]}"#
+        );
+        assert_eq!(Json::parse(&value.to_string()), Some(value));
+    }
+}
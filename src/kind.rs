@@ -4,25 +4,38 @@ pub enum Kind {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
+    DotDot,
+    Dot,
     Plus,
     Minus,
     Star,
     Slash,
+    Caret,
+    QuestionQuestion,
     Question,
     Colon,
+    ColonColon,
     Semicolon,
+    At,
 
     BangEqual,
     Bang,
     EqualEqual,
+    EqualGreater,
     Equal,
     LessEqual,
+    LessLess,
     Less,
     GreaterEqual,
+    GreaterGreater,
     Greater,
     AmpAmp,
+    Amp,
     BarBar,
+    Bar,
 
     Identifier,
     String,
@@ -30,15 +43,27 @@ pub enum Kind {
 
     If,
     Else,
+    Match,
     Fn,
     Return,
+    Raise,
+    Try,
+    Catch,
     False,
     True,
+    None,
     Loop,
+    For,
+    In,
+    As,
     Break,
     Continue,
     Let,
+    Const,
     Type,
+    Struct,
+    Enum,
+    Impl,
 
-    EOF,
+    Eof,
 }
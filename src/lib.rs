@@ -0,0 +1,85 @@
+pub mod build;
+mod checker;
+mod code;
+mod driver;
+mod error;
+pub mod expr;
+mod flow;
+mod generator;
+mod kind;
+mod optimizer;
+mod parser;
+pub mod pattern;
+mod report;
+mod resolver;
+mod scanner;
+pub mod stmt;
+mod suggest;
+mod token;
+mod value;
+pub mod variant;
+
+pub use crate::checker::{Checker, Program};
+pub use crate::code::Code;
+pub use crate::driver::{Options, Output, Stage};
+pub use crate::error::{
+    Diagnostic, GenerateError, Level, Severity, SyntaxError, TypeError,
+};
+pub use crate::generator::Generator;
+pub use crate::kind::Kind;
+pub use crate::parser::Parser;
+pub use crate::report::{Format, Reporter};
+pub use crate::resolver::Definition;
+pub use crate::scanner::{Scanner, Semicolons};
+pub use crate::token::{Span, Token};
+pub use crate::value::{Suffix, Value};
+
+pub fn compile(source: &str) -> Result<String, Vec<Diagnostic>> {
+    compile_with(source, &Options::default())
+}
+
+pub fn compile_with(
+    source: &str,
+    options: &Options,
+) -> Result<String, Vec<Diagnostic>> {
+    let reporter = collector(source, options);
+
+    match driver::compile(&reporter, source, options, Stage::Generate) {
+        Some(Output::Rust(output)) => Ok(output),
+        _ => Err(reporter.take()),
+    }
+}
+
+pub fn compile_to(
+    reporter: &Reporter,
+    source: &str,
+    options: &Options,
+    stage: Stage,
+) -> Option<Output> {
+    driver::compile(reporter, source, options, stage)
+}
+
+pub fn check(source: &str) -> Vec<Diagnostic> {
+    check_with(source, &Options::default())
+}
+
+pub fn check_with(source: &str, options: &Options) -> Vec<Diagnostic> {
+    let reporter = collector(source, options);
+    driver::compile(&reporter, source, options, Stage::Check);
+
+    reporter.take()
+}
+
+pub fn symbols(source: &str) -> Result<Vec<Definition>, Vec<Diagnostic>> {
+    let options = Options::default();
+    let reporter = collector(source, &options);
+
+    match driver::compile(&reporter, source, &options, Stage::Resolve) {
+        Some(Output::Symbols(symbols)) => Ok(symbols),
+        _ => Err(reporter.take()),
+    }
+}
+
+fn collector(source: &str, options: &Options) -> Reporter {
+    Reporter::collector(source, options.lints.clone(), options.max_errors)
+}
@@ -0,0 +1,310 @@
+use std::io::{self, BufRead, Write};
+use std::process;
+
+use blaze::{Diagnostic, Options, Severity};
+
+use crate::json::Json;
+
+const MAX_LENGTH: usize = 64 * 1024 * 1024;
+
+pub struct Server {
+    shutdown: bool,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self { shutdown: false }
+    }
+
+    pub fn run(&mut self) -> io::Result<()> {
+        let stdin = io::stdin();
+        let mut input = stdin.lock();
+
+        while let Some(message) = receive(&mut input)? {
+            if let Some(message) = Json::parse(&message) {
+                self.handle(&message)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle(&mut self, message: &Json) -> io::Result<()> {
+        let id = message.get("id").cloned();
+        let params = message.get("params").unwrap_or(&Json::Null);
+        let document = params.get("textDocument").unwrap_or(&Json::Null);
+        let uri = document.get("uri").and_then(Json::as_str).unwrap_or("");
+
+        match message.get("method").and_then(Json::as_str) {
+            Some("initialize") => {
+                let capabilities = Json::object(vec![(
+                    "capabilities",
+                    Json::object(vec![("textDocumentSync", Json::Number(1.0))]),
+                )]);
+
+                respond(id, capabilities)
+            }
+            Some("shutdown") => {
+                self.shutdown = true;
+                respond(id, Json::Null)
+            }
+            Some("exit") => process::exit(if self.shutdown { 0 } else { 1 }),
+            Some("textDocument/didOpen") => {
+                let text = document.get("text").and_then(Json::as_str);
+                publish(uri, diagnostics(text.unwrap_or("")))
+            }
+            Some("textDocument/didChange") => {
+                let text = params
+                    .get("contentChanges")
+                    .and_then(Json::as_array)
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.get("text"))
+                    .and_then(Json::as_str);
+
+                match text {
+                    Some(text) => publish(uri, diagnostics(text)),
+                    None => Ok(()),
+                }
+            }
+            Some("textDocument/didClose") => publish(uri, Vec::new()),
+            _ => match id {
+                Some(id) => send(&Json::object(vec![
+                    ("jsonrpc", Json::String("2.0".to_string())),
+                    ("id", id),
+                    (
+                        "error",
+                        Json::object(vec![
+                            ("code", Json::Number(-32601.0)),
+                            (
+                                "message",
+                                Json::String("Method not found.".to_string()),
+                            ),
+                        ]),
+                    ),
+                ])),
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn position(line: usize, character: usize) -> Json {
+    Json::object(vec![
+        ("line", Json::Number(line as f64)),
+        ("character", Json::Number(character as f64)),
+    ])
+}
+
+// Editors count characters in UTF-16 code units, so a position is measured
+// from the start of its line in those rather than in bytes or chars.
+fn convert(source: &str, diagnostic: Diagnostic) -> Json {
+    let line = diagnostic.line.saturating_sub(1);
+    let (offset, text) = source
+        .split('\n')
+        .scan(0, |offset, text| {
+            let start = *offset;
+            *offset += text.len() + 1;
+
+            Some((start, text.strip_suffix('\r').unwrap_or(text)))
+        })
+        .nth(line)
+        .unwrap_or((0, ""));
+
+    let units = |end: usize| {
+        let end = end.saturating_sub(offset).min(text.len());
+
+        text.get(..end)
+            .map_or(0, |text| text.encode_utf16().count())
+    };
+
+    let (start, end) = match diagnostic.span {
+        Some(span) => {
+            (units(span.start), units(span.end).max(units(span.start)))
+        }
+        None => (0, units(offset + text.len())),
+    };
+
+    let severity = match diagnostic.severity {
+        Severity::Error => 1.0,
+        Severity::Warning => 2.0,
+        Severity::Note => 3.0,
+    };
+
+    let message = match diagnostic.note {
+        Some(note) => format!("{}\n{}", diagnostic.message, note),
+        None => diagnostic.message,
+    };
+
+    let code = match diagnostic.code {
+        Some(code) => Json::String(code.name().to_string()),
+        None => Json::Null,
+    };
+
+    Json::object(vec![
+        (
+            "range",
+            Json::object(vec![
+                ("start", position(line, start)),
+                ("end", position(line, end)),
+            ]),
+        ),
+        ("severity", Json::Number(severity)),
+        ("code", code),
+        ("source", Json::String("blaze".to_string())),
+        ("message", Json::String(message)),
+    ])
+}
+
+fn diagnostics(text: &str) -> Vec<Json> {
+    let mut options = Options::new();
+    options.fail_fast = false;
+
+    let diagnostics = blaze::check_with(text, &options);
+
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| convert(text, diagnostic))
+        .collect()
+}
+
+fn publish(uri: &str, diagnostics: Vec<Json>) -> io::Result<()> {
+    notify(
+        "textDocument/publishDiagnostics",
+        Json::object(vec![
+            ("uri", Json::String(uri.to_string())),
+            ("diagnostics", Json::Array(diagnostics)),
+        ]),
+    )
+}
+
+fn respond(id: Option<Json>, result: Json) -> io::Result<()> {
+    send(&Json::object(vec![
+        ("jsonrpc", Json::String("2.0".to_string())),
+        ("id", id.unwrap_or(Json::Null)),
+        ("result", result),
+    ]))
+}
+
+fn notify(method: &str, params: Json) -> io::Result<()> {
+    send(&Json::object(vec![
+        ("jsonrpc", Json::String("2.0".to_string())),
+        ("method", Json::String(method.to_string())),
+        ("params", params),
+    ]))
+}
+
+fn send(message: &Json) -> io::Result<()> {
+    let body = message.to_string();
+    let stdout = io::stdout();
+    let mut output = stdout.lock();
+
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+fn receive(input: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut length = None;
+
+    loop {
+        let mut header = String::new();
+
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+
+        let header = header.trim();
+
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse().ok();
+        }
+    }
+
+    let length = length.unwrap_or(0);
+
+    if length > MAX_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message of {} bytes is too long", length),
+        ));
+    }
+
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+
+    Ok(Some(String::from_utf8_lossy(&body).into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blaze::Span;
+
+    fn range(source: &str, line: usize, start: usize, end: usize) -> Json {
+        let diagnostic = Diagnostic {
+            severity: Severity::Error,
+            line,
+            span: Some(Span {
+                start,
+                end,
+                column: 0,
+            }),
+            code: None,
+            location: String::new(),
+            message: String::new(),
+            note: None,
+            lint: None,
+        };
+
+        convert(source, diagnostic).get("range").cloned().unwrap()
+    }
+
+    fn character(range: &Json, side: &str) -> Option<f64> {
+        range.get(side)?.get("character")?.as_number()
+    }
+
+    #[test]
+    fn reads_framed_messages() {
+        let mut input: &[u8] =
+            b"Content-Length: 2\r\n\r\n{}Content-Length: 4\r\n\r\nnull";
+
+        assert_eq!(receive(&mut input).unwrap(), Some("{}".to_string()));
+        assert_eq!(receive(&mut input).unwrap(), Some("null".to_string()));
+        assert_eq!(receive(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_oversized_messages() {
+        let header = format!("Content-Length: {}\r\n\r\n", MAX_LENGTH + 1);
+        let mut input = header.as_bytes();
+
+        assert!(receive(&mut input).is_err());
+    }
+
+    #[test]
+    fn counts_characters_in_utf16() {
+        let source = "let a = \"\u{1F600}\"\nlet b = c";
+        let range = range(source, 1, 8, 14);
+
+        assert_eq!(character(&range, "start"), Some(8.0));
+        assert_eq!(character(&range, "end"), Some(12.0));
+    }
+
+    #[test]
+    fn measures_from_the_start_of_the_line() {
+        let source = "let \u{e9} = 1\r\nlet b = \u{e9}\u{e9}";
+        let range = range(source, 2, 20, 24);
+
+        assert_eq!(character(&range, "start"), Some(8.0));
+        assert_eq!(character(&range, "end"), Some(10.0));
+    }
+}
@@ -0,0 +1,595 @@
+use std::convert::TryFrom;
+
+use crate::expr::{self, Expr};
+use crate::kind::Kind;
+use crate::stmt::{self, Stmt};
+use crate::value::{Suffix, Value};
+
+#[derive(Clone, Copy)]
+enum Constant {
+    Integer(i32),
+    Float(f64),
+    Bool(bool),
+}
+
+fn constant(expr: &Expr) -> Option<(Constant, Option<Suffix>)> {
+    match expr {
+        Expr::Grouping(expr) => constant(&expr.expression),
+        Expr::Literal(expr) => match &expr.value {
+            Value::True => Some((Constant::Bool(true), None)),
+            Value::False => Some((Constant::Bool(false), None)),
+            Value::Number(digits, suffix) => {
+                if *suffix == Some(Suffix::Float)
+                    || digits.contains(['.', 'e', 'E'])
+                {
+                    let value = digits.parse().ok()?;
+                    Some((Constant::Float(value), *suffix))
+                } else {
+                    let value = digits.parse().ok()?;
+                    Some((Constant::Integer(value), *suffix))
+                }
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn literal(constant: Constant, suffix: Option<Suffix>) -> Option<Expr> {
+    let digits = match constant {
+        Constant::Bool(true) => return Some(Expr::new_literal(Value::True)),
+        Constant::Bool(false) => return Some(Expr::new_literal(Value::False)),
+        Constant::Integer(value) => value.to_string(),
+        Constant::Float(value) if value.is_finite() => format!("{:?}", value),
+        Constant::Float(_) => return None,
+    };
+
+    let negative = digits.starts_with('-');
+    let literal = Expr::new_literal(Value::Number(digits, suffix));
+
+    if negative {
+        Some(Expr::new_grouping(literal))
+    } else {
+        Some(literal)
+    }
+}
+
+fn binary(kind: Kind, left: Constant, right: Constant) -> Option<Constant> {
+    use Constant::*;
+
+    let value = match (left, right) {
+        (Integer(left), Integer(right)) => match kind {
+            Kind::Plus => Integer(left.checked_add(right)?),
+            Kind::Minus => Integer(left.checked_sub(right)?),
+            Kind::Star => Integer(left.checked_mul(right)?),
+            Kind::Slash => Integer(left.checked_div(right)?),
+            Kind::Amp => Integer(left & right),
+            Kind::Bar => Integer(left | right),
+            Kind::Caret => Integer(left ^ right),
+            Kind::LessLess => {
+                Integer(left.checked_shl(u32::try_from(right).ok()?)?)
+            }
+            Kind::GreaterGreater => {
+                Integer(left.checked_shr(u32::try_from(right).ok()?)?)
+            }
+            Kind::EqualEqual => Bool(left == right),
+            Kind::BangEqual => Bool(left != right),
+            Kind::Less => Bool(left < right),
+            Kind::LessEqual => Bool(left <= right),
+            Kind::Greater => Bool(left > right),
+            Kind::GreaterEqual => Bool(left >= right),
+            _ => return None,
+        },
+        (Float(left), Float(right)) => match kind {
+            Kind::Plus => Float(left + right),
+            Kind::Minus => Float(left - right),
+            Kind::Star => Float(left * right),
+            Kind::Slash => Float(left / right),
+            Kind::EqualEqual => Bool(left == right),
+            Kind::BangEqual => Bool(left != right),
+            Kind::Less => Bool(left < right),
+            Kind::LessEqual => Bool(left <= right),
+            Kind::Greater => Bool(left > right),
+            Kind::GreaterEqual => Bool(left >= right),
+            _ => return None,
+        },
+        (Bool(left), Bool(right)) => match kind {
+            Kind::EqualEqual => Bool(left == right),
+            Kind::BangEqual => Bool(left != right),
+            Kind::Amp => Bool(left & right),
+            Kind::Bar => Bool(left | right),
+            Kind::Caret => Bool(left ^ right),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+pub struct Optimizer;
+
+impl Optimizer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn optimize(&mut self, statements: &[Stmt]) -> Vec<Stmt> {
+        statements
+            .iter()
+            .map(|statement| statement.accept(self))
+            .collect()
+    }
+
+    fn expressions(&mut self, expressions: &[Expr]) -> Vec<Expr> {
+        expressions
+            .iter()
+            .map(|expression| expression.accept(self))
+            .collect()
+    }
+}
+
+impl Default for Optimizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl expr::Visitor for Optimizer {
+    type Result = Expr;
+
+    fn visit_conditional_expr(
+        &mut self,
+        expr: &expr::Conditional,
+    ) -> Self::Result {
+        let condition = expr.condition.accept(self);
+        let then_branch = expr.then_branch.accept(self);
+        let else_branch = expr.else_branch.accept(self);
+
+        match constant(&condition) {
+            Some((Constant::Bool(true), _)) => then_branch,
+            Some((Constant::Bool(false), _)) => else_branch,
+            _ => Expr::new_conditional(condition, then_branch, else_branch),
+        }
+    }
+
+    fn visit_logical_expr(&mut self, expr: &expr::Logical) -> Self::Result {
+        let left = expr.left.accept(self);
+        let right = expr.right.accept(self);
+
+        match (expr.operator.kind, constant(&left)) {
+            (Kind::AmpAmp, Some((Constant::Bool(true), _)))
+            | (Kind::BarBar, Some((Constant::Bool(false), _))) => right,
+            (Kind::AmpAmp, Some((Constant::Bool(false), _)))
+            | (Kind::BarBar, Some((Constant::Bool(true), _))) => left,
+            _ => Expr::new_logical(left, expr.operator.clone(), right),
+        }
+    }
+
+    fn visit_binary_expr(&mut self, expr: &expr::Binary) -> Self::Result {
+        let left = expr.left.accept(self);
+        let right = expr.right.accept(self);
+
+        let folded = match (constant(&left), constant(&right)) {
+            (Some((left, left_suffix)), Some((right, right_suffix))) => {
+                binary(expr.operator.kind, left, right).and_then(|value| {
+                    literal(value, left_suffix.or(right_suffix))
+                })
+            }
+            _ => None,
+        };
+
+        match folded {
+            Some(folded) => folded,
+            None => Expr::new_binary(left, expr.operator.clone(), right),
+        }
+    }
+
+    fn visit_loop_expr(&mut self, expr: &expr::Loop) -> Self::Result {
+        Expr::new_loop(expr.body.accept(self))
+    }
+
+    fn visit_cast_expr(&mut self, expr: &expr::Cast) -> Self::Result {
+        Expr::new_cast(expr.value.accept(self), expr.target.clone())
+    }
+
+    fn visit_unary_expr(&mut self, expr: &expr::Unary) -> Self::Result {
+        let right = expr.right.accept(self);
+
+        let folded = match (expr.operator.kind, constant(&right)) {
+            (Kind::Minus, Some((Constant::Integer(value), suffix))) => value
+                .checked_neg()
+                .and_then(|value| literal(Constant::Integer(value), suffix)),
+            (Kind::Minus, Some((Constant::Float(value), suffix))) => {
+                literal(Constant::Float(-value), suffix)
+            }
+            (Kind::Bang, Some((Constant::Integer(value), suffix))) => {
+                literal(Constant::Integer(!value), suffix)
+            }
+            (Kind::Bang, Some((Constant::Bool(value), suffix))) => {
+                literal(Constant::Bool(!value), suffix)
+            }
+            _ => None,
+        };
+
+        match folded {
+            Some(folded) => folded,
+            None => Expr::new_unary(expr.operator.clone(), right),
+        }
+    }
+
+    fn visit_call_expr(&mut self, expr: &expr::Call) -> Self::Result {
+        Expr::new_call(
+            expr.callee.accept(self),
+            self.expressions(&expr.arguments),
+        )
+    }
+
+    fn visit_get_expr(&mut self, expr: &expr::Get) -> Self::Result {
+        Expr::new_get(expr.object.accept(self), expr.name.clone())
+    }
+
+    fn visit_set_expr(&mut self, expr: &expr::Set) -> Self::Result {
+        Expr::new_set(
+            expr.object.accept(self),
+            expr.name.clone(),
+            expr.value.accept(self),
+        )
+    }
+
+    fn visit_index_expr(&mut self, expr: &expr::Index) -> Self::Result {
+        Expr::new_index(expr.object.accept(self), expr.index.accept(self))
+    }
+
+    fn visit_set_index_expr(&mut self, expr: &expr::SetIndex) -> Self::Result {
+        Expr::new_set_index(
+            expr.object.accept(self),
+            expr.index.accept(self),
+            expr.value.accept(self),
+        )
+    }
+
+    fn visit_grouping_expr(&mut self, expr: &expr::Grouping) -> Self::Result {
+        let expression = expr.expression.accept(self);
+
+        match expression {
+            Expr::Literal(_) | Expr::Grouping(_) => expression,
+            _ => Expr::new_grouping(expression),
+        }
+    }
+
+    fn visit_array_expr(&mut self, expr: &expr::Array) -> Self::Result {
+        Expr::new_array(self.expressions(&expr.elements))
+    }
+
+    fn visit_map_expr(&mut self, expr: &expr::Map) -> Self::Result {
+        let entries = expr
+            .entries
+            .iter()
+            .map(|(key, value)| (key.accept(self), value.accept(self)))
+            .collect();
+
+        Expr::new_map(entries)
+    }
+
+    fn visit_set_literal_expr(
+        &mut self,
+        expr: &expr::SetLiteral,
+    ) -> Self::Result {
+        let elements = expr
+            .elements
+            .iter()
+            .map(|element| element.accept(self))
+            .collect();
+
+        Expr::new_set_literal(elements)
+    }
+
+    fn visit_struct_expr(&mut self, expr: &expr::Struct) -> Self::Result {
+        let fields = expr
+            .fields
+            .iter()
+            .map(|(name, value)| (name.clone(), value.accept(self)))
+            .collect();
+        let base = expr.base.as_ref().map(|base| base.accept(self));
+
+        Expr::new_struct(expr.name.clone(), fields, base)
+    }
+
+    fn visit_path_expr(&mut self, expr: &expr::Path) -> Self::Result {
+        Expr::Path(Box::new(expr.clone()))
+    }
+
+    fn visit_variable_expr(&mut self, expr: &expr::Variable) -> Self::Result {
+        Expr::Variable(Box::new(expr.clone()))
+    }
+
+    fn visit_literal_expr(&mut self, expr: &expr::Literal) -> Self::Result {
+        Expr::Literal(Box::new(expr.clone()))
+    }
+}
+
+impl stmt::Visitor for Optimizer {
+    type Result = Stmt;
+
+    fn visit_if_stmt(&mut self, stmt: &stmt::If) -> Self::Result {
+        let condition = stmt.condition.accept(self);
+        let then_branch = stmt.then_branch.accept(self);
+        let else_branch =
+            stmt.else_branch.as_ref().map(|branch| branch.accept(self));
+
+        let line = stmt.keyword.line;
+
+        match (constant(&condition), then_branch, else_branch) {
+            (Some((Constant::Bool(true), _)), branch, _)
+            | (Some((Constant::Bool(false), _)), _, Some(branch)) => {
+                match branch {
+                    Stmt::Block(block) => {
+                        Stmt::new_block(line, block.statements)
+                    }
+                    branch => Stmt::new_block(line, vec![branch]),
+                }
+            }
+            (Some((Constant::Bool(false), _)), _, None) => {
+                Stmt::new_block(line, Vec::new())
+            }
+            (_, then_branch, else_branch) => Stmt::new_if(
+                stmt.keyword.clone(),
+                condition,
+                then_branch,
+                else_branch,
+            ),
+        }
+    }
+
+    fn visit_match_stmt(&mut self, stmt: &stmt::Match) -> Self::Result {
+        let arms = stmt
+            .arms
+            .iter()
+            .map(|(pattern, guard, body)| {
+                let guard = guard.as_ref().map(|guard| guard.accept(self));
+
+                (pattern.clone(), guard, body.accept(self))
+            })
+            .collect();
+
+        Stmt::new_match(stmt.keyword.clone(), stmt.value.accept(self), arms)
+    }
+
+    fn visit_function_stmt(&mut self, stmt: &stmt::Function) -> Self::Result {
+        Stmt::new_function(
+            stmt.name.clone(),
+            stmt.receiver,
+            stmt.parameters.clone(),
+            stmt.output.clone(),
+            stmt.body.accept(self),
+            stmt.doc.clone(),
+            stmt.deprecated.clone(),
+        )
+    }
+
+    fn visit_test_stmt(&mut self, stmt: &stmt::Test) -> Self::Result {
+        Stmt::new_test(
+            stmt.keyword.clone(),
+            stmt.name.clone(),
+            stmt.body.accept(self),
+        )
+    }
+
+    fn visit_bench_stmt(&mut self, stmt: &stmt::Bench) -> Self::Result {
+        Stmt::new_bench(
+            stmt.keyword.clone(),
+            stmt.name.clone(),
+            stmt.body.accept(self),
+        )
+    }
+
+    fn visit_return_stmt(&mut self, stmt: &stmt::Return) -> Self::Result {
+        Stmt::new_return(
+            stmt.keyword.clone(),
+            stmt.value.as_ref().map(|value| value.accept(self)),
+        )
+    }
+
+    fn visit_raise_stmt(&mut self, stmt: &stmt::Raise) -> Self::Result {
+        Stmt::new_raise(stmt.keyword.clone(), stmt.value.accept(self))
+    }
+
+    fn visit_try_stmt(&mut self, stmt: &stmt::Try) -> Self::Result {
+        Stmt::new_try(
+            stmt.keyword.clone(),
+            stmt.body.accept(self),
+            stmt.error.clone(),
+            stmt.handler.accept(self),
+        )
+    }
+
+    fn visit_loop_stmt(&mut self, stmt: &stmt::Loop) -> Self::Result {
+        Stmt::new_loop(
+            stmt.keyword.clone(),
+            stmt.label.clone(),
+            stmt.body.accept(self),
+        )
+    }
+
+    fn visit_for_stmt(&mut self, stmt: &stmt::For) -> Self::Result {
+        Stmt::new_for(
+            stmt.label.clone(),
+            stmt.variable.clone(),
+            stmt.start.accept(self),
+            stmt.end.accept(self),
+            stmt.body.accept(self),
+        )
+    }
+
+    fn visit_break_stmt(&mut self, stmt: &stmt::Break) -> Self::Result {
+        Stmt::new_break(
+            stmt.keyword.clone(),
+            stmt.label.clone(),
+            stmt.value.as_ref().map(|value| value.accept(self)),
+        )
+    }
+
+    fn visit_continue_stmt(&mut self, stmt: &stmt::Continue) -> Self::Result {
+        Stmt::Continue(Box::new(stmt.clone()))
+    }
+
+    fn visit_let_stmt(&mut self, stmt: &stmt::Let) -> Self::Result {
+        Stmt::new_let(
+            stmt.name.clone(),
+            stmt.variant.clone(),
+            stmt.initializer
+                .as_ref()
+                .map(|initializer| initializer.accept(self)),
+        )
+    }
+
+    fn visit_const_stmt(&mut self, stmt: &stmt::Const) -> Self::Result {
+        Stmt::new_const(
+            stmt.name.clone(),
+            stmt.variant.clone(),
+            stmt.initializer.accept(self),
+            stmt.doc.clone(),
+            stmt.deprecated.clone(),
+        )
+    }
+
+    fn visit_type_stmt(&mut self, stmt: &stmt::Type) -> Self::Result {
+        Stmt::Type(Box::new(stmt.clone()))
+    }
+
+    fn visit_struct_stmt(&mut self, stmt: &stmt::Struct) -> Self::Result {
+        Stmt::Struct(Box::new(stmt.clone()))
+    }
+
+    fn visit_enum_stmt(&mut self, stmt: &stmt::Enum) -> Self::Result {
+        Stmt::Enum(Box::new(stmt.clone()))
+    }
+
+    fn visit_impl_stmt(&mut self, stmt: &stmt::Impl) -> Self::Result {
+        Stmt::new_impl(stmt.name.clone(), self.optimize(&stmt.methods))
+    }
+
+    fn visit_block_stmt(&mut self, stmt: &stmt::Block) -> Self::Result {
+        Stmt::new_block(stmt.line, self.optimize(&stmt.statements))
+    }
+
+    fn visit_assignment_stmt(
+        &mut self,
+        stmt: &stmt::Assignment,
+    ) -> Self::Result {
+        Stmt::new_assignment(stmt.name.clone(), stmt.value.accept(self))
+    }
+
+    fn visit_expression_stmt(
+        &mut self,
+        stmt: &stmt::Expression,
+    ) -> Self::Result {
+        Stmt::new_expression(stmt.expression.accept(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Optimizer;
+    use crate::checker::Checker;
+    use crate::generator::Generator;
+    use crate::parser::Parser;
+    use crate::scanner::{Scanner, Semicolons};
+    use crate::stmt::Stmt;
+
+    fn optimize(source: &str) -> Vec<Stmt> {
+        let (tokens, _) = Scanner::new(source, Semicolons::Automatic).scan();
+        let (statements, errors) = Parser::new(tokens).parse();
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        Optimizer::new().optimize(&statements)
+    }
+
+    fn body(source: &str) -> Vec<Stmt> {
+        let source = format!("fn main() {{\n{}\n}}", source);
+
+        match optimize(&source).remove(0) {
+            Stmt::Function(function) => match function.body {
+                Stmt::Block(block) => block.statements,
+                _ => panic!("expected a block"),
+            },
+            _ => panic!("expected a function"),
+        }
+    }
+
+    fn fold(expression: &str) -> String {
+        match body(expression).remove(0) {
+            Stmt::Expression(stmt) => {
+                stmt.expression.accept(&mut Generator::default())
+            }
+            _ => panic!("expected an expression"),
+        }
+    }
+
+    #[test]
+    fn folds_arithmetic() {
+        assert_eq!(fold("1 + 2 * 3"), "7");
+        assert_eq!(fold("(10 - 4) / 3"), "2");
+        assert_eq!(fold("1.5 * 2.0"), "3.0");
+        assert_eq!(fold("2 - 5"), "(-3)");
+        assert_eq!(fold("-(4)"), "(-4)");
+    }
+
+    #[test]
+    fn folds_bitwise_and_shifts() {
+        assert_eq!(fold("6 & 3 | 8"), "10");
+        assert_eq!(fold("1 << 4 >> 2"), "4");
+        assert_eq!(fold("5 ^ 1"), "4");
+    }
+
+    #[test]
+    fn folds_short_circuit_booleans() {
+        assert_eq!(fold("true && x"), "x");
+        assert_eq!(fold("false && x"), "false");
+        assert_eq!(fold("false || x"), "x");
+        assert_eq!(fold("true || x"), "true");
+        assert_eq!(fold("1 < 2"), "true");
+    }
+
+    #[test]
+    fn skips_overflow() {
+        assert_eq!(fold("2147483647 + 1"), "(2147483647 + 1)");
+        assert_eq!(fold("1 / 0"), "(1 / 0)");
+        assert_eq!(fold("1 << 40"), "(1 << 40)");
+        assert_eq!(fold("8 >> 32"), "(8 >> 32)");
+        assert_eq!(fold("-(-2147483648)"), "(-((-2147483648)))");
+    }
+
+    #[test]
+    fn removes_dead_branches() {
+        let statements = body("if false {\nx()\n} else {\ny()\nz()\n}");
+
+        match &statements[..] {
+            [Stmt::Block(block)] => {
+                assert_eq!(block.line, 2);
+                assert_eq!(block.statements.len(), 2);
+            }
+            _ => panic!("expected the else branch"),
+        }
+
+        match &body("\nif 1 > 2 {\nx()\n}")[..] {
+            [Stmt::Block(block)] => {
+                assert_eq!(block.line, 3);
+                assert!(block.statements.is_empty());
+            }
+            _ => panic!("expected an empty block"),
+        }
+    }
+
+    #[test]
+    fn folded_branches_keep_their_line() {
+        let source = "fn main() {\n\n    if true {\n        print(1)\n    }\n}";
+        let statements = optimize(source);
+        let (program, _) = Checker::new().check(&statements);
+        let (output, errors) = Generator::new().generate(&program);
+
+        assert!(errors.is_empty());
+        assert!(output.contains("\n// line 3\n"), "{}", output);
+    }
+}
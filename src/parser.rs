@@ -1,56 +1,218 @@
+use std::mem;
+use std::num::IntErrorKind;
+
+use crate::code::Code;
 use crate::error::SyntaxError;
 use crate::expr::Expr;
 use crate::kind::Kind;
-use crate::stmt::Stmt;
+use crate::pattern::Pattern;
+use crate::scanner::KEYWORDS;
+use crate::stmt::{Deprecated, Stmt};
+use crate::suggest::suggest;
 use crate::token::Token;
-use crate::value::Value;
+use crate::value::{Suffix, Value};
 use crate::variant::Variant;
 
+// Nested blocks, parentheses and the like recurse in the parser, but chains
+// of operators and postfix calls are parsed in a loop. Both make the tree
+// deeper for the passes after it, so chains get a budget of their own.
+const MAX_DEPTH: usize = 128;
+const MAX_LENGTH: usize = 1024;
+
 #[derive(Debug)]
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    depth: usize,
+    length: usize,
+    structs: bool,
+    negated: bool,
+    labels: Vec<String>,
+    errors: Vec<SyntaxError>,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, current: 0 }
+        Self {
+            tokens,
+            current: 0,
+            depth: 0,
+            length: 0,
+            structs: true,
+            negated: false,
+            labels: Vec::new(),
+            errors: Vec::new(),
+        }
     }
 
     pub fn parse(&mut self) -> (Vec<Stmt>, Vec<SyntaxError>) {
         let mut statements = Vec::new();
-        let mut errors = Vec::new();
 
         while !self.is_at_end() {
             match self.declaration() {
                 Ok(statement) => statements.push(statement),
                 Err(error) => {
                     self.synchronize();
-                    errors.push(error);
+                    self.errors.push(error);
                 }
             }
         }
 
-        (statements, errors)
+        (statements, mem::take(&mut self.errors))
     }
 
     fn declaration(&mut self) -> Result<Stmt, SyntaxError> {
+        let doc = self.peek().doc.clone();
+        let deprecated = if self.compare(&[Kind::At]) {
+            Some(self.deprecation()?)
+        } else {
+            None
+        };
+
         if self.compare(&[Kind::Fn]) {
-            self.function_declaration()
+            self.function_declaration(doc, deprecated)
+        } else if self.compare(&[Kind::Const]) {
+            self.const_statement(doc, deprecated)
+        } else if self.compare(&[Kind::Let]) {
+            if deprecated.is_some() {
+                Err(SyntaxError {
+                    code: Code::InvalidAnnotation,
+                    ..self.error(self.previous(), "Can't deprecate a variable.")
+                })
+            } else {
+                self.let_statement()
+            }
         } else if self.compare(&[Kind::Type]) {
-            self.type_declaration()
+            self.type_declaration(doc, deprecated)
+        } else if self.compare(&[Kind::Struct]) {
+            self.struct_declaration(doc, deprecated)
+        } else if self.compare(&[Kind::Enum]) {
+            self.enum_declaration(doc, deprecated)
+        } else if self.compare(&[Kind::Impl]) {
+            if deprecated.is_some() {
+                Err(SyntaxError {
+                    code: Code::InvalidAnnotation,
+                    ..self.error(self.previous(), "Can't deprecate an impl.")
+                })
+            } else {
+                self.impl_declaration()
+            }
+        } else if self.check(Kind::Identifier)
+            && matches!(self.peek().lexeme.as_str(), "test" | "bench")
+            && self.check_next(Kind::String)
+        {
+            self.advance();
+
+            if deprecated.is_some() {
+                Err(SyntaxError {
+                    code: Code::InvalidAnnotation,
+                    ..self.error(
+                        self.previous(),
+                        &format!(
+                            "Can't deprecate a {}.",
+                            self.previous().lexeme
+                        ),
+                    )
+                })
+            } else {
+                self.test_declaration()
+            }
         } else {
-            Err(self.error(self.peek(), "Expect function or type declaration."))
+            let mut error = self.error(self.peek(), "Expect declaration.");
+            error.note = self.misspelled(self.peek());
+
+            Err(error)
         }
     }
 
-    fn function_declaration(&mut self) -> Result<Stmt, SyntaxError> {
+    fn test_declaration(&mut self) -> Result<Stmt, SyntaxError> {
+        let keyword = self.previous().clone();
+        let name = self
+            .consume(Kind::String, &format!("Expect {} name.", keyword.lexeme))?
+            .clone();
+
+        self.consume(
+            Kind::LeftBrace,
+            &format!("Expect '{{' before {} body.", keyword.lexeme),
+        )?;
+
+        let body = self.block_statement()?;
+
+        if keyword.lexeme == "bench" {
+            Ok(Stmt::new_bench(keyword, name, body))
+        } else {
+            Ok(Stmt::new_test(keyword, name, body))
+        }
+    }
+
+    fn deprecation(&mut self) -> Result<Deprecated, SyntaxError> {
+        let name = self
+            .consume(Kind::Identifier, "Expect annotation name.")?
+            .clone();
+
+        if name.lexeme != "deprecated" {
+            return Err(SyntaxError {
+                code: Code::InvalidAnnotation,
+                ..self.error(&name, Code::InvalidAnnotation.template())
+            });
+        }
+
+        let note = if self.compare(&[Kind::LeftParen]) {
+            let note = self
+                .consume(Kind::String, "Expect deprecation note.")?
+                .lexeme
+                .clone();
+
+            self.consume(Kind::RightParen, "Expect ')' after note.")?;
+
+            Some(note[1..note.len() - 1].to_string())
+        } else {
+            None
+        };
+
+        self.compare(&[Kind::Semicolon]);
+
+        Ok(Deprecated { note })
+    }
+
+    fn function_declaration(
+        &mut self,
+        doc: Option<String>,
+        deprecated: Option<Deprecated>,
+    ) -> Result<Stmt, SyntaxError> {
+        self.function(doc, deprecated, false)
+    }
+
+    fn function(
+        &mut self,
+        doc: Option<String>,
+        deprecated: Option<Deprecated>,
+        method: bool,
+    ) -> Result<Stmt, SyntaxError> {
         let name = self
             .consume(Kind::Identifier, "Expect function name.")?
             .clone();
 
         self.consume(Kind::LeftParen, "Expect '(' after function name.")?;
 
+        let receiver =
+            self.check(Kind::Identifier) && self.peek().lexeme == "self";
+
+        if receiver {
+            if !method {
+                return Err(self.error(
+                    self.peek(),
+                    "Can't use 'self' outside of an impl.",
+                ));
+            }
+
+            self.advance();
+
+            if !self.check(Kind::RightParen) {
+                self.consume(Kind::Comma, "Expect ',' after 'self'.")?;
+            }
+        }
+
         let mut parameters = Vec::new();
 
         if !self.check(Kind::RightParen) {
@@ -89,42 +251,169 @@ impl Parser {
 
         self.consume(Kind::LeftBrace, "Expect '{' before function body.")?;
 
-        let body = self.block_statement()?;
+        let labels = mem::take(&mut self.labels);
+        let body = self.block_statement();
+        self.labels = labels;
+        let body = body?;
+
+        Ok(Stmt::new_function(
+            name, receiver, parameters, output, body, doc, deprecated,
+        ))
+    }
+
+    fn impl_declaration(&mut self) -> Result<Stmt, SyntaxError> {
+        let name = self.consume(Kind::Identifier, "Expect type name.")?.clone();
+        self.consume(Kind::LeftBrace, "Expect '{' after type name.")?;
+
+        let mut methods = Vec::new();
+
+        while !self.is_at_end() && !self.check(Kind::RightBrace) {
+            let doc = self.peek().doc.clone();
+            let deprecated = if self.compare(&[Kind::At]) {
+                Some(self.deprecation()?)
+            } else {
+                None
+            };
+
+            self.consume(Kind::Fn, "Expect method.")?;
+            methods.push(self.function(doc, deprecated, true)?);
+        }
+
+        self.consume(Kind::RightBrace, "Expect '}' after methods.")?;
 
-        Ok(Stmt::new_function(name, parameters, output, body))
+        Ok(Stmt::new_impl(name, methods))
     }
 
-    fn type_declaration(&mut self) -> Result<Stmt, SyntaxError> {
+    fn type_declaration(
+        &mut self,
+        doc: Option<String>,
+        deprecated: Option<Deprecated>,
+    ) -> Result<Stmt, SyntaxError> {
         let name = self.consume(Kind::Identifier, "Expect type name.")?.clone();
         self.consume(Kind::Equal, "Expect '=' after type name.")?;
         let variant = self.variant()?;
         self.consume(Kind::Semicolon, "Expect ';' after type.")?;
 
-        Ok(Stmt::new_type(name, variant))
+        Ok(Stmt::new_type(name, variant, doc, deprecated))
+    }
+
+    fn struct_declaration(
+        &mut self,
+        doc: Option<String>,
+        deprecated: Option<Deprecated>,
+    ) -> Result<Stmt, SyntaxError> {
+        let name = self
+            .consume(Kind::Identifier, "Expect struct name.")?
+            .clone();
+
+        self.consume(Kind::LeftBrace, "Expect '{' after struct name.")?;
+
+        let mut fields = Vec::new();
+
+        while !self.is_at_end() && !self.check(Kind::RightBrace) {
+            let name = self
+                .consume(Kind::Identifier, "Expect field name.")?
+                .clone();
+
+            self.consume(Kind::Colon, "Expect ':' after field name.")?;
+            let variant = self.variant()?;
+            self.separate("Expect ',' after field.")?;
+
+            fields.push((name, variant));
+        }
+
+        self.consume(Kind::RightBrace, "Expect '}' after fields.")?;
+
+        Ok(Stmt::new_struct(name, fields, doc, deprecated))
+    }
+
+    fn enum_declaration(
+        &mut self,
+        doc: Option<String>,
+        deprecated: Option<Deprecated>,
+    ) -> Result<Stmt, SyntaxError> {
+        let name = self.consume(Kind::Identifier, "Expect enum name.")?.clone();
+        self.consume(Kind::LeftBrace, "Expect '{' after enum name.")?;
+
+        let mut variants = Vec::new();
+
+        while !self.is_at_end() && !self.check(Kind::RightBrace) {
+            let name = self
+                .consume(Kind::Identifier, "Expect variant name.")?
+                .clone();
+
+            let mut fields = Vec::new();
+
+            if self.compare(&[Kind::LeftParen]) {
+                if !self.check(Kind::RightParen) {
+                    fields.push(self.variant()?);
+
+                    while self.compare(&[Kind::Comma]) {
+                        fields.push(self.variant()?);
+                    }
+
+                    self.compare(&[Kind::Comma]);
+                }
+
+                self.consume(Kind::RightParen, "Expect ')' after fields.")?;
+            }
+
+            self.separate("Expect ',' after variant.")?;
+
+            variants.push((name, fields));
+        }
+
+        self.consume(Kind::RightBrace, "Expect '}' after variants.")?;
+
+        Ok(Stmt::new_enum(name, variants, doc, deprecated))
     }
 
     fn statement(&mut self) -> Result<Stmt, SyntaxError> {
+        self.nest()?;
+        let statement = self.nested_statement();
+        self.depth -= 1;
+
+        statement
+    }
+
+    fn nested_statement(&mut self) -> Result<Stmt, SyntaxError> {
         if self.compare(&[Kind::If]) {
             self.if_statement()
+        } else if self.compare(&[Kind::Match]) {
+            self.match_statement()
         } else if self.compare(&[Kind::Return]) {
             self.return_statement()
+        } else if self.compare(&[Kind::Raise]) {
+            self.raise_statement()
+        } else if self.check(Kind::Try) && self.check_next(Kind::LeftBrace) {
+            self.advance();
+            self.try_statement()
         } else if self.compare(&[Kind::Loop]) {
-            self.loop_statement()
+            self.loop_statement(None)
+        } else if self.compare(&[Kind::For]) {
+            self.for_statement(None)
         } else if self.compare(&[Kind::Break]) {
             self.break_statement()
         } else if self.compare(&[Kind::Continue]) {
             self.continue_statement()
         } else if self.compare(&[Kind::Let]) {
             self.let_statement()
+        } else if self.compare(&[Kind::Const]) {
+            self.const_statement(None, None)
+        } else if self.compare(&[Kind::Fn]) {
+            self.function_declaration(None, None)
         } else if self.compare(&[Kind::LeftBrace]) {
             self.block_statement()
+        } else if self.check(Kind::Identifier) && self.check_next(Kind::Colon) {
+            self.labeled_statement()
         } else {
             self.assignment_statement()
         }
     }
 
     fn if_statement(&mut self) -> Result<Stmt, SyntaxError> {
-        let condition = self.expression()?;
+        let keyword = self.previous().clone();
+        let condition = self.condition()?;
         self.consume(Kind::LeftBrace, "Expect block after 'if'.")?;
 
         let then_branch = self.block_statement()?;
@@ -136,38 +425,164 @@ impl Parser {
             None
         };
 
-        Ok(Stmt::new_if(condition, then_branch, else_branch))
+        Ok(Stmt::new_if(keyword, condition, then_branch, else_branch))
+    }
+
+    fn match_statement(&mut self) -> Result<Stmt, SyntaxError> {
+        let keyword = self.previous().clone();
+        let value = self.condition()?;
+        self.consume(Kind::LeftBrace, "Expect '{' after match value.")?;
+
+        let mut arms = Vec::new();
+
+        while !self.is_at_end() && !self.check(Kind::RightBrace) {
+            let pattern = self.pattern()?;
+            let guard = if self.compare(&[Kind::If]) {
+                Some(self.expression()?)
+            } else {
+                None
+            };
+
+            self.consume(Kind::EqualGreater, "Expect '=>' after pattern.")?;
+            let body = self.statement()?;
+
+            arms.push((pattern, guard, body));
+        }
+
+        self.consume(Kind::RightBrace, "Expect '}' after match arms.")?;
+
+        Ok(Stmt::new_match(keyword, value, arms))
+    }
+
+    fn try_statement(&mut self) -> Result<Stmt, SyntaxError> {
+        let keyword = self.previous().clone();
+        self.consume(Kind::LeftBrace, "Expect block after 'try'.")?;
+        let body = self.block_statement()?;
+
+        self.consume(Kind::Catch, "Expect 'catch' after try block.")?;
+
+        let error = if self.check(Kind::Identifier) {
+            Some(self.advance().clone())
+        } else {
+            None
+        };
+
+        self.consume(Kind::LeftBrace, "Expect block after 'catch'.")?;
+        let handler = self.block_statement()?;
+
+        Ok(Stmt::new_try(keyword, body, error, handler))
     }
 
     fn return_statement(&mut self) -> Result<Stmt, SyntaxError> {
-        let value = if self.check(Kind::Semicolon) {
+        let keyword = self.previous().clone();
+        let value = if self.check(Kind::Semicolon)
+            || self.check(Kind::RightBrace)
+            || self.check(Kind::Eof)
+        {
             None
         } else {
             Some(self.expression()?)
         };
 
-        self.consume(Kind::Semicolon, "Expect ';' after return value.")?;
+        self.terminate("Expect ';' after return value.")?;
 
-        Ok(Stmt::new_return(value))
+        Ok(Stmt::new_return(keyword, value))
     }
 
-    fn loop_statement(&mut self) -> Result<Stmt, SyntaxError> {
+    fn raise_statement(&mut self) -> Result<Stmt, SyntaxError> {
+        let keyword = self.previous().clone();
+        let value = self.expression()?;
+
+        self.terminate("Expect ';' after raised value.")?;
+
+        Ok(Stmt::new_raise(keyword, value))
+    }
+
+    fn labeled_statement(&mut self) -> Result<Stmt, SyntaxError> {
+        let label = self.advance().clone();
+        self.advance();
+
+        self.labels.push(label.lexeme.clone());
+
+        let statement = if self.compare(&[Kind::Loop]) {
+            self.loop_statement(Some(label))
+        } else if self.compare(&[Kind::For]) {
+            self.for_statement(Some(label))
+        } else {
+            Err(self.error(self.peek(), "Expect loop after label."))
+        };
+
+        self.labels.pop();
+
+        statement
+    }
+
+    fn loop_statement(
+        &mut self,
+        label: Option<Token>,
+    ) -> Result<Stmt, SyntaxError> {
+        let keyword = self.previous().clone();
+
         self.consume(Kind::LeftBrace, "Expect '{' after 'loop'.")?;
         let body = self.block_statement()?;
 
-        Ok(Stmt::new_loop(body))
+        Ok(Stmt::new_loop(keyword, label, body))
+    }
+
+    fn for_statement(
+        &mut self,
+        label: Option<Token>,
+    ) -> Result<Stmt, SyntaxError> {
+        let variable = self
+            .consume(Kind::Identifier, "Expect loop variable name.")?
+            .clone();
+
+        self.consume(Kind::In, "Expect 'in' after loop variable.")?;
+        let start = self.condition()?;
+        self.consume(Kind::DotDot, "Expect '..' after range start.")?;
+        let end = self.condition()?;
+
+        self.consume(Kind::LeftBrace, "Expect '{' after range.")?;
+        let body = self.block_statement()?;
+
+        Ok(Stmt::new_for(label, variable, start, end, body))
     }
 
     fn break_statement(&mut self) -> Result<Stmt, SyntaxError> {
-        self.consume(Kind::Semicolon, "Expect ';' after 'break'.")?;
+        let keyword = self.previous().clone();
+        let label = self.label();
 
-        Ok(Stmt::new_break())
+        let value = if self.check(Kind::Semicolon)
+            || self.check(Kind::RightBrace)
+            || self.check(Kind::Eof)
+        {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+
+        self.terminate("Expect ';' after 'break'.")?;
+
+        Ok(Stmt::new_break(keyword, label, value))
     }
 
     fn continue_statement(&mut self) -> Result<Stmt, SyntaxError> {
-        self.consume(Kind::Semicolon, "Expect ';' after 'continue'.")?;
+        let keyword = self.previous().clone();
+        let label = self.label();
 
-        Ok(Stmt::new_continue())
+        self.terminate("Expect ';' after 'continue'.")?;
+
+        Ok(Stmt::new_continue(keyword, label))
+    }
+
+    fn label(&mut self) -> Option<Token> {
+        if self.check(Kind::Identifier)
+            && self.labels.contains(&self.peek().lexeme)
+        {
+            Some(self.advance().clone())
+        } else {
+            None
+        }
     }
 
     fn let_statement(&mut self) -> Result<Stmt, SyntaxError> {
@@ -175,33 +590,62 @@ impl Parser {
             .consume(Kind::Identifier, "Expect variable name.")?
             .clone();
 
-        self.consume(Kind::Colon, "Expect variable type.")?;
-        let variant = self.variant()?;
+        let variant = if self.compare(&[Kind::Colon]) {
+            Some(self.variant()?)
+        } else {
+            None
+        };
 
         let initializer = if self.compare(&[Kind::Equal]) {
             Some(self.expression()?)
+        } else if variant.is_none() {
+            return Err(self.error(self.peek(), "Expect variable type."));
         } else {
             None
         };
 
-        self.consume(
-            Kind::Semicolon,
-            "Expect ';' after variable declaration.",
-        )?;
+        self.terminate("Expect ';' after variable declaration.")?;
 
         Ok(Stmt::new_let(name, variant, initializer))
     }
 
+    fn const_statement(
+        &mut self,
+        doc: Option<String>,
+        deprecated: Option<Deprecated>,
+    ) -> Result<Stmt, SyntaxError> {
+        let name = self
+            .consume(Kind::Identifier, "Expect constant name.")?
+            .clone();
+
+        self.consume(Kind::Colon, "Expect constant type.")?;
+        let variant = self.variant()?;
+
+        self.consume(Kind::Equal, "Expect '=' after constant type.")?;
+        let initializer = self.expression()?;
+
+        self.terminate("Expect ';' after constant declaration.")?;
+
+        Ok(Stmt::new_const(name, variant, initializer, doc, deprecated))
+    }
+
     fn block_statement(&mut self) -> Result<Stmt, SyntaxError> {
+        let line = self.previous().line;
         let mut statements = Vec::new();
 
         while !self.is_at_end() && !self.check(Kind::RightBrace) {
-            statements.push(self.statement()?);
+            match self.statement() {
+                Ok(statement) => statements.push(statement),
+                Err(error) => {
+                    self.recover();
+                    self.errors.push(error);
+                }
+            }
         }
 
         self.consume(Kind::RightBrace, "Expect '}' after block.")?;
 
-        Ok(Stmt::new_block(statements))
+        Ok(Stmt::new_block(line, statements))
     }
 
     fn assignment_statement(&mut self) -> Result<Stmt, SyntaxError> {
@@ -211,111 +655,237 @@ impl Parser {
             let equals = self.previous().clone();
             let value = self.expression()?;
 
-            if let Expr::Variable(variable) = expr {
-                self.consume(Kind::Semicolon, "Expect ';' after assignment.")?;
+            match expr {
+                Expr::Variable(variable) => {
+                    self.terminate("Expect ';' after assignment.")?;
 
-                Ok(Stmt::new_assignment(variable.name, value))
-            } else {
-                Err(self.error(&equals, "Invalid assignment target."))
+                    Ok(Stmt::new_assignment(variable.name, value))
+                }
+                Expr::Get(get) => {
+                    self.terminate("Expect ';' after assignment.")?;
+
+                    let get = *get;
+                    let set = Expr::new_set(get.object, get.name, value);
+
+                    Ok(Stmt::new_expression(set))
+                }
+                Expr::Index(index) => {
+                    self.terminate("Expect ';' after assignment.")?;
+
+                    let index = *index;
+                    let set =
+                        Expr::new_set_index(index.object, index.index, value);
+
+                    Ok(Stmt::new_expression(set))
+                }
+                _ => Err(SyntaxError {
+                    code: Code::InvalidAssignment,
+                    ..self.error(&equals, "Invalid assignment target.")
+                }),
             }
         } else {
-            self.consume(Kind::Semicolon, "Expect ';' after expression.")?;
+            if let Err(mut error) =
+                self.terminate("Expect ';' after expression.")
+            {
+                if let Expr::Variable(variable) = &expr {
+                    error.note = self.misspelled(&variable.name);
+                }
+
+                return Err(error);
+            }
 
             Ok(Stmt::new_expression(expr))
         }
     }
 
-    fn expression(&mut self) -> Result<Expr, SyntaxError> {
-        self.or_expression()
+    fn condition(&mut self) -> Result<Expr, SyntaxError> {
+        let structs = mem::replace(&mut self.structs, false);
+        let expr = self.expression();
+        self.structs = structs;
+
+        expr
     }
 
-    fn or_expression(&mut self) -> Result<Expr, SyntaxError> {
-        let mut expr = self.and_expression()?;
+    fn enclosed_expression(&mut self) -> Result<Expr, SyntaxError> {
+        let structs = mem::replace(&mut self.structs, true);
+        let expr = self.expression();
+        self.structs = structs;
 
-        while self.compare(&[Kind::BarBar]) {
-            let operator = self.previous().clone();
-            let right = self.and_expression()?;
+        expr
+    }
 
-            expr = Expr::new_logical(expr, operator, right);
-        }
+    fn expression(&mut self) -> Result<Expr, SyntaxError> {
+        self.nest()?;
+        let expr = self.conditional_expression();
+        self.depth -= 1;
 
-        Ok(expr)
+        expr
     }
 
-    fn and_expression(&mut self) -> Result<Expr, SyntaxError> {
-        let mut expr = self.equality_expression()?;
+    fn conditional_expression(&mut self) -> Result<Expr, SyntaxError> {
+        let expr = self.coalesce_expression()?;
 
-        while self.compare(&[Kind::AmpAmp]) {
-            let operator = self.previous().clone();
-            let right = self.equality_expression()?;
+        if self.compare(&[Kind::Question]) {
+            let then_branch = self.expression()?;
+            self.consume(Kind::Colon, "Expect ':' after then branch.")?;
+            let else_branch = self.expression()?;
 
-            expr = Expr::new_logical(expr, operator, right);
+            Ok(Expr::new_conditional(expr, then_branch, else_branch))
+        } else {
+            Ok(expr)
         }
-
-        Ok(expr)
     }
 
-    fn equality_expression(&mut self) -> Result<Expr, SyntaxError> {
-        let mut expr = self.comparison_expression()?;
+    fn coalesce_expression(&mut self) -> Result<Expr, SyntaxError> {
+        let expr = self.or_expression()?;
 
-        while self.compare(&[Kind::BangEqual, Kind::EqualEqual]) {
+        if self.compare(&[Kind::QuestionQuestion]) {
             let operator = self.previous().clone();
-            let right = self.comparison_expression()?;
 
-            expr = Expr::new_binary(expr, operator, right);
+            self.nest()?;
+            let right = self.coalesce_expression();
+            self.depth -= 1;
+
+            return Ok(Expr::new_logical(expr, operator, right?));
         }
 
         Ok(expr)
     }
 
+    fn or_expression(&mut self) -> Result<Expr, SyntaxError> {
+        self.chain(&[Kind::BarBar], Self::and_expression, Expr::new_logical)
+    }
+
+    fn and_expression(&mut self) -> Result<Expr, SyntaxError> {
+        self.chain(
+            &[Kind::AmpAmp],
+            Self::equality_expression,
+            Expr::new_logical,
+        )
+    }
+
+    fn equality_expression(&mut self) -> Result<Expr, SyntaxError> {
+        self.chain(
+            &[Kind::BangEqual, Kind::EqualEqual],
+            Self::comparison_expression,
+            Expr::new_binary,
+        )
+    }
+
     fn comparison_expression(&mut self) -> Result<Expr, SyntaxError> {
-        let mut expr = self.term_expression()?;
+        self.chain(
+            &[
+                Kind::Greater,
+                Kind::GreaterEqual,
+                Kind::Less,
+                Kind::LessEqual,
+            ],
+            Self::bit_or_expression,
+            Expr::new_binary,
+        )
+    }
 
-        while self.compare(&[
-            Kind::Greater,
-            Kind::GreaterEqual,
-            Kind::Less,
-            Kind::LessEqual,
-        ]) {
-            let operator = self.previous().clone();
-            let right = self.term_expression()?;
+    fn bit_or_expression(&mut self) -> Result<Expr, SyntaxError> {
+        self.chain(&[Kind::Bar], Self::bit_xor_expression, Expr::new_binary)
+    }
 
-            expr = Expr::new_binary(expr, operator, right);
-        }
+    fn bit_xor_expression(&mut self) -> Result<Expr, SyntaxError> {
+        self.chain(&[Kind::Caret], Self::bit_and_expression, Expr::new_binary)
+    }
 
-        Ok(expr)
+    fn bit_and_expression(&mut self) -> Result<Expr, SyntaxError> {
+        self.chain(&[Kind::Amp], Self::shift_expression, Expr::new_binary)
+    }
+
+    fn shift_expression(&mut self) -> Result<Expr, SyntaxError> {
+        self.chain(
+            &[Kind::LessLess, Kind::GreaterGreater],
+            Self::term_expression,
+            Expr::new_binary,
+        )
     }
 
     fn term_expression(&mut self) -> Result<Expr, SyntaxError> {
-        let mut expr = self.factor_expression()?;
+        self.chain(
+            &[Kind::Minus, Kind::Plus],
+            Self::factor_expression,
+            Expr::new_binary,
+        )
+    }
 
-        while self.compare(&[Kind::Minus, Kind::Plus]) {
+    fn factor_expression(&mut self) -> Result<Expr, SyntaxError> {
+        self.chain(
+            &[Kind::Slash, Kind::Star],
+            Self::cast_expression,
+            Expr::new_binary,
+        )
+    }
+
+    fn chain(
+        &mut self,
+        operators: &[Kind],
+        operand: fn(&mut Self) -> Result<Expr, SyntaxError>,
+        combine: fn(Expr, Token, Expr) -> Expr,
+    ) -> Result<Expr, SyntaxError> {
+        let length = self.length;
+        let expr = self.links(operators, operand, combine);
+        self.length = length;
+
+        expr
+    }
+
+    fn links(
+        &mut self,
+        operators: &[Kind],
+        operand: fn(&mut Self) -> Result<Expr, SyntaxError>,
+        combine: fn(Expr, Token, Expr) -> Expr,
+    ) -> Result<Expr, SyntaxError> {
+        let mut expr = operand(self)?;
+
+        while self.compare(operators) {
             let operator = self.previous().clone();
-            let right = self.factor_expression()?;
+            self.link()?;
+            let right = operand(self)?;
 
-            expr = Expr::new_binary(expr, operator, right);
+            expr = combine(expr, operator, right);
         }
 
         Ok(expr)
     }
 
-    fn factor_expression(&mut self) -> Result<Expr, SyntaxError> {
+    fn cast_expression(&mut self) -> Result<Expr, SyntaxError> {
+        let length = self.length;
+        let expr = self.casts();
+        self.length = length;
+
+        expr
+    }
+
+    fn casts(&mut self) -> Result<Expr, SyntaxError> {
         let mut expr = self.unary_expression()?;
 
-        while self.compare(&[Kind::Slash, Kind::Star]) {
-            let operator = self.previous().clone();
-            let right = self.unary_expression()?;
+        while self.compare(&[Kind::As]) {
+            self.link()?;
+
+            let target = self
+                .consume(Kind::Identifier, "Expect type after 'as'.")?
+                .clone();
 
-            expr = Expr::new_binary(expr, operator, right);
+            expr = Expr::new_cast(expr, target);
         }
 
         Ok(expr)
     }
 
     fn unary_expression(&mut self) -> Result<Expr, SyntaxError> {
-        if self.compare(&[Kind::Bang, Kind::Minus]) {
+        if self.compare(&[Kind::Bang, Kind::Minus, Kind::Try]) {
             let operator = self.previous().clone();
-            let right = self.unary_expression()?;
+            self.negated = operator.kind == Kind::Minus;
+
+            self.nest()?;
+            let right = self.unary_expression();
+            self.depth -= 1;
+            let right = right?;
 
             Ok(Expr::new_unary(operator, right))
         } else {
@@ -324,67 +894,394 @@ impl Parser {
     }
 
     fn call_expression(&mut self) -> Result<Expr, SyntaxError> {
+        let length = self.length;
+        let expr = self.calls();
+        self.length = length;
+
+        expr
+    }
+
+    fn calls(&mut self) -> Result<Expr, SyntaxError> {
         let mut expr = self.primary_expression()?;
 
-        while self.compare(&[Kind::LeftParen]) {
-            let mut arguments = Vec::new();
+        loop {
+            if matches!(
+                self.peek().kind,
+                Kind::LeftParen | Kind::LeftBracket | Kind::Dot
+            ) {
+                self.link()?;
+            }
 
-            if !self.check(Kind::RightParen) {
-                arguments.push(self.expression()?);
+            if self.compare(&[Kind::LeftParen]) {
+                let mut arguments = Vec::new();
 
-                while self.compare(&[Kind::Comma]) {
-                    arguments.push(self.expression()?);
+                if !self.check(Kind::RightParen) {
+                    arguments.push(self.enclosed_expression()?);
+
+                    while self.compare(&[Kind::Comma]) {
+                        arguments.push(self.enclosed_expression()?);
+                    }
+
+                    self.compare(&[Kind::Comma]);
                 }
 
-                self.compare(&[Kind::Comma]);
-            }
+                self.consume(Kind::RightParen, "Expect ')' after arguments.")?;
 
-            self.consume(Kind::RightParen, "Expect ')' after arguments.")?;
+                expr = Expr::new_call(expr, arguments);
+            } else if self.compare(&[Kind::LeftBracket]) {
+                let index = self.enclosed_expression()?;
+                self.consume(Kind::RightBracket, "Expect ']' after index.")?;
 
-            expr = Expr::new_call(expr, arguments);
+                expr = Expr::new_index(expr, index);
+            } else if self.compare(&[Kind::Dot]) {
+                let name = self
+                    .consume(Kind::Identifier, "Expect name after '.'.")?
+                    .clone();
+
+                expr = Expr::new_get(expr, name);
+            } else {
+                break;
+            }
         }
 
         Ok(expr)
     }
 
     fn primary_expression(&mut self) -> Result<Expr, SyntaxError> {
-        if self.compare(&[Kind::False, Kind::True, Kind::Number, Kind::String])
-        {
+        let negated = mem::replace(&mut self.negated, false);
+
+        if self.compare(&[
+            Kind::False,
+            Kind::True,
+            Kind::None,
+            Kind::Number,
+            Kind::String,
+        ]) {
             let token = self.previous();
             let literal = match token.kind {
                 Kind::False => Value::False,
                 Kind::True => Value::True,
-                Kind::Number => Value::Number(token.lexeme.clone()),
-                Kind::String => {
-                    let mut characters = token.lexeme.chars();
-                    characters.next();
-                    characters.next_back();
-
-                    Value::String(characters.collect())
-                }
+                Kind::None => Value::None,
+                Kind::Number => self.number(token, negated)?,
+                Kind::String => self.string(token),
                 _ => return Err(self.error(token, "Parser bug, wrong literal")),
             };
 
             Ok(Expr::new_literal(literal))
         } else if self.compare(&[Kind::Identifier]) {
-            Ok(Expr::new_variable(self.previous().clone()))
+            let name = self.previous().clone();
+
+            if self.compare(&[Kind::ColonColon]) {
+                let variant = self
+                    .consume(Kind::Identifier, "Expect variant name.")?
+                    .clone();
+
+                Ok(Expr::new_path(name, variant))
+            } else if self.structs && self.compare(&[Kind::LeftBrace]) {
+                self.struct_expression(name)
+            } else {
+                Ok(Expr::new_variable(name))
+            }
         } else if self.compare(&[Kind::LeftParen]) {
-            let expr = self.expression()?;
+            let expr = self.enclosed_expression()?;
             self.consume(Kind::RightParen, "Expect ')' after expression.")?;
 
             Ok(Expr::new_grouping(expr))
+        } else if self.compare(&[Kind::Loop]) {
+            self.consume(Kind::LeftBrace, "Expect '{' after 'loop'.")?;
+
+            let structs = mem::replace(&mut self.structs, true);
+            let body = self.block_statement();
+            self.structs = structs;
+
+            Ok(Expr::new_loop(body?))
+        } else if self.compare(&[Kind::LeftBracket]) {
+            let mut elements = Vec::new();
+
+            if !self.check(Kind::RightBracket) {
+                elements.push(self.enclosed_expression()?);
+
+                while self.compare(&[Kind::Comma]) {
+                    if self.check(Kind::RightBracket) {
+                        break;
+                    }
+
+                    elements.push(self.enclosed_expression()?);
+                }
+            }
+
+            self.consume(Kind::RightBracket, "Expect ']' after elements.")?;
+
+            Ok(Expr::new_array(elements))
+        } else if self.structs && self.compare(&[Kind::LeftBrace]) {
+            self.map_expression()
         } else {
             Err(self.error(self.peek(), "Expect expression."))
         }
     }
 
+    fn string(&self, token: &Token) -> Value {
+        let mut characters = token.lexeme.chars();
+        characters.next();
+        characters.next_back();
+
+        Value::String(characters.collect())
+    }
+
+    fn number(
+        &self,
+        token: &Token,
+        negated: bool,
+    ) -> Result<Value, SyntaxError> {
+        let lexeme = &token.lexeme;
+
+        let radix = match lexeme.get(..2) {
+            Some("0x") => 16,
+            Some("0o") => 8,
+            Some("0b") => 2,
+            _ => 10,
+        };
+
+        if radix != 10 {
+            return self.radix_number(token, radix, negated);
+        }
+
+        let (digits, suffix) = if let Some(digits) = lexeme.strip_suffix('i') {
+            (digits, Some(Suffix::Integer))
+        } else if let Some(digits) = lexeme.strip_suffix('f') {
+            (digits, Some(Suffix::Float))
+        } else {
+            (lexeme.as_str(), None)
+        };
+
+        let digits = digits.replace('_', "");
+
+        let float =
+            suffix == Some(Suffix::Float) || digits.contains(['.', 'e', 'E']);
+
+        if suffix == Some(Suffix::Integer) && digits.contains(['.', 'e', 'E']) {
+            Err(SyntaxError {
+                code: Code::InvalidLiteral,
+                ..self.error(token, "Integer suffix on a float literal.")
+            })
+        } else if float && digits.parse::<f64>().map_or(true, f64::is_infinite)
+        {
+            Err(self.range(token, "f64"))
+        } else if !float && !self.fits(&digits, negated) {
+            Err(self.range(token, "i32"))
+        } else {
+            Ok(Value::Number(digits, suffix))
+        }
+    }
+
+    fn fits(&self, digits: &str, negated: bool) -> bool {
+        let limit = if negated {
+            i32::MIN.unsigned_abs()
+        } else {
+            i32::MAX as u32
+        };
+
+        matches!(digits.parse::<u32>(), Ok(value) if value <= limit)
+    }
+
+    fn range(&self, token: &Token, kind: &str) -> SyntaxError {
+        SyntaxError {
+            code: Code::OutOfRange,
+            ..self.error(token, &Code::OutOfRange.message(&[&kind]))
+        }
+    }
+
+    fn radix_number(
+        &self,
+        token: &Token,
+        radix: u32,
+        negated: bool,
+    ) -> Result<Value, SyntaxError> {
+        let (digits, suffix) = match token.lexeme.strip_suffix('i') {
+            Some(digits) => (digits, Some(Suffix::Integer)),
+            None => (token.lexeme.as_str(), None),
+        };
+
+        match u64::from_str_radix(&digits[2..].replace('_', ""), radix) {
+            Ok(value) if self.fits(&value.to_string(), negated) => {
+                Ok(Value::Number(value.to_string(), suffix))
+            }
+            Ok(_) => Err(self.range(token, "i32")),
+            Err(error) if *error.kind() == IntErrorKind::PosOverflow => {
+                Err(self.range(token, "i32"))
+            }
+            Err(_) => Err(SyntaxError {
+                code: Code::InvalidLiteral,
+                ..self
+                    .error(token, &Code::InvalidLiteral.message(&[&"integer"]))
+            }),
+        }
+    }
+
+    fn struct_expression(&mut self, name: Token) -> Result<Expr, SyntaxError> {
+        let mut fields = Vec::new();
+        let mut base = None;
+
+        while !self.is_at_end() && !self.check(Kind::RightBrace) {
+            if self.compare(&[Kind::DotDot]) {
+                base = Some(self.enclosed_expression()?);
+                self.separate("Expect '}' after struct base.")?;
+                break;
+            }
+
+            let name = self
+                .consume(Kind::Identifier, "Expect field name.")?
+                .clone();
+
+            self.consume(Kind::Colon, "Expect ':' after field name.")?;
+            let value = self.enclosed_expression()?;
+            self.separate("Expect ',' after field.")?;
+
+            fields.push((name, value));
+        }
+
+        self.consume(Kind::RightBrace, "Expect '}' after fields.")?;
+
+        Ok(Expr::new_struct(name, fields, base))
+    }
+
+    fn map_expression(&mut self) -> Result<Expr, SyntaxError> {
+        let mut entries = Vec::new();
+
+        while !self.is_at_end() && !self.check(Kind::RightBrace) {
+            let key = self.enclosed_expression()?;
+
+            if entries.is_empty() && !self.check(Kind::Colon) {
+                return self.set_expression(key);
+            }
+
+            self.consume(Kind::Colon, "Expect ':' after key.")?;
+            let value = self.enclosed_expression()?;
+            self.separate("Expect ',' after entry.")?;
+
+            entries.push((key, value));
+        }
+
+        self.consume(Kind::RightBrace, "Expect '}' after entries.")?;
+
+        Ok(Expr::new_map(entries))
+    }
+
+    fn set_expression(&mut self, first: Expr) -> Result<Expr, SyntaxError> {
+        let mut elements = vec![first];
+        self.separate("Expect ',' after element.")?;
+
+        while !self.is_at_end() && !self.check(Kind::RightBrace) {
+            elements.push(self.enclosed_expression()?);
+            self.separate("Expect ',' after element.")?;
+        }
+
+        self.consume(Kind::RightBrace, "Expect '}' after elements.")?;
+
+        Ok(Expr::new_set_literal(elements))
+    }
+
+    fn pattern(&mut self) -> Result<Pattern, SyntaxError> {
+        if self.compare(&[Kind::False]) {
+            Ok(Pattern::new_literal(Value::False))
+        } else if self.compare(&[Kind::True]) {
+            Ok(Pattern::new_literal(Value::True))
+        } else if self.compare(&[Kind::None]) {
+            Ok(Pattern::new_literal(Value::None))
+        } else if self.compare(&[Kind::String]) {
+            Ok(Pattern::new_literal(self.string(self.previous())))
+        } else if self.compare(&[Kind::Number]) {
+            Ok(Pattern::new_literal(self.number(self.previous(), false)?))
+        } else if self.compare(&[Kind::Minus]) {
+            let token = self
+                .consume(Kind::Number, "Expect number after '-'.")?
+                .clone();
+
+            match self.number(&token, true)? {
+                Value::Number(digits, suffix) => Ok(Pattern::new_literal(
+                    Value::Number(format!("-{}", digits), suffix),
+                )),
+                _ => Err(self.error(&token, "Parser bug, wrong literal")),
+            }
+        } else if self.check(Kind::Identifier) && self.peek().lexeme == "_" {
+            self.advance();
+            Ok(Pattern::new_wildcard())
+        } else if self.compare(&[Kind::Identifier]) {
+            let name = self.previous().clone();
+
+            if self.compare(&[Kind::ColonColon]) {
+                self.path_pattern(name)
+            } else {
+                Ok(Pattern::new_binding(name))
+            }
+        } else {
+            Err(self.error(self.peek(), "Expect pattern."))
+        }
+    }
+
+    fn path_pattern(
+        &mut self,
+        namespace: Token,
+    ) -> Result<Pattern, SyntaxError> {
+        let name = self
+            .consume(Kind::Identifier, "Expect variant name.")?
+            .clone();
+
+        let mut fields = Vec::new();
+
+        if self.compare(&[Kind::LeftParen]) {
+            if !self.check(Kind::RightParen) {
+                fields.push(self.pattern()?);
+
+                while self.compare(&[Kind::Comma]) {
+                    fields.push(self.pattern()?);
+                }
+
+                self.compare(&[Kind::Comma]);
+            }
+
+            self.consume(Kind::RightParen, "Expect ')' after patterns.")?;
+        }
+
+        Ok(Pattern::new_path(namespace, name, fields))
+    }
+
     fn variant(&mut self) -> Result<Variant, SyntaxError> {
+        self.nest()?;
+        let variant = self.nested_variant();
+        self.depth -= 1;
+
+        let variant = if self.compare(&[Kind::Question]) {
+            Variant::new_optional(variant?)
+        } else {
+            variant?
+        };
+
+        if self.compare(&[Kind::Bang]) {
+            Ok(Variant::new_fallible(variant, self.variant()?))
+        } else {
+            Ok(variant)
+        }
+    }
+
+    fn nested_variant(&mut self) -> Result<Variant, SyntaxError> {
         if self.compare(&[Kind::Identifier]) {
-            Ok(self.literal_variant()?)
+            if self.previous().lexeme == "map" && self.check(Kind::LeftBracket)
+            {
+                Ok(self.map_variant()?)
+            } else if self.previous().lexeme == "set"
+                && self.check(Kind::LeftBracket)
+            {
+                Ok(self.set_variant()?)
+            } else {
+                Ok(self.literal_variant()?)
+            }
         } else if self.compare(&[Kind::Fn]) {
             Ok(self.function_variant()?)
+        } else if self.compare(&[Kind::LeftBracket]) {
+            Ok(self.array_variant()?)
         } else {
-            Err(self.error(self.peek(), "Expect literal or function type."))
+            Err(self.error(self.peek(), "Expect type."))
         }
     }
 
@@ -418,11 +1315,46 @@ impl Parser {
         Ok(Variant::new_function(parameters, output))
     }
 
+    fn array_variant(&mut self) -> Result<Variant, SyntaxError> {
+        let element = self.variant()?;
+        self.consume(Kind::RightBracket, "Expect ']' after element type.")?;
+
+        Ok(Variant::new_array(element))
+    }
+
+    fn map_variant(&mut self) -> Result<Variant, SyntaxError> {
+        self.consume(Kind::LeftBracket, "Expect '[' after 'map'.")?;
+        let key = self.variant()?;
+        self.consume(Kind::Comma, "Expect ',' after key type.")?;
+        let value = self.variant()?;
+        self.consume(Kind::RightBracket, "Expect ']' after value type.")?;
+
+        Ok(Variant::new_map(key, value))
+    }
+
+    fn set_variant(&mut self) -> Result<Variant, SyntaxError> {
+        self.consume(Kind::LeftBracket, "Expect '[' after 'set'.")?;
+        let element = self.variant()?;
+        self.consume(Kind::RightBracket, "Expect ']' after element type.")?;
+
+        Ok(Variant::new_set(element))
+    }
+
     fn synchronize(&mut self) {
         self.advance();
 
         while !self.is_at_end() {
-            if matches!(self.peek().kind, Kind::Fn | Kind::Type) {
+            if matches!(
+                self.peek().kind,
+                Kind::At
+                    | Kind::Fn
+                    | Kind::Const
+                    | Kind::Let
+                    | Kind::Type
+                    | Kind::Struct
+                    | Kind::Enum
+                    | Kind::Impl
+            ) {
                 return;
             }
 
@@ -430,6 +1362,78 @@ impl Parser {
         }
     }
 
+    fn recover(&mut self) {
+        let mut braces = 0;
+
+        while !self.is_at_end() {
+            match self.peek().kind {
+                Kind::Semicolon if braces == 0 => {
+                    self.advance();
+                    return;
+                }
+                Kind::LeftBrace => braces += 1,
+                Kind::RightBrace if braces == 0 => return,
+                Kind::RightBrace => braces -= 1,
+                _ => (),
+            }
+
+            self.advance();
+        }
+    }
+
+    fn nest(&mut self) -> Result<(), SyntaxError> {
+        if self.depth >= MAX_DEPTH {
+            Err(SyntaxError {
+                code: Code::TooDeep,
+                ..self.error(self.peek(), Code::TooDeep.template())
+            })
+        } else {
+            self.depth += 1;
+            Ok(())
+        }
+    }
+
+    fn link(&mut self) -> Result<(), SyntaxError> {
+        if self.length >= MAX_LENGTH {
+            Err(SyntaxError {
+                code: Code::TooDeep,
+                ..self.error(self.peek(), Code::TooDeep.template())
+            })
+        } else {
+            self.length += 1;
+            Ok(())
+        }
+    }
+
+    fn separate(&mut self, message: &str) -> Result<(), SyntaxError> {
+        if self.compare(&[Kind::Comma]) {
+            self.compare(&[Kind::Semicolon]);
+            Ok(())
+        } else if self.compare(&[Kind::Semicolon])
+            || self.check(Kind::RightBrace)
+        {
+            Ok(())
+        } else {
+            Err(SyntaxError {
+                code: Code::MissingToken,
+                ..self.error(self.peek(), message)
+            })
+        }
+    }
+
+    fn terminate(&mut self, message: &str) -> Result<(), SyntaxError> {
+        let closed = self.previous().kind == Kind::RightBrace
+            || self.check(Kind::RightBrace)
+            || self.check(Kind::Eof);
+
+        if closed && !self.check(Kind::Semicolon) {
+            Ok(())
+        } else {
+            self.consume(Kind::Semicolon, message)?;
+            Ok(())
+        }
+    }
+
     fn consume(
         &mut self,
         kind: Kind,
@@ -438,23 +1442,42 @@ impl Parser {
         if self.check(kind) {
             Ok(self.advance())
         } else {
-            Err(self.error(self.peek(), message))
+            let code = match kind {
+                Kind::Identifier => Code::MissingName,
+                _ => Code::MissingToken,
+            };
+
+            Err(SyntaxError {
+                code,
+                ..self.error(self.peek(), message)
+            })
         }
     }
 
     fn error(&self, token: &Token, message: &str) -> SyntaxError {
         let location = match token.kind {
-            Kind::EOF => " at end".to_string(),
+            Kind::Eof => " at end".to_string(),
             _ => format!(" at '{}'", token.lexeme),
         };
 
         SyntaxError {
             line: token.line,
+            span: token.span,
+            code: Code::InvalidSyntax,
             location,
             message: message.to_string(),
+            note: None,
         }
     }
 
+    fn misspelled(&self, token: &Token) -> Option<String> {
+        if token.kind != Kind::Identifier {
+            return None;
+        }
+
+        suggest(&token.lexeme, KEYWORDS.iter().map(|(keyword, _)| *keyword))
+    }
+
     fn compare(&mut self, kinds: &[Kind]) -> bool {
         for kind in kinds.iter() {
             if self.check(*kind) {
@@ -470,6 +1493,12 @@ impl Parser {
         !self.is_at_end() && self.peek().kind == kind
     }
 
+    fn check_next(&self, kind: Kind) -> bool {
+        self.tokens
+            .get(self.current + 1)
+            .is_some_and(|token| token.kind == kind)
+    }
+
     fn advance(&mut self) -> &Token {
         if !self.is_at_end() {
             self.current += 1;
@@ -487,6 +1516,6 @@ impl Parser {
     }
 
     fn is_at_end(&self) -> bool {
-        matches!(self.peek().kind, Kind::EOF)
+        matches!(self.peek().kind, Kind::Eof)
     }
 }
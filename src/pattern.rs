@@ -0,0 +1,74 @@
+use crate::token::Token;
+use crate::value::Value;
+
+#[derive(Clone, Debug)]
+pub struct Literal {
+    pub value: Value,
+}
+
+#[derive(Clone, Debug)]
+pub struct Path {
+    pub namespace: Token,
+    pub name: Token,
+    pub fields: Vec<Pattern>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Binding {
+    pub name: Token,
+}
+
+#[derive(Clone, Debug)]
+pub struct Wildcard {}
+
+#[derive(Clone, Debug)]
+pub enum Pattern {
+    Literal(Box<Literal>),
+    Path(Box<Path>),
+    Binding(Box<Binding>),
+    Wildcard(Box<Wildcard>),
+}
+
+impl Pattern {
+    pub fn new_literal(value: Value) -> Self {
+        Self::Literal(Box::new(Literal { value }))
+    }
+
+    pub fn new_path(
+        namespace: Token,
+        name: Token,
+        fields: Vec<Pattern>,
+    ) -> Self {
+        Self::Path(Box::new(Path {
+            namespace,
+            name,
+            fields,
+        }))
+    }
+
+    pub fn new_binding(name: Token) -> Self {
+        Self::Binding(Box::new(Binding { name }))
+    }
+
+    pub fn new_wildcard() -> Self {
+        Self::Wildcard(Box::new(Wildcard {}))
+    }
+
+    pub fn accept<V: Visitor>(&self, visitor: &mut V) -> V::Result {
+        match self {
+            Self::Literal(pattern) => visitor.visit_literal_pattern(pattern),
+            Self::Path(pattern) => visitor.visit_path_pattern(pattern),
+            Self::Binding(pattern) => visitor.visit_binding_pattern(pattern),
+            Self::Wildcard(pattern) => visitor.visit_wildcard_pattern(pattern),
+        }
+    }
+}
+
+pub trait Visitor {
+    type Result;
+
+    fn visit_literal_pattern(&mut self, pattern: &Literal) -> Self::Result;
+    fn visit_path_pattern(&mut self, pattern: &Path) -> Self::Result;
+    fn visit_binding_pattern(&mut self, pattern: &Binding) -> Self::Result;
+    fn visit_wildcard_pattern(&mut self, pattern: &Wildcard) -> Self::Result;
+}
@@ -0,0 +1,566 @@
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Read, Write};
+use std::path::PathBuf;
+use std::process::{self, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use blaze::expr::Expr;
+use blaze::stmt::Stmt;
+use blaze::{
+    Diagnostic, Kind, Level, Options, Output, Parser, Reporter, Scanner,
+    Semicolons, Span, Stage, Token,
+};
+
+use crate::backend::Backend;
+use crate::json::Json;
+
+pub struct Repl {
+    items: Vec<String>,
+    body: Vec<String>,
+    printed: usize,
+    input: Vec<String>,
+    directory: PathBuf,
+    rustc: Command,
+    options: Options,
+}
+
+impl Repl {
+    pub fn new(mut rustc: Command, mut options: Options) -> Self {
+        let directory =
+            env::temp_dir().join(format!("blaze-repl-{}", process::id()));
+
+        rustc
+            .arg("--error-format=json")
+            .arg(directory.join("repl.rs"))
+            .arg("-o")
+            .arg(directory.join(format!("repl{}", env::consts::EXE_SUFFIX)));
+
+        // Every line rebuilds the ones before it, so their warnings would be
+        // repeated each time. Flags given on the command line still win.
+        options
+            .lints
+            .insert(0, ("warnings".to_string(), Level::Allow));
+
+        Self {
+            items: Vec::new(),
+            body: Vec::new(),
+            printed: 0,
+            input: Vec::new(),
+            directory,
+            rustc,
+            options,
+        }
+    }
+
+    pub fn run(&mut self) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+
+        let lines = lines();
+
+        while let Some(snippet) = read(&lines)? {
+            match snippet.trim() {
+                ":quit" => break,
+                "" => continue,
+                _ => self.evaluate(&snippet, &lines)?,
+            }
+        }
+
+        fs::remove_dir_all(&self.directory)
+    }
+
+    fn evaluate(
+        &mut self,
+        snippet: &str,
+        lines: &Receiver<String>,
+    ) -> io::Result<()> {
+        let mut items = self.items.clone();
+        let mut body = self.body.clone();
+        let item = is_item(snippet);
+
+        let (prefix, offset) = if item {
+            items.push(snippet.to_string());
+            (0, 0)
+        } else if is_expression(snippet) {
+            body.push(format!("print({})", snippet.trim()));
+            ("print(".len(), snippet.len() - snippet.trim_start().len())
+        } else {
+            body.push(snippet.to_string());
+            (0, 0)
+        };
+
+        let (program, start) = assemble(&items, &body, item);
+        let place = Place {
+            snippet,
+            start: start + prefix,
+            line: program[..start].matches('\n').count() + 1,
+            offset,
+        };
+
+        let reporter = Reporter::new(
+            "<repl>",
+            self.options.format,
+            snippet,
+            self.options.lints.clone(),
+            self.options.max_errors,
+        );
+
+        let output = match self.compile(&program) {
+            Ok(output) => output,
+            Err(diagnostics) => {
+                reporter.report(place.all(diagnostics));
+                return Ok(());
+            }
+        };
+
+        fs::write(self.directory.join("repl.rs"), &output)?;
+
+        let build = match self.rustc.output() {
+            Ok(build) => build,
+            Err(error) => {
+                eprintln!(
+                    "failed to run '{}': {}",
+                    self.rustc.get_program().to_string_lossy(),
+                    error
+                );
+                return Ok(());
+            }
+        };
+
+        let backend = Backend::new(&output, &program);
+        let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+        for line in String::from_utf8_lossy(&build.stderr).lines() {
+            let diagnostic = match Json::parse(line) {
+                Some(message) => backend.convert(&message),
+                None => {
+                    eprintln!("{}", line);
+                    continue;
+                }
+            };
+
+            if let Some(diagnostic) = diagnostic {
+                let duplicate = diagnostics.iter().any(|seen| {
+                    seen.message == diagnostic.message
+                        && seen.span == diagnostic.span
+                });
+
+                if !duplicate {
+                    diagnostics.push(diagnostic);
+                }
+            }
+        }
+
+        if reporter.report(place.all(diagnostics)) || !build.status.success() {
+            return Ok(());
+        }
+
+        let live = !item && reads(&self.items, snippet);
+        let (printed, input, success) = self.execute(lines, live)?;
+
+        if success {
+            self.items = items;
+            self.body = body;
+            self.printed = printed;
+            self.input.extend(input);
+        }
+
+        Ok(())
+    }
+
+    fn compile(&self, program: &str) -> Result<String, Vec<Diagnostic>> {
+        let reporter =
+            Reporter::collector(program, self.options.lints.clone(), None);
+
+        match blaze::compile_to(
+            &reporter,
+            program,
+            &self.options,
+            Stage::Generate,
+        ) {
+            Some(Output::Rust(output)) => Ok(output),
+            _ => Err(reporter.take()),
+        }
+    }
+
+    // The program starts over on every line, so it is fed the input the
+    // earlier lines read before anything new is passed on from the prompt,
+    // and only the output past what was already shown is printed.
+    fn execute(
+        &self,
+        lines: &Receiver<String>,
+        live: bool,
+    ) -> io::Result<(usize, Vec<String>, bool)> {
+        let mut child = Command::new(
+            self.directory
+                .join(format!("repl{}", env::consts::EXE_SUFFIX)),
+        )
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+        let mut stdin = child.stdin.take();
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let skip = self.printed;
+
+        let printed = thread::spawn(move || show(stdout, skip));
+        let errors = thread::spawn(move || {
+            let mut errors = Vec::new();
+
+            if let Some(mut stderr) = stderr {
+                stderr.read_to_end(&mut errors).ok();
+            }
+
+            errors
+        });
+
+        if let Some(pipe) = stdin.as_mut() {
+            for line in &self.input {
+                if pipe.write_all(line.as_bytes()).is_err() {
+                    break;
+                }
+            }
+        }
+
+        if !live {
+            drop(stdin.take());
+        }
+
+        let mut input = Vec::new();
+
+        let status = loop {
+            if stdin.is_none() {
+                break child.wait()?;
+            }
+
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+
+            match lines.recv_timeout(Duration::from_millis(20)) {
+                Ok(line) => {
+                    if let Some(pipe) = stdin.as_mut() {
+                        pipe.write_all(line.as_bytes()).ok();
+                    }
+
+                    input.push(line);
+                }
+                Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => drop(stdin.take()),
+            }
+        };
+
+        let printed = printed.join().unwrap_or(skip);
+        let errors = errors.join().unwrap_or_default();
+
+        if !status.success() {
+            io::stderr().write_all(&errors)?;
+        }
+
+        Ok((printed, input, status.success()))
+    }
+}
+
+struct Place<'a> {
+    snippet: &'a str,
+    start: usize,
+    line: usize,
+    offset: usize,
+}
+
+impl Place<'_> {
+    fn all(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        diagnostics
+            .into_iter()
+            .map(|diagnostic| self.locate(diagnostic))
+            .collect()
+    }
+
+    // Moves a diagnostic from the rebuilt program onto the line that was
+    // typed. Anything outside of it keeps its message but loses its position.
+    fn locate(&self, mut diagnostic: Diagnostic) -> Diagnostic {
+        let snippet = self.snippet;
+
+        let span = diagnostic.span.and_then(|span| {
+            let start = span.start.checked_sub(self.start)? + self.offset;
+            let end = span.end.checked_sub(self.start)? + self.offset;
+
+            if start >= snippet.len() || !snippet.is_char_boundary(start) {
+                return None;
+            }
+
+            let end = end.min(snippet.trim_end().len()).max(start);
+            let before = snippet.get(..start)?;
+            let column = before[before.rfind('\n').map_or(0, |i| i + 1)..]
+                .chars()
+                .count();
+
+            Some(Span {
+                start,
+                end: if snippet.is_char_boundary(end) {
+                    end
+                } else {
+                    start
+                },
+                column: column + 1,
+            })
+        });
+
+        let first = snippet[..self.offset].matches('\n').count() + 1;
+        let count = snippet.trim_end().matches('\n').count() + 1;
+
+        diagnostic.line = match span {
+            Some(span) => snippet[..span.start].matches('\n').count() + 1,
+            None if diagnostic.span.is_some() => 0,
+            None => match diagnostic.line.checked_sub(self.line) {
+                Some(line) if first + line <= count => first + line,
+                _ => 0,
+            },
+        };
+        diagnostic.span = span;
+
+        diagnostic
+    }
+}
+
+// Whether running the snippet can read from the prompt, either by calling
+// 'input' itself or through a function declared on an earlier line that does.
+fn reads(items: &[String], snippet: &str) -> bool {
+    let mut readers = vec!["input".to_string(), "input_num".to_string()];
+
+    loop {
+        let known = readers.len();
+
+        for item in items {
+            let tokens = tokens(item);
+
+            if !tokens.iter().any(|token| readers.contains(&token.lexeme)) {
+                continue;
+            }
+
+            for pair in tokens.windows(2) {
+                if pair[0].kind == Kind::Fn
+                    && !readers.contains(&pair[1].lexeme)
+                {
+                    readers.push(pair[1].lexeme.clone());
+                }
+            }
+        }
+
+        if readers.len() == known {
+            break;
+        }
+    }
+
+    tokens(snippet).iter().any(|token| {
+        token.kind == Kind::Identifier && readers.contains(&token.lexeme)
+    })
+}
+
+fn tokens(source: &str) -> Vec<Token> {
+    Scanner::new(source, Semicolons::Automatic).scan().0
+}
+
+fn assemble(items: &[String], body: &[String], item: bool) -> (String, usize) {
+    let mut program = String::new();
+    let mut start = 0;
+
+    for entry in items {
+        if item {
+            start = program.len();
+        }
+
+        program.push_str(entry);
+        program.push_str("\n\n");
+    }
+
+    program.push_str("fn main() {\n");
+
+    for entry in body {
+        if !item {
+            start = program.len();
+        }
+
+        program.push_str(entry);
+        program.push('\n');
+    }
+
+    program.push_str("}\n");
+
+    (program, start)
+}
+
+fn lines() -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let stdin = io::stdin();
+        let mut input = stdin.lock();
+
+        loop {
+            let mut line = String::new();
+
+            match input.read_line(&mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) if sender.send(line).is_err() => break,
+                Ok(_) => (),
+            }
+        }
+    });
+
+    receiver
+}
+
+fn show(stdout: Option<impl Read>, skip: usize) -> usize {
+    let mut stdout = match stdout {
+        Some(stdout) => stdout,
+        None => return skip,
+    };
+
+    let mut buffer = [0; 4096];
+    let mut total = 0;
+
+    loop {
+        let count = match stdout.read(&mut buffer) {
+            Ok(0) | Err(_) => return total,
+            Ok(count) => count,
+        };
+
+        let start = skip.saturating_sub(total).min(count);
+        total += count;
+
+        let mut output = io::stdout().lock();
+        output.write_all(&buffer[start..count]).ok();
+        output.flush().ok();
+    }
+}
+
+fn read(lines: &Receiver<String>) -> io::Result<Option<String>> {
+    let mut snippet = String::new();
+    let mut prompt = "> ";
+
+    loop {
+        print!("{}", prompt);
+        io::stdout().flush()?;
+
+        match lines.recv() {
+            Ok(line) => snippet.push_str(&line),
+            Err(_) if snippet.is_empty() => {
+                println!();
+                return Ok(None);
+            }
+            Err(_) => return Ok(Some(snippet)),
+        }
+
+        if !is_open(&snippet) {
+            return Ok(Some(snippet));
+        }
+
+        prompt = "... ";
+    }
+}
+
+fn parse(source: &str) -> Option<Vec<Stmt>> {
+    let mut scanner = Scanner::new(source, Semicolons::Automatic);
+    let (tokens, errors) = scanner.scan();
+
+    let mut parser = Parser::new(tokens);
+    let (statements, syntax) = parser.parse();
+
+    if errors.is_empty() && syntax.is_empty() {
+        Some(statements)
+    } else {
+        None
+    }
+}
+
+fn is_open(snippet: &str) -> bool {
+    let (tokens, _) = Scanner::new(snippet, Semicolons::Automatic).scan();
+    let depth: i32 = tokens
+        .iter()
+        .map(|token| match token.kind {
+            Kind::LeftBrace => 1,
+            Kind::RightBrace => -1,
+            _ => 0,
+        })
+        .sum();
+
+    depth > 0
+}
+
+fn is_item(snippet: &str) -> bool {
+    match parse(snippet) {
+        Some(statements) => statements
+            .iter()
+            .all(|statement| !matches!(statement, Stmt::Let(_))),
+        None => false,
+    }
+}
+
+fn is_expression(snippet: &str) -> bool {
+    let statements = match parse(&format!("fn main() {{\n{}\n}}", snippet)) {
+        Some(statements) => statements,
+        None => return false,
+    };
+
+    let body = match statements.first() {
+        Some(Stmt::Function(function)) => &function.body,
+        _ => return false,
+    };
+
+    match body {
+        Stmt::Block(block) => match block.statements.as_slice() {
+            [Stmt::Expression(statement)] => {
+                !matches!(statement.expression, Expr::Call(_) | Expr::Set(_))
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place(lines: &[&str], snippet: &str) -> Diagnostic {
+        let body: Vec<String> = lines.iter().map(|s| s.to_string()).collect();
+        let (program, start) = assemble(&[], &body, false);
+        let place = Place {
+            snippet,
+            start,
+            line: program[..start].matches('\n').count() + 1,
+            offset: 0,
+        };
+
+        let reporter = Reporter::collector(&program, Vec::new(), None);
+        let options = Options::new();
+        blaze::compile_to(&reporter, &program, &options, Stage::Generate);
+
+        place.locate(reporter.take().remove(0))
+    }
+
+    #[test]
+    fn errors_point_at_the_typed_line() {
+        let diagnostic = place(
+            &["let a = 1", "let b = a\nlet c: str = b"],
+            "let b = a\nlet c: str = b",
+        );
+
+        assert_eq!(diagnostic.line, 2);
+        assert_eq!(diagnostic.span.map(|span| span.column), Some(5));
+    }
+
+    #[test]
+    fn braces_in_strings_and_comments_are_not_blocks() {
+        assert!(is_open("fn main() {\n"));
+        assert!(!is_open("fn main() {\n}\n"));
+        assert!(!is_open("print(\"{\")\n"));
+        assert!(!is_open("print(1) // {\n"));
+        assert!(is_open("if true { print(\"}\")\n"));
+    }
+}
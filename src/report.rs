@@ -0,0 +1,304 @@
+use std::cell::{Cell, RefCell};
+use std::env;
+use std::io::{self, IsTerminal};
+
+use crate::error::{Diagnostic, Level, Severity};
+use crate::scanner;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Human,
+    Json,
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                escaped.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    format!("\"{}\"", escaped)
+}
+
+pub struct Reporter {
+    file: String,
+    format: Format,
+    collect: bool,
+    source: String,
+    lints: Vec<(String, Level)>,
+    max_errors: Option<usize>,
+    errors: Cell<usize>,
+    stopped: Cell<bool>,
+    collected: RefCell<Vec<Diagnostic>>,
+    color: bool,
+}
+
+impl Reporter {
+    pub fn new(
+        file: &str,
+        format: Format,
+        source: &str,
+        lints: Vec<(String, Level)>,
+        max_errors: Option<usize>,
+    ) -> Self {
+        Self {
+            file: file.to_string(),
+            format,
+            collect: false,
+            source: source.to_string(),
+            lints,
+            max_errors,
+            errors: Cell::new(0),
+            stopped: Cell::new(false),
+            collected: RefCell::new(Vec::new()),
+            color: io::stderr().is_terminal()
+                && env::var_os("NO_COLOR").is_none(),
+        }
+    }
+
+    pub fn collector(
+        source: &str,
+        lints: Vec<(String, Level)>,
+        max_errors: Option<usize>,
+    ) -> Self {
+        Self {
+            collect: true,
+            ..Self::new("", Format::Human, source, lints, max_errors)
+        }
+    }
+
+    pub fn report<T: Into<Diagnostic>>(&self, diagnostics: Vec<T>) -> bool {
+        let mut failed = false;
+
+        if self.stopped.get() {
+            return true;
+        }
+
+        for diagnostic in diagnostics {
+            let mut diagnostic = diagnostic.into();
+            let mut denied = None;
+
+            if let Some(lint) = diagnostic.lint {
+                let level = self
+                    .lints
+                    .iter()
+                    .rev()
+                    .find(|(name, _)| name == lint || name == "warnings");
+
+                match level {
+                    Some((_, Level::Allow)) => continue,
+                    Some((name, Level::Deny)) => {
+                        diagnostic.severity = Severity::Error;
+                        denied = Some(name);
+                    }
+                    _ => (),
+                }
+            }
+
+            if diagnostic.severity == Severity::Error {
+                if self.max_errors == Some(self.errors.get()) {
+                    if self.format == Format::Human && !self.collect {
+                        eprintln!(
+                            "Stopping after {} errors.",
+                            self.errors.get()
+                        );
+                    }
+
+                    self.stopped.set(true);
+                    return true;
+                }
+
+                self.errors.set(self.errors.get() + 1);
+                failed = true;
+            }
+
+            if self.collect {
+                self.collected.borrow_mut().push(diagnostic);
+                continue;
+            }
+
+            self.emit(&diagnostic);
+
+            if let Some(note) = diagnostic.note.take() {
+                self.note(&diagnostic, note);
+            }
+
+            if let Some(name) = denied {
+                self.note(
+                    &diagnostic,
+                    format!("'-D {}' turns this into an error.", name),
+                );
+            }
+        }
+
+        failed
+    }
+
+    pub fn take(&self) -> Vec<Diagnostic> {
+        self.collected.take()
+    }
+
+    fn note(&self, diagnostic: &Diagnostic, message: String) {
+        self.emit(&Diagnostic {
+            severity: Severity::Note,
+            code: None,
+            location: String::new(),
+            message,
+            note: None,
+            lint: None,
+            ..*diagnostic
+        });
+    }
+
+    fn emit(&self, diagnostic: &Diagnostic) {
+        match self.format {
+            Format::Human => {
+                eprintln!("{}", self.render(diagnostic));
+
+                if let Some(snippet) = self.snippet(diagnostic) {
+                    eprintln!("{}", snippet);
+                }
+            }
+            Format::Json => eprintln!("{}", self.json(diagnostic)),
+        }
+    }
+
+    fn json(&self, diagnostic: &Diagnostic) -> String {
+        let severity = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        };
+
+        let (column, span) = match diagnostic.span {
+            Some(span) => (
+                span.column.to_string(),
+                format!("{{\"start\":{},\"end\":{}}}", span.start, span.end),
+            ),
+            None => ("null".to_string(), "null".to_string()),
+        };
+
+        format!(
+            concat!(
+                "{{\"file\":{},\"line\":{},\"column\":{},\"span\":{},",
+                "\"severity\":\"{}\",\"code\":{},\"lint\":{},",
+                "\"message\":{}}}"
+            ),
+            escape(&self.file),
+            diagnostic.line,
+            column,
+            span,
+            severity,
+            diagnostic
+                .code
+                .map_or("null".to_string(), |code| escape(code.name())),
+            diagnostic.lint.map_or("null".to_string(), escape),
+            escape(&diagnostic.message)
+        )
+    }
+
+    fn paint(&self, text: &str, color: &str) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", color, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn snippet(&self, diagnostic: &Diagnostic) -> Option<String> {
+        let span = diagnostic.span?;
+
+        if diagnostic.severity == Severity::Note {
+            return None;
+        }
+
+        let before = self.source.get(..span.start)?;
+        let start = before
+            .rfind('\n')
+            .map_or(scanner::bom(&self.source), |index| index + 1);
+        let end = self.source[span.start..]
+            .find('\n')
+            .map_or(self.source.len(), |index| span.start + index);
+        let end = start + self.source[start..end].trim_end_matches('\r').len();
+
+        let number = (before.matches('\n').count() + 1).to_string();
+        let gutter = " ".repeat(number.len());
+        let bar = self.paint("|", "1;34");
+
+        let padding: String = self.source[start..span.start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        let width = self.source[span.start..span.end.clamp(span.start, end)]
+            .chars()
+            .count()
+            .max(1);
+
+        let carets = self.paint(
+            &"^".repeat(width),
+            match diagnostic.severity {
+                Severity::Warning => "1;33",
+                _ => "1;31",
+            },
+        );
+
+        Some(format!(
+            "{} {}\n{} {} {}\n{} {} {}{}",
+            gutter,
+            bar,
+            self.paint(&number, "1;34"),
+            bar,
+            &self.source[start..end],
+            gutter,
+            bar,
+            padding,
+            carets
+        ))
+    }
+
+    fn render(&self, diagnostic: &Diagnostic) -> String {
+        if !self.color {
+            return diagnostic.to_string();
+        }
+
+        let (label, color) = match diagnostic.severity {
+            Severity::Error => ("Error", 31),
+            Severity::Warning => ("Warning", 33),
+            Severity::Note => ("Note", 36),
+        };
+
+        let code = match diagnostic.code {
+            Some(code) => format!("[{}]", code.name()),
+            None => String::new(),
+        };
+
+        let position = match diagnostic.span {
+            Some(span) => {
+                format!("[line {}, column {}] ", diagnostic.line, span.column)
+            }
+            None if diagnostic.line == 0 => String::new(),
+            None => format!("[line {}] ", diagnostic.line),
+        };
+
+        format!(
+            "{}\x1b[1;{}m{}{}\x1b[0m\x1b[1m{}: {}\x1b[0m",
+            position,
+            color,
+            label,
+            code,
+            diagnostic.location,
+            diagnostic.message
+        )
+    }
+}
@@ -0,0 +1,1037 @@
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+use crate::code::Code;
+use crate::error::{ResolveError, Warning};
+use crate::expr;
+use crate::pattern;
+use crate::stmt;
+use crate::suggest::suggest;
+use crate::token::Token;
+use crate::variant;
+
+const BUILTINS: &[&str] = &[
+    "abs",
+    "add",
+    "args",
+    "assert",
+    "bit",
+    "checked_add",
+    "checked_div",
+    "checked_mul",
+    "checked_sub",
+    "clock",
+    "contains",
+    "cos",
+    "count_ones",
+    "count_zeros",
+    "div",
+    "env",
+    "floor",
+    "get",
+    "input",
+    "insert",
+    "input_num",
+    "leading_zeros",
+    "len",
+    "lower",
+    "parse_num",
+    "pow",
+    "pop",
+    "print",
+    "push",
+    "random",
+    "remove",
+    "rotate_left",
+    "rotate_right",
+    "saturating_add",
+    "saturating_mul",
+    "saturating_sub",
+    "sin",
+    "sqrt",
+    "substring",
+    "to_string",
+    "trailing_zeros",
+    "upper",
+    "wrapping_add",
+    "wrapping_div",
+    "wrapping_mul",
+    "wrapping_neg",
+    "wrapping_sub",
+];
+
+const MUTATORS: &[&str] = &["add", "insert", "pop", "push", "remove"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Symbol {
+    Variable,
+    Constant,
+    Function,
+}
+
+impl Symbol {
+    fn kind(self) -> &'static str {
+        match self {
+            Self::Variable => "Variable",
+            Self::Constant => "Constant",
+            Self::Function => "Function",
+        }
+    }
+}
+
+struct Binding {
+    symbol: Symbol,
+    name: Option<Token>,
+    used: bool,
+}
+
+impl Binding {
+    fn new(symbol: Symbol, name: Option<Token>) -> Self {
+        Self {
+            symbol,
+            name,
+            used: false,
+        }
+    }
+}
+
+// A variable, constant or function along with every place it's used, for
+// tools that need go-to-definition or find-references.
+#[derive(Clone, Debug)]
+pub struct Definition {
+    pub kind: &'static str,
+    pub name: Token,
+    pub references: Vec<Token>,
+}
+
+pub struct Resolver {
+    errors: Vec<ResolveError>,
+    warnings: Vec<Warning>,
+    scopes: Vec<HashMap<String, Binding>>,
+    deprecated: HashMap<String, Option<String>>,
+    types: HashMap<String, (&'static str, Option<String>)>,
+    methods: HashMap<(String, String), Token>,
+    owner: Option<String>,
+    uses: HashMap<String, Vec<String>>,
+    symbols: Vec<Definition>,
+    definitions: HashMap<usize, usize>,
+    boundary: usize,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self {
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            scopes: Vec::new(),
+            deprecated: HashMap::new(),
+            types: HashMap::new(),
+            methods: HashMap::new(),
+            owner: None,
+            uses: HashMap::new(),
+            symbols: Vec::new(),
+            definitions: HashMap::new(),
+            boundary: 1,
+        }
+    }
+
+    pub fn resolve(
+        &mut self,
+        statements: &[stmt::Stmt],
+    ) -> (Vec<Warning>, Vec<ResolveError>) {
+        self.symbols.clear();
+        self.scopes.push(
+            BUILTINS
+                .iter()
+                .map(|name| {
+                    (name.to_string(), Binding::new(Symbol::Function, None))
+                })
+                .collect(),
+        );
+
+        self.declarations(statements);
+
+        for statement in statements {
+            let (kind, name, deprecated) = match statement {
+                stmt::Stmt::Function(stmt) => {
+                    if let Some(deprecated) = &stmt.deprecated {
+                        self.deprecated.insert(
+                            stmt.name.lexeme.clone(),
+                            deprecated.note.clone(),
+                        );
+                    }
+
+                    continue;
+                }
+                stmt::Stmt::Struct(stmt) => {
+                    ("Struct", &stmt.name, &stmt.deprecated)
+                }
+                stmt::Stmt::Enum(stmt) => {
+                    ("Enum", &stmt.name, &stmt.deprecated)
+                }
+                stmt::Stmt::Type(stmt) => {
+                    ("Type", &stmt.name, &stmt.deprecated)
+                }
+                _ => continue,
+            };
+
+            if let Some(deprecated) = deprecated {
+                self.types.insert(
+                    name.lexeme.clone(),
+                    (kind, deprecated.note.clone()),
+                );
+            }
+        }
+
+        for statement in statements {
+            match statement {
+                stmt::Stmt::Let(stmt) => {
+                    self.declare(&stmt.name, Symbol::Variable)
+                }
+                stmt::Stmt::Const(stmt) => {
+                    self.declare(&stmt.name, Symbol::Constant)
+                }
+                _ => (),
+            }
+        }
+
+        for statement in statements {
+            self.owner = match statement {
+                stmt::Stmt::Let(stmt) => Some(stmt.name.lexeme.clone()),
+                stmt::Stmt::Const(stmt) => Some(stmt.name.lexeme.clone()),
+                stmt::Stmt::Function(stmt) => Some(stmt.name.lexeme.clone()),
+                _ => None,
+            };
+
+            statement.accept(self);
+        }
+
+        self.owner = None;
+        self.cycles(statements);
+
+        if let Some(scope) = self.scopes.pop() {
+            self.unused(scope, &[Symbol::Function]);
+        }
+
+        self.warnings.sort_by_key(|warning| warning.span.start);
+
+        self.scopes.clear();
+        self.deprecated.clear();
+        self.types.clear();
+        self.methods.clear();
+        self.uses.clear();
+        self.definitions.clear();
+
+        (mem::take(&mut self.warnings), mem::take(&mut self.errors))
+    }
+
+    // Definitions found by the last call to resolve, in the order they appear
+    // in the source.
+    pub fn symbols(&mut self) -> Vec<Definition> {
+        let mut symbols = mem::take(&mut self.symbols);
+        symbols.sort_by_key(|symbol| symbol.name.span.start);
+
+        for symbol in symbols.iter_mut() {
+            symbol
+                .references
+                .sort_by_key(|reference| reference.span.start);
+        }
+
+        symbols
+    }
+
+    // Globals and constants are initialized the first time they're read, so
+    // an initializer that reaches its own global, directly or through the
+    // functions it calls, would recurse until the stack runs out.
+    fn cycles(&mut self, statements: &[stmt::Stmt]) {
+        for statement in statements {
+            let name = match statement {
+                stmt::Stmt::Let(stmt) => &stmt.name,
+                stmt::Stmt::Const(stmt) => &stmt.name,
+                _ => continue,
+            };
+
+            let mut path = vec![name.lexeme.clone()];
+            let mut seen = HashSet::new();
+
+            if self.reaches(&name.lexeme, &mut path, &mut seen) {
+                let path: Vec<String> =
+                    path.iter().map(|name| format!("'{}'", name)).collect();
+
+                self.errors.push(ResolveError {
+                    line: name.line,
+                    span: name.span,
+                    code: Code::CyclicInitializer,
+                    message: Code::CyclicInitializer.message(&[&name.lexeme]),
+                    note: Some(format!(
+                        "It goes through {}.",
+                        path.join(" -> ")
+                    )),
+                });
+            }
+        }
+    }
+
+    fn reaches(
+        &self,
+        target: &str,
+        path: &mut Vec<String>,
+        seen: &mut HashSet<String>,
+    ) -> bool {
+        let current = match path.last() {
+            Some(current) => current.clone(),
+            None => return false,
+        };
+
+        for used in self.uses.get(&current).into_iter().flatten() {
+            path.push(used.clone());
+
+            if used == target
+                || seen.insert(used.clone()) && self.reaches(target, path, seen)
+            {
+                return true;
+            }
+
+            path.pop();
+        }
+
+        false
+    }
+
+    fn declarations(&mut self, statements: &[stmt::Stmt]) {
+        for statement in statements {
+            if let stmt::Stmt::Function(stmt) = statement {
+                self.declare(&stmt.name, Symbol::Function);
+            }
+        }
+    }
+
+    // Everything at the top level shares one namespace, while inside a
+    // function a variable can shadow another but a nested function can't.
+    fn declare(&mut self, name: &Token, symbol: Symbol) {
+        self.reserve(name);
+
+        let global = self.scopes.len() == 1;
+        let first = self
+            .scopes
+            .last()
+            .and_then(|scope| scope.get(&name.lexeme))
+            .filter(|binding| {
+                global || symbol == Symbol::Function && binding.symbol == symbol
+            })
+            .and_then(|binding| binding.name.clone());
+
+        if let Some(first) = first {
+            return self.duplicate(symbol.kind(), name, &first);
+        }
+
+        self.define(name, symbol);
+
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(
+                name.lexeme.clone(),
+                Binding::new(symbol, Some(name.clone())),
+            );
+        }
+    }
+
+    fn define(&mut self, name: &Token, symbol: Symbol) {
+        self.definitions.insert(name.span.start, self.symbols.len());
+        self.symbols.push(Definition {
+            kind: symbol.kind(),
+            name: name.clone(),
+            references: Vec::new(),
+        });
+    }
+
+    fn end_scope(&mut self) {
+        if let Some(scope) = self.scopes.pop() {
+            self.unused(
+                scope,
+                &[Symbol::Variable, Symbol::Constant, Symbol::Function],
+            );
+        }
+    }
+
+    fn unused(&mut self, scope: HashMap<String, Binding>, symbols: &[Symbol]) {
+        let mut unused: Vec<(Token, Symbol)> = scope
+            .into_values()
+            .filter(|binding| {
+                !binding.used && symbols.contains(&binding.symbol)
+            })
+            .filter_map(|binding| Some((binding.name?, binding.symbol)))
+            .filter(|(name, _)| !name.lexeme.starts_with('_'))
+            .filter(|(name, symbol)| {
+                *symbol != Symbol::Function || name.lexeme != "main"
+            })
+            .collect();
+
+        unused.sort_by_key(|(name, _)| name.span.start);
+
+        for (name, symbol) in unused {
+            self.warnings.push(Warning {
+                line: name.line,
+                span: name.span,
+                code: Code::UnusedItem,
+                lint: "unused",
+                message: Code::UnusedItem
+                    .message(&[&symbol.kind(), &name.lexeme]),
+            });
+        }
+    }
+
+    fn reserve(&mut self, name: &Token) {
+        if name.lexeme.starts_with("__") {
+            self.error(
+                name,
+                Code::ReservedName,
+                &Code::ReservedName.message(&[&name.lexeme]),
+            );
+        }
+    }
+
+    fn lookup(&mut self, name: &Token) -> Option<Symbol> {
+        let found = self.scopes.iter_mut().enumerate().rev().find_map(
+            |(depth, scope)| {
+                let binding = scope.get_mut(&name.lexeme)?;
+                binding.used = true;
+
+                Some((depth, binding.symbol, binding.name.clone()))
+            },
+        );
+
+        match found {
+            Some((depth, symbol, definition)) => {
+                let declared = definition.is_some();
+
+                let index = definition
+                    .and_then(|token| self.definitions.get(&token.span.start));
+
+                if let Some(&index) = index {
+                    self.symbols[index].references.push(name.clone());
+                }
+
+                if let Some(owner) = &self.owner {
+                    if depth == 0 && declared {
+                        self.uses
+                            .entry(owner.clone())
+                            .or_default()
+                            .push(name.lexeme.clone());
+                    }
+                }
+
+                if depth > 0
+                    && depth < self.boundary
+                    && symbol != Symbol::Function
+                {
+                    self.error(
+                        name,
+                        Code::CapturedLocal,
+                        Code::CapturedLocal.template(),
+                    );
+                }
+
+                Some(symbol)
+            }
+            None => None,
+        }
+    }
+
+    fn binding(&self, name: &str) -> Option<&Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    // Fields and elements of a constant are as read-only as the constant
+    // itself, so a target is checked all the way down to its root.
+    fn constant(&mut self, target: &expr::Expr) {
+        let root = match target {
+            expr::Expr::Variable(variable) => &variable.name,
+            expr::Expr::Get(get) => return self.constant(&get.object),
+            expr::Expr::Index(index) => return self.constant(&index.object),
+            expr::Expr::Grouping(grouping) => {
+                return self.constant(&grouping.expression)
+            }
+            _ => return,
+        };
+
+        let symbol = self.binding(&root.lexeme).map(|binding| binding.symbol);
+
+        if symbol == Some(Symbol::Constant) {
+            self.error(
+                root,
+                Code::InvalidAssignment,
+                &Code::InvalidAssignment.message(&[&"constant", &root.lexeme]),
+            );
+        }
+    }
+
+    fn duplicate(&mut self, kind: &str, name: &Token, first: &Token) {
+        self.errors.push(ResolveError {
+            line: name.line,
+            span: name.span,
+            code: Code::DuplicateName,
+            message: Code::DuplicateName.message(&[&kind, &name.lexeme]),
+            note: Some(format!("The first one is on line {}.", first.line)),
+        });
+    }
+
+    fn unique<'a>(
+        &mut self,
+        kind: &str,
+        names: impl Iterator<Item = &'a Token>,
+    ) {
+        let mut seen: HashMap<&str, &Token> = HashMap::new();
+
+        for name in names {
+            match seen.get(name.lexeme.as_str()) {
+                Some(first) => self.duplicate(kind, name, first),
+                None => {
+                    seen.insert(&name.lexeme, name);
+                }
+            }
+        }
+    }
+
+    fn error(&mut self, token: &Token, code: Code, message: &str) {
+        self.errors.push(ResolveError {
+            line: token.line,
+            span: token.span,
+            code,
+            message: message.to_string(),
+            note: None,
+        });
+    }
+
+    fn deprecation(&mut self, name: &Token) {
+        let global = self
+            .scopes
+            .iter()
+            .rposition(|scope| scope.contains_key(&name.lexeme))
+            == Some(0);
+
+        let note = match self.deprecated.get(&name.lexeme) {
+            Some(note) if global => note.clone(),
+            _ => return,
+        };
+
+        self.deprecate(name, "Function", note);
+    }
+
+    fn type_deprecation(&mut self, name: &Token) {
+        if let Some((kind, note)) = self.types.get(&name.lexeme).cloned() {
+            self.deprecate(name, kind, note);
+        }
+    }
+
+    fn deprecate(&mut self, name: &Token, kind: &str, note: Option<String>) {
+        let message = match note {
+            Some(note) => {
+                format!("{} '{}' is deprecated: {}", kind, name.lexeme, note)
+            }
+            None => format!("{} '{}' is deprecated.", kind, name.lexeme),
+        };
+
+        self.warnings.push(Warning {
+            line: name.line,
+            span: name.span,
+            code: Code::DeprecatedItem,
+            lint: "deprecated",
+            message,
+        });
+    }
+
+    fn undefined(&mut self, token: &Token, kind: &str, symbols: &[Symbol]) {
+        let note = suggest(
+            &token.lexeme,
+            self.scopes
+                .iter()
+                .flat_map(|scope| scope.iter())
+                .filter(|(_, binding)| symbols.contains(&binding.symbol))
+                .map(|(name, _)| name.as_str()),
+        );
+
+        self.errors.push(ResolveError {
+            line: token.line,
+            span: token.span,
+            code: Code::UndefinedName,
+            message: Code::UndefinedName.message(&[&kind, &token.lexeme]),
+            note,
+        });
+    }
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl expr::Visitor for Resolver {
+    type Result = ();
+
+    fn visit_conditional_expr(
+        &mut self,
+        expr: &expr::Conditional,
+    ) -> Self::Result {
+        expr.condition.accept(self);
+        expr.then_branch.accept(self);
+        expr.else_branch.accept(self);
+    }
+
+    fn visit_logical_expr(&mut self, expr: &expr::Logical) -> Self::Result {
+        expr.left.accept(self);
+        expr.right.accept(self);
+    }
+
+    fn visit_binary_expr(&mut self, expr: &expr::Binary) -> Self::Result {
+        expr.left.accept(self);
+        expr.right.accept(self);
+    }
+
+    fn visit_loop_expr(&mut self, expr: &expr::Loop) -> Self::Result {
+        expr.body.accept(self);
+    }
+
+    fn visit_cast_expr(&mut self, expr: &expr::Cast) -> Self::Result {
+        expr.value.accept(self);
+    }
+
+    fn visit_unary_expr(&mut self, expr: &expr::Unary) -> Self::Result {
+        expr.right.accept(self);
+    }
+
+    fn visit_call_expr(&mut self, expr: &expr::Call) -> Self::Result {
+        match &expr.callee {
+            expr::Expr::Variable(callee) => match self.lookup(&callee.name) {
+                Some(Symbol::Function) => self.deprecation(&callee.name),
+                Some(_) => (),
+                None => self.undefined(
+                    &callee.name,
+                    "function",
+                    &[Symbol::Function],
+                ),
+            },
+            callee => callee.accept(self),
+        }
+
+        if let expr::Expr::Variable(callee) = &expr.callee {
+            let builtin =
+                self.binding(&callee.name.lexeme).is_some_and(|binding| {
+                    binding.symbol == Symbol::Function && binding.name.is_none()
+                });
+
+            if builtin && MUTATORS.contains(&callee.name.lexeme.as_str()) {
+                if let Some(collection) = expr.arguments.first() {
+                    self.constant(collection);
+                }
+            }
+        }
+
+        for argument in expr.arguments.iter() {
+            argument.accept(self);
+        }
+    }
+
+    fn visit_get_expr(&mut self, expr: &expr::Get) -> Self::Result {
+        expr.object.accept(self);
+    }
+
+    fn visit_set_expr(&mut self, expr: &expr::Set) -> Self::Result {
+        self.constant(&expr.object);
+        expr.object.accept(self);
+        expr.value.accept(self);
+    }
+
+    fn visit_index_expr(&mut self, expr: &expr::Index) -> Self::Result {
+        expr.object.accept(self);
+        expr.index.accept(self);
+    }
+
+    fn visit_set_index_expr(&mut self, expr: &expr::SetIndex) -> Self::Result {
+        self.constant(&expr.object);
+        expr.object.accept(self);
+        expr.index.accept(self);
+        expr.value.accept(self);
+    }
+
+    fn visit_grouping_expr(&mut self, expr: &expr::Grouping) -> Self::Result {
+        expr.expression.accept(self);
+    }
+
+    fn visit_array_expr(&mut self, expr: &expr::Array) -> Self::Result {
+        for element in expr.elements.iter() {
+            element.accept(self);
+        }
+    }
+
+    fn visit_map_expr(&mut self, expr: &expr::Map) -> Self::Result {
+        for (key, value) in expr.entries.iter() {
+            key.accept(self);
+            value.accept(self);
+        }
+    }
+
+    fn visit_set_literal_expr(
+        &mut self,
+        expr: &expr::SetLiteral,
+    ) -> Self::Result {
+        for element in expr.elements.iter() {
+            element.accept(self);
+        }
+    }
+
+    fn visit_struct_expr(&mut self, expr: &expr::Struct) -> Self::Result {
+        self.type_deprecation(&expr.name);
+
+        for (_, value) in expr.fields.iter() {
+            value.accept(self);
+        }
+
+        if let Some(base) = &expr.base {
+            base.accept(self);
+        }
+    }
+
+    fn visit_path_expr(&mut self, expr: &expr::Path) -> Self::Result {
+        self.type_deprecation(&expr.namespace);
+    }
+
+    fn visit_variable_expr(&mut self, expr: &expr::Variable) -> Self::Result {
+        if self.lookup(&expr.name).is_none() {
+            self.undefined(
+                &expr.name,
+                "variable",
+                &[Symbol::Variable, Symbol::Constant],
+            );
+        }
+    }
+
+    fn visit_literal_expr(&mut self, _expr: &expr::Literal) -> Self::Result {}
+}
+
+impl stmt::Visitor for Resolver {
+    type Result = ();
+
+    fn visit_if_stmt(&mut self, stmt: &stmt::If) -> Self::Result {
+        stmt.condition.accept(self);
+        stmt.then_branch.accept(self);
+
+        if let Some(branch) = &stmt.else_branch {
+            branch.accept(self);
+        }
+    }
+
+    fn visit_match_stmt(&mut self, stmt: &stmt::Match) -> Self::Result {
+        stmt.value.accept(self);
+
+        for (pattern, guard, body) in stmt.arms.iter() {
+            self.scopes.push(HashMap::new());
+            pattern.accept(self);
+
+            if let Some(guard) = guard {
+                guard.accept(self);
+            }
+
+            body.accept(self);
+            self.end_scope();
+        }
+    }
+
+    fn visit_function_stmt(&mut self, stmt: &stmt::Function) -> Self::Result {
+        let mut scope = HashMap::new();
+
+        self.unique("Parameter", stmt.parameters.iter().map(|(name, _)| name));
+
+        for (name, variant) in stmt.parameters.iter() {
+            self.reserve(name);
+            variant.accept(self);
+            self.define(name, Symbol::Variable);
+            scope.insert(
+                name.lexeme.clone(),
+                Binding::new(Symbol::Variable, Some(name.clone())),
+            );
+        }
+
+        if let Some(output) = &stmt.output {
+            output.accept(self);
+        }
+
+        if stmt.receiver {
+            scope.insert(
+                "self".to_string(),
+                Binding::new(Symbol::Variable, None),
+            );
+        }
+
+        self.scopes.push(scope);
+
+        let boundary = mem::replace(&mut self.boundary, self.scopes.len() - 1);
+        stmt.body.accept(self);
+        self.boundary = boundary;
+
+        self.end_scope();
+    }
+
+    fn visit_test_stmt(&mut self, stmt: &stmt::Test) -> Self::Result {
+        self.scopes.push(HashMap::new());
+
+        let boundary = mem::replace(&mut self.boundary, self.scopes.len() - 1);
+        stmt.body.accept(self);
+        self.boundary = boundary;
+
+        self.end_scope();
+    }
+
+    fn visit_bench_stmt(&mut self, stmt: &stmt::Bench) -> Self::Result {
+        self.scopes.push(HashMap::new());
+
+        let boundary = mem::replace(&mut self.boundary, self.scopes.len() - 1);
+        stmt.body.accept(self);
+        self.boundary = boundary;
+
+        self.end_scope();
+    }
+
+    fn visit_return_stmt(&mut self, stmt: &stmt::Return) -> Self::Result {
+        if let Some(value) = &stmt.value {
+            value.accept(self);
+        }
+    }
+
+    fn visit_raise_stmt(&mut self, stmt: &stmt::Raise) -> Self::Result {
+        stmt.value.accept(self);
+    }
+
+    fn visit_try_stmt(&mut self, stmt: &stmt::Try) -> Self::Result {
+        stmt.body.accept(self);
+
+        self.scopes.push(HashMap::new());
+
+        if let Some(error) = &stmt.error {
+            self.declare(error, Symbol::Variable);
+        }
+
+        stmt.handler.accept(self);
+        self.end_scope();
+    }
+
+    fn visit_loop_stmt(&mut self, stmt: &stmt::Loop) -> Self::Result {
+        stmt.body.accept(self);
+    }
+
+    fn visit_for_stmt(&mut self, stmt: &stmt::For) -> Self::Result {
+        stmt.start.accept(self);
+        stmt.end.accept(self);
+
+        self.scopes.push(HashMap::new());
+        self.declare(&stmt.variable, Symbol::Variable);
+        stmt.body.accept(self);
+        self.end_scope();
+    }
+
+    fn visit_break_stmt(&mut self, stmt: &stmt::Break) -> Self::Result {
+        if let Some(value) = &stmt.value {
+            value.accept(self);
+        }
+    }
+
+    fn visit_continue_stmt(&mut self, _stmt: &stmt::Continue) -> Self::Result {}
+
+    fn visit_let_stmt(&mut self, stmt: &stmt::Let) -> Self::Result {
+        if let Some(variant) = &stmt.variant {
+            variant.accept(self);
+        }
+
+        if let Some(initializer) = &stmt.initializer {
+            initializer.accept(self);
+        }
+
+        if self.scopes.len() > 1 {
+            self.declare(&stmt.name, Symbol::Variable);
+        }
+    }
+
+    fn visit_const_stmt(&mut self, stmt: &stmt::Const) -> Self::Result {
+        stmt.variant.accept(self);
+        stmt.initializer.accept(self);
+
+        if self.scopes.len() > 1 {
+            self.declare(&stmt.name, Symbol::Constant);
+        }
+    }
+
+    fn visit_type_stmt(&mut self, stmt: &stmt::Type) -> Self::Result {
+        self.reserve(&stmt.name);
+        stmt.variant.accept(self);
+    }
+
+    fn visit_struct_stmt(&mut self, stmt: &stmt::Struct) -> Self::Result {
+        self.reserve(&stmt.name);
+        self.unique("Field", stmt.fields.iter().map(|(name, _)| name));
+
+        for (_, variant) in stmt.fields.iter() {
+            variant.accept(self);
+        }
+    }
+
+    fn visit_enum_stmt(&mut self, stmt: &stmt::Enum) -> Self::Result {
+        self.reserve(&stmt.name);
+        self.unique("Variant", stmt.variants.iter().map(|(name, _)| name));
+
+        for (_, fields) in stmt.variants.iter() {
+            for variant in fields.iter() {
+                variant.accept(self);
+            }
+        }
+    }
+
+    fn visit_impl_stmt(&mut self, stmt: &stmt::Impl) -> Self::Result {
+        self.type_deprecation(&stmt.name);
+
+        for method in stmt.methods.iter() {
+            if let stmt::Stmt::Function(method) = method {
+                let key =
+                    (stmt.name.lexeme.clone(), method.name.lexeme.clone());
+
+                match self.methods.get(&key).cloned() {
+                    Some(first) => {
+                        self.duplicate("Method", &method.name, &first)
+                    }
+                    None => {
+                        self.methods.insert(key, method.name.clone());
+                    }
+                }
+            }
+
+            method.accept(self);
+        }
+    }
+
+    fn visit_block_stmt(&mut self, stmt: &stmt::Block) -> Self::Result {
+        self.scopes.push(HashMap::new());
+        self.declarations(&stmt.statements);
+
+        for statement in stmt.statements.iter() {
+            statement.accept(self);
+        }
+
+        self.end_scope();
+    }
+
+    fn visit_assignment_stmt(
+        &mut self,
+        stmt: &stmt::Assignment,
+    ) -> Self::Result {
+        stmt.value.accept(self);
+
+        match self.lookup(&stmt.name) {
+            Some(Symbol::Variable) => (),
+            Some(Symbol::Constant) => self.error(
+                &stmt.name,
+                Code::InvalidAssignment,
+                &Code::InvalidAssignment
+                    .message(&[&"constant", &stmt.name.lexeme]),
+            ),
+            Some(Symbol::Function) => self.error(
+                &stmt.name,
+                Code::InvalidAssignment,
+                &Code::InvalidAssignment
+                    .message(&[&"function", &stmt.name.lexeme]),
+            ),
+            None => self.undefined(&stmt.name, "variable", &[Symbol::Variable]),
+        }
+    }
+
+    fn visit_expression_stmt(
+        &mut self,
+        stmt: &stmt::Expression,
+    ) -> Self::Result {
+        stmt.expression.accept(self);
+    }
+}
+
+impl pattern::Visitor for Resolver {
+    type Result = ();
+
+    fn visit_literal_pattern(
+        &mut self,
+        _pattern: &pattern::Literal,
+    ) -> Self::Result {
+    }
+
+    fn visit_path_pattern(&mut self, pattern: &pattern::Path) -> Self::Result {
+        self.type_deprecation(&pattern.namespace);
+
+        for field in pattern.fields.iter() {
+            field.accept(self);
+        }
+    }
+
+    fn visit_binding_pattern(
+        &mut self,
+        pattern: &pattern::Binding,
+    ) -> Self::Result {
+        self.declare(&pattern.name, Symbol::Variable);
+    }
+
+    fn visit_wildcard_pattern(
+        &mut self,
+        _pattern: &pattern::Wildcard,
+    ) -> Self::Result {
+    }
+}
+
+impl variant::Visitor for Resolver {
+    type Result = ();
+
+    fn visit_literal_variant(
+        &mut self,
+        variant: &variant::Literal,
+    ) -> Self::Result {
+        self.type_deprecation(&variant.name);
+    }
+
+    fn visit_function_variant(
+        &mut self,
+        variant: &variant::Function,
+    ) -> Self::Result {
+        for parameter in variant.parameters.iter() {
+            parameter.accept(self);
+        }
+
+        if let Some(output) = &variant.output {
+            output.accept(self);
+        }
+    }
+
+    fn visit_array_variant(
+        &mut self,
+        variant: &variant::Array,
+    ) -> Self::Result {
+        variant.element.accept(self);
+    }
+
+    fn visit_map_variant(&mut self, variant: &variant::Map) -> Self::Result {
+        variant.key.accept(self);
+        variant.value.accept(self);
+    }
+
+    fn visit_set_variant(&mut self, variant: &variant::Set) -> Self::Result {
+        variant.element.accept(self);
+    }
+
+    fn visit_optional_variant(
+        &mut self,
+        variant: &variant::Optional,
+    ) -> Self::Result {
+        variant.inner.accept(self);
+    }
+
+    fn visit_fallible_variant(
+        &mut self,
+        variant: &variant::Fallible,
+    ) -> Self::Result {
+        variant.value.accept(self);
+        variant.error.accept(self);
+    }
+}
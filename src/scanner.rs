@@ -1,44 +1,101 @@
 use std::mem;
 
+use crate::code::Code;
 use crate::error::SyntaxError;
 use crate::kind::Kind;
-use crate::token::Token;
+use crate::token::{Span, Token};
+
+pub const KEYWORDS: &[(&str, Kind)] = &[
+    ("if", Kind::If),
+    ("else", Kind::Else),
+    ("match", Kind::Match),
+    ("fn", Kind::Fn),
+    ("return", Kind::Return),
+    ("raise", Kind::Raise),
+    ("try", Kind::Try),
+    ("catch", Kind::Catch),
+    ("false", Kind::False),
+    ("true", Kind::True),
+    ("none", Kind::None),
+    ("loop", Kind::Loop),
+    ("for", Kind::For),
+    ("in", Kind::In),
+    ("as", Kind::As),
+    ("break", Kind::Break),
+    ("continue", Kind::Continue),
+    ("let", Kind::Let),
+    ("const", Kind::Const),
+    ("type", Kind::Type),
+    ("struct", Kind::Struct),
+    ("enum", Kind::Enum),
+    ("impl", Kind::Impl),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Semicolons {
+    Automatic,
+    Explicit,
+}
 
 #[derive(Debug)]
 pub struct Scanner {
-    source: String,
+    source: Vec<char>,
+    semicolons: Semicolons,
     tokens: Vec<Token>,
     errors: Vec<SyntaxError>,
+    docs: Vec<String>,
     start: usize,
     current: usize,
     line: usize,
     parens: i32,
+    offset: usize,
+    span: Span,
+    line_start: usize,
+}
+
+pub fn bom(source: &str) -> usize {
+    if source.starts_with('\u{feff}') {
+        '\u{feff}'.len_utf8()
+    } else {
+        0
+    }
 }
 
 impl Scanner {
-    pub fn new(source: &str) -> Self {
+    pub fn new(source: &str, semicolons: Semicolons) -> Self {
         Self {
-            source: source.to_string(),
+            source: source[bom(source)..].chars().collect(),
+            semicolons,
             tokens: Vec::new(),
             errors: Vec::new(),
+            docs: Vec::new(),
             start: 0,
             current: 0,
             line: 1,
             parens: 0,
+            offset: bom(source),
+            span: Span::default(),
+            line_start: 0,
         }
     }
 
     pub fn scan(&mut self) -> (Vec<Token>, Vec<SyntaxError>) {
         while !self.is_at_end() {
             self.start = self.current;
+            self.span = self.here();
             self.scan_token();
         }
 
-        self.add_semicolon();
+        if self.semicolons == Semicolons::Automatic {
+            self.add_semicolon(self.here());
+        }
+
         self.tokens.push(Token {
-            kind: Kind::EOF,
+            kind: Kind::Eof,
             lexeme: String::new(),
             line: self.line,
+            span: self.here(),
+            doc: None,
         });
 
         let tokens = mem::take(&mut self.tokens);
@@ -59,31 +116,54 @@ impl Scanner {
             }
             '{' => self.add_token(Kind::LeftBrace),
             '}' => self.add_token(Kind::RightBrace),
+            '[' => {
+                self.add_token(Kind::LeftBracket);
+                self.parens += 1;
+            }
+            ']' => {
+                self.add_token(Kind::RightBracket);
+                self.parens -= 1;
+            }
             ',' => self.add_token(Kind::Comma),
+            '.' if self.compare('.') => self.add_token(Kind::DotDot),
+            '.' => self.add_token(Kind::Dot),
             '+' => self.add_token(Kind::Plus),
             '-' => self.add_token(Kind::Minus),
             '*' => self.add_token(Kind::Star),
             '/' if self.compare('/') => self.scan_comment(),
+            '/' if self.compare('*') => self.scan_block_comment(),
             '/' => self.add_token(Kind::Slash),
+            '?' if self.compare('?') => self.add_token(Kind::QuestionQuestion),
             '?' => self.add_token(Kind::Question),
+            ':' if self.compare(':') => self.add_token(Kind::ColonColon),
             ':' => self.add_token(Kind::Colon),
             ';' => self.add_token(Kind::Semicolon),
+            '@' => self.add_token(Kind::At),
             '!' if self.compare('=') => self.add_token(Kind::BangEqual),
             '!' => self.add_token(Kind::Bang),
             '=' if self.compare('=') => self.add_token(Kind::EqualEqual),
+            '=' if self.compare('>') => self.add_token(Kind::EqualGreater),
             '=' => self.add_token(Kind::Equal),
             '<' if self.compare('=') => self.add_token(Kind::LessEqual),
+            '<' if self.compare('<') => self.add_token(Kind::LessLess),
             '<' => self.add_token(Kind::Less),
             '>' if self.compare('=') => self.add_token(Kind::GreaterEqual),
+            '>' if self.compare('>') => self.add_token(Kind::GreaterGreater),
             '>' => self.add_token(Kind::Greater),
             '&' if self.compare('&') => self.add_token(Kind::AmpAmp),
+            '&' => self.add_token(Kind::Amp),
             '|' if self.compare('|') => self.add_token(Kind::BarBar),
+            '|' => self.add_token(Kind::Bar),
+            '^' => self.add_token(Kind::Caret),
             '\n' => self.scan_newline(),
             ' ' | '\r' | '\t' => (),
             '"' => self.scan_string(),
             '0'..='9' => self.scan_number(),
             'a'..='z' | 'A'..='Z' | '_' => self.scan_identifier(),
-            _ => self.add_error("Unexpected character."),
+            _ => self.add_error(
+                Code::UnexpectedCharacter,
+                Code::UnexpectedCharacter.template(),
+            ),
         }
     }
 
@@ -91,27 +171,85 @@ impl Scanner {
         while !self.is_at_end() && self.peek() != '\n' {
             self.advance();
         }
+
+        let text: String =
+            self.source[self.start + 2..self.current].iter().collect();
+
+        if let Some(doc) = text.strip_prefix('/') {
+            if !doc.starts_with('/') {
+                let doc = doc.strip_prefix(' ').unwrap_or(doc);
+                self.docs.push(doc.trim_end().to_string());
+            }
+        }
+    }
+
+    fn scan_block_comment(&mut self) {
+        let line = self.line;
+        let opening = self.spanned();
+        let mut depth = 1;
+        let mut newline = false;
+
+        while depth > 0 && !self.is_at_end() {
+            match self.advance() {
+                '/' if self.compare('*') => depth += 1,
+                '*' if self.compare('/') => depth -= 1,
+                '\n' => {
+                    newline = true;
+                    self.line += 1;
+                    self.line_start = self.current;
+                }
+                _ => (),
+            }
+        }
+
+        if depth > 0 {
+            self.errors.push(SyntaxError {
+                line,
+                span: opening,
+                code: Code::UnterminatedComment,
+                location: String::new(),
+                message: Code::UnterminatedComment.template().to_string(),
+                note: None,
+            });
+        } else if newline
+            && self.semicolons == Semicolons::Automatic
+            && self.parens <= 0
+        {
+            self.add_semicolon(self.here());
+        }
     }
 
     fn scan_newline(&mut self) {
-        if self.parens <= 0 {
-            self.add_semicolon();
+        if self.semicolons == Semicolons::Automatic && self.parens <= 0 {
+            self.add_semicolon(self.spanned());
         }
 
-        self.line += 1
+        self.line += 1;
+        self.line_start = self.current;
     }
 
     fn scan_string(&mut self) {
+        let line = self.line;
+        let opening = self.spanned();
+
         while !self.is_at_end() && self.peek() != '"' {
             if self.peek() == '\n' {
                 self.line += 1;
+                self.line_start = self.current + 1;
             }
 
             self.advance();
         }
 
         if self.is_at_end() {
-            self.add_error("Unterminated string.");
+            self.errors.push(SyntaxError {
+                line,
+                span: opening,
+                code: Code::UnterminatedString,
+                location: String::new(),
+                message: Code::UnterminatedString.template().to_string(),
+                note: None,
+            });
         } else {
             self.advance();
             self.add_token(Kind::String);
@@ -119,47 +257,79 @@ impl Scanner {
     }
 
     fn scan_number(&mut self) {
-        while self.peek().is_ascii_digit() {
+        if self.source[self.start] == '0'
+            && matches!(self.peek(), 'x' | 'o' | 'b')
+            && self.peek_next().is_ascii_alphanumeric()
+        {
             self.advance();
+
+            while self.peek().is_ascii_alphanumeric() || self.peek() == '_' {
+                self.advance();
+            }
+
+            self.add_token(Kind::Number);
+            return;
         }
 
+        self.scan_digits();
+
         if self.peek() == '.' && self.peek_next().is_ascii_digit() {
             self.advance();
+            self.scan_digits();
+        }
+
+        if self.is_exponent() {
+            self.advance();
 
-            while self.peek().is_ascii_digit() {
+            if matches!(self.peek(), '+' | '-') {
                 self.advance();
             }
+
+            self.scan_digits();
+        }
+
+        if matches!(self.peek(), 'i' | 'f')
+            && !self.peek_next().is_ascii_alphanumeric()
+            && self.peek_next() != '_'
+        {
+            self.advance();
         }
 
         self.add_token(Kind::Number);
     }
 
+    fn scan_digits(&mut self) {
+        while self.peek().is_ascii_digit() || self.peek() == '_' {
+            self.advance();
+        }
+    }
+
+    fn is_exponent(&self) -> bool {
+        if !matches!(self.peek(), 'e' | 'E') {
+            return false;
+        }
+
+        match self.peek_next() {
+            '+' | '-' => self
+                .source
+                .get(self.current + 2)
+                .is_some_and(char::is_ascii_digit),
+            next => next.is_ascii_digit(),
+        }
+    }
+
     fn scan_identifier(&mut self) {
         while self.peek().is_ascii_alphanumeric() || self.peek() == '_' {
             self.advance();
         }
 
-        let text: String = self
-            .source
-            .chars()
-            .skip(self.start)
-            .take(self.current - self.start)
-            .collect();
-
-        let kind = match text.as_str() {
-            "if" => Kind::If,
-            "else" => Kind::Else,
-            "fn" => Kind::Fn,
-            "return" => Kind::Return,
-            "false" => Kind::False,
-            "true" => Kind::True,
-            "loop" => Kind::Loop,
-            "break" => Kind::Break,
-            "continue" => Kind::Continue,
-            "let" => Kind::Let,
-            "type" => Kind::Type,
-            _ => Kind::Identifier,
-        };
+        let text: String =
+            self.source[self.start..self.current].iter().collect();
+
+        let kind = KEYWORDS
+            .iter()
+            .find(|(keyword, _)| *keyword == text)
+            .map_or(Kind::Identifier, |(_, kind)| *kind);
 
         self.add_token(kind);
     }
@@ -168,52 +338,81 @@ impl Scanner {
         if self.is_at_end() || self.peek() != expected {
             false
         } else {
-            self.current += 1;
+            self.advance();
             true
         }
     }
 
     fn peek(&self) -> char {
-        self.source.chars().nth(self.current).unwrap_or('\0')
+        self.source.get(self.current).copied().unwrap_or('\0')
     }
 
     fn peek_next(&self) -> char {
-        self.source.chars().nth(self.current + 1).unwrap_or('\0')
+        self.source.get(self.current + 1).copied().unwrap_or('\0')
     }
 
     fn advance(&mut self) -> char {
+        let character = self.peek();
+
+        if !self.is_at_end() {
+            self.offset += character.len_utf8();
+        }
+
         self.current += 1;
-        self.source.chars().nth(self.current - 1).unwrap_or('\0')
+        character
+    }
+
+    fn here(&self) -> Span {
+        Span {
+            start: self.offset,
+            end: self.offset,
+            column: self.current - self.line_start + 1,
+        }
+    }
+
+    fn spanned(&self) -> Span {
+        Span {
+            end: self.offset,
+            ..self.span
+        }
     }
 
     fn is_at_end(&self) -> bool {
-        self.current >= self.source.chars().count()
+        self.current >= self.source.len()
     }
 
     fn add_token(&mut self, kind: Kind) {
-        let text = self
-            .source
-            .chars()
-            .skip(self.start)
-            .take(self.current - self.start)
-            .collect();
+        let text: String =
+            self.source[self.start..self.current].iter().collect();
+        let text = text.replace("\r\n", "\n");
+
+        let doc = if self.docs.is_empty() {
+            None
+        } else {
+            Some(mem::take(&mut self.docs).join("\n"))
+        };
 
         self.tokens.push(Token {
             kind,
             lexeme: text,
             line: self.line,
+            span: self.spanned(),
+            doc,
         });
     }
 
-    fn add_error(&mut self, message: &str) {
+    fn add_error(&mut self, code: Code, message: &str) {
         self.errors.push(SyntaxError {
             line: self.line,
+            span: self.spanned(),
+            code,
             location: String::new(),
             message: message.to_string(),
+            note: None,
         });
     }
 
-    fn add_semicolon(&mut self) {
+    fn add_semicolon(&mut self, span: Span) {
         if let Some(token) = self.tokens.last() {
             if !matches!(
                 token.kind,
@@ -223,6 +422,8 @@ impl Scanner {
                     kind: Kind::Semicolon,
                     lexeme: ";".to_string(),
                     line: self.line,
+                    span,
+                    doc: None,
                 });
             }
         }
@@ -1,53 +1,151 @@
 use crate::expr::Expr;
+use crate::pattern::Pattern;
 use crate::token::Token;
 use crate::variant::Variant;
 
+#[derive(Clone, Debug)]
+pub struct Deprecated {
+    pub note: Option<String>,
+}
+
 #[derive(Clone, Debug)]
 pub struct If {
+    pub keyword: Token,
     pub condition: Expr,
     pub then_branch: Stmt,
     pub else_branch: Option<Stmt>,
 }
 
+#[derive(Clone, Debug)]
+pub struct Match {
+    pub keyword: Token,
+    pub value: Expr,
+    pub arms: Vec<(Pattern, Option<Expr>, Stmt)>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Function {
     pub name: Token,
+    pub receiver: bool,
     pub parameters: Vec<(Token, Variant)>,
     pub output: Option<Variant>,
     pub body: Stmt,
+    pub doc: Option<String>,
+    pub deprecated: Option<Deprecated>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Test {
+    pub keyword: Token,
+    pub name: Token,
+    pub body: Stmt,
+}
+
+#[derive(Clone, Debug)]
+pub struct Bench {
+    pub keyword: Token,
+    pub name: Token,
+    pub body: Stmt,
 }
 
 #[derive(Clone, Debug)]
 pub struct Return {
+    pub keyword: Token,
     pub value: Option<Expr>,
 }
 
+#[derive(Clone, Debug)]
+pub struct Raise {
+    pub keyword: Token,
+    pub value: Expr,
+}
+
+#[derive(Clone, Debug)]
+pub struct Try {
+    pub keyword: Token,
+    pub body: Stmt,
+    pub error: Option<Token>,
+    pub handler: Stmt,
+}
+
 #[derive(Clone, Debug)]
 pub struct Loop {
+    pub keyword: Token,
+    pub label: Option<Token>,
     pub body: Stmt,
 }
 
 #[derive(Clone, Debug)]
-pub struct Break {}
+pub struct For {
+    pub label: Option<Token>,
+    pub variable: Token,
+    pub start: Expr,
+    pub end: Expr,
+    pub body: Stmt,
+}
+
+#[derive(Clone, Debug)]
+pub struct Break {
+    pub keyword: Token,
+    pub label: Option<Token>,
+    pub value: Option<Expr>,
+}
 
 #[derive(Clone, Debug)]
-pub struct Continue {}
+pub struct Continue {
+    pub keyword: Token,
+    pub label: Option<Token>,
+}
 
 #[derive(Clone, Debug)]
 pub struct Let {
     pub name: Token,
-    pub variant: Variant,
+    pub variant: Option<Variant>,
     pub initializer: Option<Expr>,
 }
 
+#[derive(Clone, Debug)]
+pub struct Const {
+    pub name: Token,
+    pub variant: Variant,
+    pub initializer: Expr,
+    pub doc: Option<String>,
+    pub deprecated: Option<Deprecated>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Type {
     pub name: Token,
     pub variant: Variant,
+    pub doc: Option<String>,
+    pub deprecated: Option<Deprecated>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Struct {
+    pub name: Token,
+    pub fields: Vec<(Token, Variant)>,
+    pub doc: Option<String>,
+    pub deprecated: Option<Deprecated>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Enum {
+    pub name: Token,
+    pub variants: Vec<(Token, Vec<Variant>)>,
+    pub doc: Option<String>,
+    pub deprecated: Option<Deprecated>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Impl {
+    pub name: Token,
+    pub methods: Vec<Stmt>,
 }
 
 #[derive(Clone, Debug)]
 pub struct Block {
+    pub line: usize,
     pub statements: Vec<Stmt>,
 }
 
@@ -65,13 +163,23 @@ pub struct Expression {
 #[derive(Clone, Debug)]
 pub enum Stmt {
     If(Box<If>),
+    Match(Box<Match>),
     Function(Box<Function>),
+    Test(Box<Test>),
+    Bench(Box<Bench>),
     Return(Box<Return>),
+    Raise(Box<Raise>),
+    Try(Box<Try>),
     Loop(Box<Loop>),
+    For(Box<For>),
     Break(Box<Break>),
     Continue(Box<Continue>),
     Let(Box<Let>),
+    Const(Box<Const>),
     Type(Box<Type>),
+    Struct(Box<Struct>),
+    Enum(Box<Enum>),
+    Impl(Box<Impl>),
     Block(Box<Block>),
     Assignment(Box<Assignment>),
     Expression(Box<Expression>),
@@ -79,50 +187,132 @@ pub enum Stmt {
 
 impl Stmt {
     pub fn new_if(
+        keyword: Token,
         condition: Expr,
         then_branch: Stmt,
         else_branch: Option<Stmt>,
     ) -> Self {
         Self::If(Box::new(If {
+            keyword,
             condition,
             then_branch,
             else_branch,
         }))
     }
 
+    pub fn new_match(
+        keyword: Token,
+        value: Expr,
+        arms: Vec<(Pattern, Option<Expr>, Stmt)>,
+    ) -> Self {
+        Self::Match(Box::new(Match {
+            keyword,
+            value,
+            arms,
+        }))
+    }
+
     pub fn new_function(
         name: Token,
+        receiver: bool,
         parameters: Vec<(Token, Variant)>,
         output: Option<Variant>,
         body: Stmt,
+        doc: Option<String>,
+        deprecated: Option<Deprecated>,
     ) -> Self {
         Self::Function(Box::new(Function {
             name,
+            receiver,
             parameters,
             output,
             body,
+            doc,
+            deprecated,
+        }))
+    }
+
+    pub fn new_test(keyword: Token, name: Token, body: Stmt) -> Self {
+        Self::Test(Box::new(Test {
+            keyword,
+            name,
+            body,
+        }))
+    }
+
+    pub fn new_bench(keyword: Token, name: Token, body: Stmt) -> Self {
+        Self::Bench(Box::new(Bench {
+            keyword,
+            name,
+            body,
         }))
     }
 
-    pub fn new_return(value: Option<Expr>) -> Self {
-        Self::Return(Box::new(Return { value }))
+    pub fn new_return(keyword: Token, value: Option<Expr>) -> Self {
+        Self::Return(Box::new(Return { keyword, value }))
+    }
+
+    pub fn new_raise(keyword: Token, value: Expr) -> Self {
+        Self::Raise(Box::new(Raise { keyword, value }))
+    }
+
+    pub fn new_try(
+        keyword: Token,
+        body: Stmt,
+        error: Option<Token>,
+        handler: Stmt,
+    ) -> Self {
+        Self::Try(Box::new(Try {
+            keyword,
+            body,
+            error,
+            handler,
+        }))
+    }
+
+    pub fn new_loop(keyword: Token, label: Option<Token>, body: Stmt) -> Self {
+        Self::Loop(Box::new(Loop {
+            keyword,
+            label,
+            body,
+        }))
     }
 
-    pub fn new_loop(body: Stmt) -> Self {
-        Self::Loop(Box::new(Loop { body }))
+    pub fn new_for(
+        label: Option<Token>,
+        variable: Token,
+        start: Expr,
+        end: Expr,
+        body: Stmt,
+    ) -> Self {
+        Self::For(Box::new(For {
+            label,
+            variable,
+            start,
+            end,
+            body,
+        }))
     }
 
-    pub fn new_break() -> Self {
-        Self::Break(Box::new(Break {}))
+    pub fn new_break(
+        keyword: Token,
+        label: Option<Token>,
+        value: Option<Expr>,
+    ) -> Self {
+        Self::Break(Box::new(Break {
+            keyword,
+            label,
+            value,
+        }))
     }
 
-    pub fn new_continue() -> Self {
-        Self::Continue(Box::new(Continue {}))
+    pub fn new_continue(keyword: Token, label: Option<Token>) -> Self {
+        Self::Continue(Box::new(Continue { keyword, label }))
     }
 
     pub fn new_let(
         name: Token,
-        variant: Variant,
+        variant: Option<Variant>,
         initializer: Option<Expr>,
     ) -> Self {
         Self::Let(Box::new(Let {
@@ -132,12 +322,70 @@ impl Stmt {
         }))
     }
 
-    pub fn new_type(name: Token, variant: Variant) -> Self {
-        Self::Type(Box::new(Type { name, variant }))
+    pub fn new_const(
+        name: Token,
+        variant: Variant,
+        initializer: Expr,
+        doc: Option<String>,
+        deprecated: Option<Deprecated>,
+    ) -> Self {
+        Self::Const(Box::new(Const {
+            name,
+            variant,
+            initializer,
+            doc,
+            deprecated,
+        }))
+    }
+
+    pub fn new_type(
+        name: Token,
+        variant: Variant,
+        doc: Option<String>,
+        deprecated: Option<Deprecated>,
+    ) -> Self {
+        Self::Type(Box::new(Type {
+            name,
+            variant,
+            doc,
+            deprecated,
+        }))
+    }
+
+    pub fn new_struct(
+        name: Token,
+        fields: Vec<(Token, Variant)>,
+        doc: Option<String>,
+        deprecated: Option<Deprecated>,
+    ) -> Self {
+        Self::Struct(Box::new(Struct {
+            name,
+            fields,
+            doc,
+            deprecated,
+        }))
+    }
+
+    pub fn new_enum(
+        name: Token,
+        variants: Vec<(Token, Vec<Variant>)>,
+        doc: Option<String>,
+        deprecated: Option<Deprecated>,
+    ) -> Self {
+        Self::Enum(Box::new(Enum {
+            name,
+            variants,
+            doc,
+            deprecated,
+        }))
+    }
+
+    pub fn new_impl(name: Token, methods: Vec<Stmt>) -> Self {
+        Self::Impl(Box::new(Impl { name, methods }))
     }
 
-    pub fn new_block(statements: Vec<Stmt>) -> Self {
-        Self::Block(Box::new(Block { statements }))
+    pub fn new_block(line: usize, statements: Vec<Stmt>) -> Self {
+        Self::Block(Box::new(Block { line, statements }))
     }
 
     pub fn new_assignment(name: Token, value: Expr) -> Self {
@@ -148,16 +396,78 @@ impl Stmt {
         Self::Expression(Box::new(Expression { expression }))
     }
 
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::If(stmt) => Some(stmt.keyword.line),
+            Self::Match(stmt) => Some(stmt.keyword.line),
+            Self::Function(stmt) => Some(stmt.name.line),
+            Self::Test(stmt) => Some(stmt.keyword.line),
+            Self::Bench(stmt) => Some(stmt.keyword.line),
+            Self::Return(stmt) => Some(stmt.keyword.line),
+            Self::Raise(stmt) => Some(stmt.keyword.line),
+            Self::Try(stmt) => Some(stmt.keyword.line),
+            Self::Loop(stmt) => Some(stmt.keyword.line),
+            Self::For(stmt) => Some(stmt.variable.line),
+            Self::Break(stmt) => Some(stmt.keyword.line),
+            Self::Continue(stmt) => Some(stmt.keyword.line),
+            Self::Let(stmt) => Some(stmt.name.line),
+            Self::Const(stmt) => Some(stmt.name.line),
+            Self::Type(stmt) => Some(stmt.name.line),
+            Self::Struct(stmt) => Some(stmt.name.line),
+            Self::Enum(stmt) => Some(stmt.name.line),
+            Self::Impl(stmt) => Some(stmt.name.line),
+            Self::Block(stmt) => Some(stmt.line),
+            Self::Assignment(stmt) => Some(stmt.name.line),
+            Self::Expression(stmt) => stmt.expression.line(),
+        }
+    }
+
+    pub fn token(&self) -> Option<&Token> {
+        match self {
+            Self::If(stmt) => Some(&stmt.keyword),
+            Self::Match(stmt) => Some(&stmt.keyword),
+            Self::Function(stmt) => Some(&stmt.name),
+            Self::Test(stmt) => Some(&stmt.keyword),
+            Self::Bench(stmt) => Some(&stmt.keyword),
+            Self::Return(stmt) => Some(&stmt.keyword),
+            Self::Raise(stmt) => Some(&stmt.keyword),
+            Self::Try(stmt) => Some(&stmt.keyword),
+            Self::Loop(stmt) => stmt.label.as_ref().or(Some(&stmt.keyword)),
+            Self::For(stmt) => stmt.label.as_ref().or(Some(&stmt.variable)),
+            Self::Break(stmt) => Some(&stmt.keyword),
+            Self::Continue(stmt) => Some(&stmt.keyword),
+            Self::Let(stmt) => Some(&stmt.name),
+            Self::Const(stmt) => Some(&stmt.name),
+            Self::Type(stmt) => Some(&stmt.name),
+            Self::Struct(stmt) => Some(&stmt.name),
+            Self::Enum(stmt) => Some(&stmt.name),
+            Self::Impl(stmt) => Some(&stmt.name),
+            Self::Block(stmt) => stmt.statements.iter().find_map(Self::token),
+            Self::Assignment(stmt) => Some(&stmt.name),
+            Self::Expression(stmt) => stmt.expression.token(),
+        }
+    }
+
     pub fn accept<V: Visitor>(&self, visitor: &mut V) -> V::Result {
         match self {
             Self::If(stmt) => visitor.visit_if_stmt(stmt),
+            Self::Match(stmt) => visitor.visit_match_stmt(stmt),
             Self::Function(stmt) => visitor.visit_function_stmt(stmt),
+            Self::Test(stmt) => visitor.visit_test_stmt(stmt),
+            Self::Bench(stmt) => visitor.visit_bench_stmt(stmt),
             Self::Return(stmt) => visitor.visit_return_stmt(stmt),
+            Self::Raise(stmt) => visitor.visit_raise_stmt(stmt),
+            Self::Try(stmt) => visitor.visit_try_stmt(stmt),
             Self::Loop(stmt) => visitor.visit_loop_stmt(stmt),
+            Self::For(stmt) => visitor.visit_for_stmt(stmt),
             Self::Break(stmt) => visitor.visit_break_stmt(stmt),
             Self::Continue(stmt) => visitor.visit_continue_stmt(stmt),
             Self::Let(stmt) => visitor.visit_let_stmt(stmt),
+            Self::Const(stmt) => visitor.visit_const_stmt(stmt),
             Self::Type(stmt) => visitor.visit_type_stmt(stmt),
+            Self::Struct(stmt) => visitor.visit_struct_stmt(stmt),
+            Self::Enum(stmt) => visitor.visit_enum_stmt(stmt),
+            Self::Impl(stmt) => visitor.visit_impl_stmt(stmt),
             Self::Block(stmt) => visitor.visit_block_stmt(stmt),
             Self::Assignment(stmt) => visitor.visit_assignment_stmt(stmt),
             Self::Expression(stmt) => visitor.visit_expression_stmt(stmt),
@@ -169,13 +479,23 @@ pub trait Visitor {
     type Result;
 
     fn visit_if_stmt(&mut self, stmt: &If) -> Self::Result;
+    fn visit_match_stmt(&mut self, stmt: &Match) -> Self::Result;
     fn visit_function_stmt(&mut self, stmt: &Function) -> Self::Result;
+    fn visit_test_stmt(&mut self, stmt: &Test) -> Self::Result;
+    fn visit_bench_stmt(&mut self, stmt: &Bench) -> Self::Result;
     fn visit_return_stmt(&mut self, stmt: &Return) -> Self::Result;
+    fn visit_raise_stmt(&mut self, stmt: &Raise) -> Self::Result;
+    fn visit_try_stmt(&mut self, stmt: &Try) -> Self::Result;
     fn visit_loop_stmt(&mut self, stmt: &Loop) -> Self::Result;
+    fn visit_for_stmt(&mut self, stmt: &For) -> Self::Result;
     fn visit_break_stmt(&mut self, stmt: &Break) -> Self::Result;
     fn visit_continue_stmt(&mut self, stmt: &Continue) -> Self::Result;
     fn visit_let_stmt(&mut self, stmt: &Let) -> Self::Result;
+    fn visit_const_stmt(&mut self, stmt: &Const) -> Self::Result;
     fn visit_type_stmt(&mut self, stmt: &Type) -> Self::Result;
+    fn visit_struct_stmt(&mut self, stmt: &Struct) -> Self::Result;
+    fn visit_enum_stmt(&mut self, stmt: &Enum) -> Self::Result;
+    fn visit_impl_stmt(&mut self, stmt: &Impl) -> Self::Result;
     fn visit_block_stmt(&mut self, stmt: &Block) -> Self::Result;
     fn visit_assignment_stmt(&mut self, stmt: &Assignment) -> Self::Result;
     fn visit_expression_stmt(&mut self, stmt: &Expression) -> Self::Result;
@@ -0,0 +1,99 @@
+use std::mem;
+
+// Optimal string alignment distance, so swapping two neighbouring letters
+// counts as a single edit like in the Damerau-Levenshtein distance.
+fn distance(left: &str, right: &str) -> usize {
+    let left: Vec<char> = left.chars().collect();
+    let right: Vec<char> = right.chars().collect();
+    let mut before: Vec<usize> = Vec::new();
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+
+    for (i, a) in left.iter().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, b) in right.iter().enumerate() {
+            let cost = if a == b { 0 } else { 1 };
+            let mut value = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+
+            if i > 0 && j > 0 && *a == right[j - 1] && left[i - 1] == *b {
+                value = value.min(before[j - 1] + 1);
+            }
+
+            current.push(value);
+        }
+
+        before = mem::replace(&mut previous, current);
+    }
+
+    previous[right.len()]
+}
+
+pub fn suggest<'a>(
+    name: &str,
+    candidates: impl Iterator<Item = &'a str>,
+) -> Option<String> {
+    let length = name.chars().count();
+    let limit = length.max(3) / 3;
+
+    candidates
+        .filter(|candidate| *candidate != name)
+        .filter(|candidate| {
+            length >= 4
+                || candidate.starts_with(name)
+                || name.starts_with(candidate)
+        })
+        .map(|candidate| (distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min()
+        .map(|(_, candidate)| format!("Did you mean '{}'?", candidate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::suggest;
+
+    fn note(name: &str, candidates: &[&str]) -> Option<String> {
+        suggest(name, candidates.iter().copied())
+    }
+
+    #[test]
+    fn suggests_close_names() {
+        assert_eq!(
+            note("prnt", &["print", "input"]),
+            Some("Did you mean 'print'?".to_string())
+        );
+        assert_eq!(
+            note("lenght", &["length", "len"]),
+            Some("Did you mean 'length'?".to_string())
+        );
+    }
+
+    #[test]
+    fn swapped_letters_are_one_edit() {
+        assert_eq!(
+            note("pritn", &["print", "input"]),
+            Some("Did you mean 'print'?".to_string())
+        );
+        assert_eq!(
+            note("cuont", &["count", "const"]),
+            Some("Did you mean 'count'?".to_string())
+        );
+    }
+
+    #[test]
+    fn skips_distant_names() {
+        assert_eq!(note("count", &["total", "index"]), None);
+    }
+
+    #[test]
+    fn short_names_need_a_prefix_match() {
+        assert_eq!(note("z", &["f", "a"]), None);
+        assert_eq!(note("b", &["a"]), None);
+        assert_eq!(
+            note("fnn", &["fn", "if"]),
+            Some("Did you mean 'fn'?".to_string())
+        );
+    }
+}
@@ -2,11 +2,20 @@ use std::fmt;
 
 use crate::kind::Kind;
 
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub column: usize,
+}
+
 #[derive(Clone, Debug)]
 pub struct Token {
     pub kind: Kind,
     pub lexeme: String,
     pub line: usize,
+    pub span: Span,
+    pub doc: Option<String>,
 }
 
 impl fmt::Display for Token {
@@ -1,7 +1,14 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Suffix {
+    Integer,
+    Float,
+}
+
 #[derive(Clone, Debug)]
 pub enum Value {
     False,
     True,
-    Number(String),
+    None,
+    Number(String, Option<Suffix>),
     String(String),
 }
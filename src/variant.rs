@@ -11,10 +11,42 @@ pub struct Function {
     pub output: Option<Variant>,
 }
 
+#[derive(Clone, Debug)]
+pub struct Array {
+    pub element: Variant,
+}
+
+#[derive(Clone, Debug)]
+pub struct Map {
+    pub key: Variant,
+    pub value: Variant,
+}
+
+#[derive(Clone, Debug)]
+pub struct Set {
+    pub element: Variant,
+}
+
+#[derive(Clone, Debug)]
+pub struct Optional {
+    pub inner: Variant,
+}
+
+#[derive(Clone, Debug)]
+pub struct Fallible {
+    pub value: Variant,
+    pub error: Variant,
+}
+
 #[derive(Clone, Debug)]
 pub enum Variant {
     Literal(Box<Literal>),
     Function(Box<Function>),
+    Array(Box<Array>),
+    Map(Box<Map>),
+    Set(Box<Set>),
+    Optional(Box<Optional>),
+    Fallible(Box<Fallible>),
 }
 
 impl Variant {
@@ -29,10 +61,35 @@ impl Variant {
         Self::Function(Box::new(Function { parameters, output }))
     }
 
+    pub fn new_array(element: Variant) -> Self {
+        Self::Array(Box::new(Array { element }))
+    }
+
+    pub fn new_map(key: Variant, value: Variant) -> Self {
+        Self::Map(Box::new(Map { key, value }))
+    }
+
+    pub fn new_set(element: Variant) -> Self {
+        Self::Set(Box::new(Set { element }))
+    }
+
+    pub fn new_optional(inner: Variant) -> Self {
+        Self::Optional(Box::new(Optional { inner }))
+    }
+
+    pub fn new_fallible(value: Variant, error: Variant) -> Self {
+        Self::Fallible(Box::new(Fallible { value, error }))
+    }
+
     pub fn accept<V: Visitor>(&self, visitor: &mut V) -> V::Result {
         match self {
             Self::Literal(variant) => visitor.visit_literal_variant(variant),
             Self::Function(variant) => visitor.visit_function_variant(variant),
+            Self::Array(variant) => visitor.visit_array_variant(variant),
+            Self::Map(variant) => visitor.visit_map_variant(variant),
+            Self::Set(variant) => visitor.visit_set_variant(variant),
+            Self::Optional(variant) => visitor.visit_optional_variant(variant),
+            Self::Fallible(variant) => visitor.visit_fallible_variant(variant),
         }
     }
 }
@@ -42,4 +99,9 @@ pub trait Visitor {
 
     fn visit_literal_variant(&mut self, variant: &Literal) -> Self::Result;
     fn visit_function_variant(&mut self, variant: &Function) -> Self::Result;
+    fn visit_array_variant(&mut self, variant: &Array) -> Self::Result;
+    fn visit_map_variant(&mut self, variant: &Map) -> Self::Result;
+    fn visit_set_variant(&mut self, variant: &Set) -> Self::Result;
+    fn visit_optional_variant(&mut self, variant: &Optional) -> Self::Result;
+    fn visit_fallible_variant(&mut self, variant: &Fallible) -> Self::Result;
 }
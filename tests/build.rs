@@ -0,0 +1,70 @@
+use std::env;
+use std::fs;
+use std::process::{self, Command};
+
+use blaze::build::{self, BuildError};
+
+#[test]
+fn compile_dir_writes_a_module_per_script() {
+    let root = env::temp_dir().join(format!("blaze-build-{}", process::id()));
+    let scripts = root.join("scripts");
+    let output = root.join("out");
+    fs::create_dir_all(&scripts).unwrap();
+    fs::create_dir_all(&output).unwrap();
+    env::set_var("OUT_DIR", &output);
+
+    fs::write(
+        scripts.join("hello.blz"),
+        "fn main() {\n    print(\"hello\")\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        scripts.join("two-words.blz"),
+        "fn main() {\n    print(1 + 1)\n}\n",
+    )
+    .unwrap();
+    fs::write(scripts.join("notes.txt"), "not a script").unwrap();
+    build::compile_dir(&scripts).unwrap();
+
+    let main = root.join("main.rs");
+    let binary = root.join(format!("main{}", env::consts::EXE_SUFFIX));
+    fs::write(
+        &main,
+        format!(
+            "include!({:?});\n\nfn main() {{\n    hello::run();\n    \
+             two_words::run();\n}}\n",
+            output.join("blaze.rs").to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let rustc = env::var("BLAZE_RUSTC").unwrap_or_else(|_| "rustc".into());
+    let build = Command::new(rustc)
+        .arg(&main)
+        .arg("-o")
+        .arg(&binary)
+        .output()
+        .unwrap();
+    assert!(
+        build.status.success(),
+        "rustc failed:\n{}",
+        String::from_utf8_lossy(&build.stderr)
+    );
+
+    let run = Command::new(&binary).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "hello\n2\n");
+
+    fs::write(scripts.join("broken.blz"), "fn main() {\n    print(x)\n}\n")
+        .unwrap();
+    let error = build::compile_dir(&scripts).unwrap_err();
+
+    fs::remove_dir_all(&root).unwrap();
+
+    match error {
+        BuildError::Compile(path, diagnostics) => {
+            assert_eq!(path, scripts.join("broken.blz"));
+            assert_eq!(diagnostics.len(), 1);
+        }
+        error => panic!("unexpected error: {}", error),
+    }
+}
@@ -0,0 +1,1373 @@
+use blaze::{Diagnostic, Level, Options, Semicolons};
+
+fn check(source: &str) -> Vec<Diagnostic> {
+    let mut options = Options::new();
+    options.fail_fast = false;
+    options.lints.push(("unused".to_string(), Level::Allow));
+
+    blaze::check_with(source, &options)
+}
+
+fn errors(source: &str) -> Vec<String> {
+    check(source).iter().map(|d| d.to_string()).collect()
+}
+
+#[test]
+fn print_rejects_struct() {
+    let source = r#"
+struct Point {
+    x: i32
+}
+
+fn main() {
+    print(Point { x: 1 })
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec!["[line 7, column 5] Error[B0007]: Can't print 'Point'."]
+    );
+}
+
+#[test]
+fn to_string_rejects_array() {
+    let source = r#"
+fn main() {
+    let text = to_string([1, 2])
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 3, column 16] Error[B0007]: Can't convert '[i32]' to 'str'."
+        ]
+    );
+}
+
+#[test]
+fn resolve_errors_have_columns() {
+    let source = r#"
+fn main() {
+    print(1 + missing)
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec!["[line 3, column 15] Error[B0004]: Undefined variable 'missing'."]
+    );
+}
+
+#[test]
+fn flow_errors_have_columns() {
+    let source = r#"
+fn sign(n: i32): i32 {
+    if n < 0 {
+        return -1
+    }
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 2, column 4] Error[B0011]: Function 'sign' doesn't return \
+             a value on every path."
+        ]
+    );
+}
+
+#[test]
+fn raise_outside_fallible_function() {
+    let source = r#"
+fn check(n: i32): i32 {
+    if n < 0 {
+        raise "negative"
+    }
+
+    return n
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 4, column 9] Error[B0012]: Can't raise outside of a \
+             fallible function."
+        ]
+    );
+}
+
+#[test]
+fn cast_to_unsupported_type() {
+    let source = r#"
+fn main() {
+    let x = 1 as str
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec!["[line 3, column 18] Error[B0012]: Can only cast to 'i32' or 'f64'."]
+    );
+}
+
+#[test]
+fn assert_argument_count() {
+    let source = r#"
+fn main() {
+    assert(true, "a", "b")
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec!["[line 3, column 5] Error[B0009]: Expect 1 or 2 arguments to 'assert'."]
+    );
+}
+
+#[test]
+fn global_without_type() {
+    let source = r#"
+let count = 0
+
+fn main() {}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec!["[line 2, column 5] Error[B0012]: Global variable needs a type."]
+    );
+}
+
+#[test]
+fn spans_are_offsets_into_crlf_source() {
+    let source =
+        "\u{feff}fn main() {\r\n    let a = 1\r\n    print(a + zz)\r\n}\r\n";
+    let diagnostics = check(source);
+    let span = diagnostics[0].span.unwrap();
+
+    assert_eq!(&source[span.start..span.end], "zz");
+    assert_eq!((diagnostics[0].line, span.column), (3, 15));
+}
+
+fn notes(source: &str) -> Vec<Option<String>> {
+    check(source).into_iter().map(|d| d.note).collect()
+}
+
+#[test]
+fn short_names_get_no_suggestion() {
+    let source = r#"
+fn f() {}
+
+fn main() {
+    print(z)
+}
+"#;
+
+    assert_eq!(notes(source), vec![None]);
+}
+
+#[test]
+fn suggestions_match_the_kind_looked_up() {
+    let source = r#"
+fn main() {
+    let total = 1
+    let x = prnt
+    totl(total)
+}
+"#;
+
+    assert_eq!(notes(source), vec![None, None]);
+}
+
+#[test]
+fn deprecated_call_warns() {
+    let source = r#"
+@deprecated("use total")
+fn sum(a: i32, b: i32): i32 {
+    return a + b
+}
+
+@deprecated
+fn old() {}
+
+fn main() {
+    print(sum(1, 2))
+    old()
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 11, column 11] Warning[B0015]: Function 'sum' is \
+             deprecated: use total",
+            "[line 12, column 5] Warning[B0015]: Function 'old' is deprecated.",
+        ]
+    );
+}
+
+#[test]
+fn deprecated_types_warn() {
+    let source = r#"
+@deprecated("use Vector")
+struct Point { x: i32 }
+
+@deprecated
+enum Shape { Dot }
+
+@deprecated("use i32")
+type Count = i32
+
+fn main() {
+    let p = Point { x: 1 }
+    let s = Shape::Dot
+    let c: Count = 2
+    print(p.x + c)
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 12, column 13] Warning[B0015]: Struct 'Point' is \
+             deprecated: use Vector",
+            "[line 13, column 13] Warning[B0015]: Enum 'Shape' is deprecated.",
+            "[line 14, column 12] Warning[B0015]: Type 'Count' is \
+             deprecated: use i32",
+        ]
+    );
+}
+
+#[test]
+fn shadowed_deprecated_name_does_not_warn() {
+    let source = r#"
+@deprecated
+fn old() {}
+
+fn main() {
+    fn old() {}
+
+    old()
+}
+"#;
+
+    assert!(errors(source).is_empty());
+}
+
+#[test]
+fn jumps_end_at_closing_brace() {
+    let source = r#"
+fn first(a: [i32]): i32 { return a[0] }
+
+fn main() {
+    let found = loop { break 5 }
+    let i = 0
+    loop { i = i + 1; if i < 3 { continue } break }
+    print(found + first([i]))
+}"#;
+
+    assert!(errors(source).is_empty());
+}
+
+#[test]
+fn empty_collection_needs_a_type() {
+    let source = r#"
+fn main() {
+    let e = []
+    let m = {}
+    let ok: [i32] = []
+    print(len(e) + len(m) + len(ok))
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 3, column 9] Error[B0017]: Can't infer the type of 'e'.",
+            "[line 4, column 9] Error[B0017]: Can't infer the type of 'm'.",
+        ]
+    );
+}
+
+#[test]
+fn literal_out_of_range() {
+    let source = r#"
+fn main() {
+    print(-2147483648)
+    print(2147483648)
+    print(1e400)
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 4, column 11] Error[B0016] at '2147483648': Literal is out \
+             of range for 'i32'.",
+            "[line 5, column 11] Error[B0016] at '1e400': Literal is out of \
+             range for 'f64'.",
+        ]
+    );
+}
+
+#[test]
+fn constant_overflow() {
+    let source = r#"
+fn main() {
+    print(2147483647 + 1)
+    print((2147483646 + 1) * 2)
+    print(1 / 0)
+    print(1 << 40)
+    print(-(-2147483648))
+    print(2147483647 - 1 + 1)
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 3, column 22] Error[B0016]: '+' overflows 'i32' here.",
+            "[line 4, column 28] Error[B0016]: '*' overflows 'i32' here.",
+            "[line 5, column 13] Error[B0016]: Can't divide by zero.",
+            "[line 6, column 13] Error[B0016]: '<<' overflows 'i32' here.",
+            "[line 7, column 11] Error[B0016]: '-' overflows 'i32' here.",
+        ]
+    );
+}
+
+#[test]
+fn radix_literal_out_of_range() {
+    let source = r#"
+fn main() {
+    print(0x7fff_ffff + -0x8000_0000 + 0b1 + 0o17)
+    print(0x8000_0000)
+    print(0b1_0000_0000_0000_0000_0000_0000_0000_0000)
+    print(0o1_0000_0000_0000_0000_0000_0000)
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 4, column 11] Error[B0016] at '0x8000_0000': Literal is \
+             out of range for 'i32'.",
+            "[line 5, column 11] Error[B0016] at \
+             '0b1_0000_0000_0000_0000_0000_0000_0000_0000': Literal is out \
+             of range for 'i32'.",
+            "[line 6, column 11] Error[B0016] at \
+             '0o1_0000_0000_0000_0000_0000_0000': Literal is out of range \
+             for 'i32'.",
+        ]
+    );
+}
+
+#[test]
+fn unreachable_points_at_next_statement() {
+    let source = r#"
+fn main() {
+    return
+
+    print("never")
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 5, column 5] Warning[B0013]: Unreachable code after \
+             'return'."
+        ]
+    );
+}
+
+#[test]
+fn jump_outside_loop() {
+    let source = r#"
+fn main() {
+    loop {
+        fn inner() {
+            continue
+        }
+
+        break
+    }
+
+    break
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 5, column 13] Error[B0018]: Can't use 'continue' outside \
+             of a loop.",
+            "[line 11, column 5] Error[B0018]: Can't use 'break' outside of a \
+             loop.",
+        ]
+    );
+}
+
+#[test]
+fn match_must_cover_every_value() {
+    let source = r#"
+fn main() {
+    match 2 {
+        0 => print("zero")
+        1 => print("one")
+    }
+
+    match true {
+        true => print("yes")
+    }
+
+    match false {
+        true => print("yes")
+        false => print("no")
+    }
+
+    match "a" {
+        "a" => print("a")
+        _ => print("other")
+    }
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 3, column 5] Error[B0019]: Match doesn't cover every value \
+             of 'i32'.",
+            "[line 8, column 5] Error[B0019]: Match doesn't cover every value \
+             of 'bool'.",
+        ]
+    );
+}
+
+#[test]
+fn enum_match_must_cover_every_variant() {
+    let source = r#"
+enum Shape {
+    Circle(f64),
+    Square(f64),
+    Empty,
+}
+
+enum Text {
+    Word(str),
+}
+
+fn main() {
+    let shape = Shape::Empty
+
+    match shape {
+        Shape::Circle(r) => print(r)
+        Shape::Square(s) => print(s)
+    }
+
+    match shape {
+        Shape::Circle(1.0) => print("unit")
+        Shape::Circle(_) => print("circle")
+        Shape::Square(_) => print("square")
+        Shape::Empty => print("empty")
+    }
+
+    match Text::Word("a") {
+        Text::Word("a") => print("a")
+        _ => print("other")
+    }
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 15, column 5] Error[B0019]: Match doesn't cover every \
+             value of 'Shape'.",
+            "[line 28, column 15] Error[B0012]: Can't match a string inside a \
+             variant.",
+        ]
+    );
+}
+
+#[test]
+fn non_exhaustive_match_lists_missing_cases() {
+    let source = r#"
+enum Shape {
+    Circle(f64),
+    Square(f64),
+    Empty,
+}
+
+fn main() {
+    match Shape::Empty {
+        Shape::Circle(1.0) => print("unit")
+        Shape::Square(_) => print("square")
+    }
+
+    match true {
+        true => print("yes")
+    }
+
+    match 1 {
+        0 => print("zero")
+    }
+}
+"#;
+
+    assert_eq!(
+        notes(source),
+        vec![
+            Some(
+                "Add arms for 'Shape::Circle(_)' and 'Shape::Empty', or a '_' \
+                 arm for the rest."
+                    .to_string()
+            ),
+            Some("Add an arm for 'false', or a '_' arm for the rest.".into()),
+            Some("Add a '_' arm for the values that aren't listed.".into()),
+        ]
+    );
+}
+
+#[test]
+fn match_guards_are_conditions() {
+    let source = r#"
+fn main() {
+    match 1 {
+        n if n => print(n)
+        n if n > 0 => print(n)
+    }
+
+    match true {
+        true => print("yes")
+        false if 1 > 0 => print("no")
+        _ => print("other")
+    }
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 4, column 14] Error[B0007]: Condition must be 'bool', not \
+             'i32'.",
+            "[line 3, column 5] Error[B0019]: Match doesn't cover every value \
+             of 'i32'.",
+        ]
+    );
+}
+
+#[test]
+fn struct_update_base_has_the_same_type() {
+    let source = r#"
+struct Point { x: i32, y: i32 }
+struct Size { x: i32, y: i32 }
+
+fn main() {
+    let size = Size { x: 1, y: 2 }
+    let a = Point { x: 3, ..size }
+    let b = Point { x: 3, ..Point { x: 0, y: 0 } }
+    let c = Point { z: 3, ..b }
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 7, column 13] Error[B0007]: Expect type 'Point' but found \
+             'Size'.",
+            "[line 9, column 21] Error[B0008]: Undefined field 'z' on 'Point'.",
+        ]
+    );
+}
+
+#[test]
+fn try_blocks_check_their_errors() {
+    let source = r#"
+fn parse(text: str): i32 ! str {
+    return 1
+}
+
+fn code(text: str): i32 ! i32 {
+    return 1
+}
+
+fn main() {
+    try {
+        let a = try parse("a")
+        raise 5
+    } catch e {
+        let n: i32 = e
+    }
+
+    try {
+        print(1)
+    } catch {
+        print(2)
+    }
+
+    try {
+        let b = try code("b")
+    } catch e {
+        let n: i32 = e
+    }
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 13, column 9] Error[B0007]: Expect type 'str' but found \
+             'i32'.",
+            "[line 15, column 13] Error[B0007]: Expect type 'i32' but found \
+             'str'.",
+            "[line 18, column 5] Error[B0012]: Nothing in this 'try' block \
+             can raise an error.",
+        ]
+    );
+}
+
+#[test]
+fn set_builtins_check_their_arguments() {
+    let source = r#"
+fn main() {
+    let numbers: set[i32] = {1, 2}
+    let mixed = {1, "a"}
+    add(numbers, "b")
+    let found: bool = contains(numbers, 1)
+    let gone: i32 = remove(numbers, 1)
+    add([1], 2)
+    let n: set[str] = numbers
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 4, column 9] Error[B0007]: Expect type 'i32' but found \
+             'str'.",
+            "[line 5, column 5] Error[B0007]: Expect type 'i32' but found \
+             'str'.",
+            "[line 7, column 9] Error[B0007]: Expect type 'i32' but found \
+             'bool'.",
+            "[line 8, column 5] Error[B0007]: Can't add to '[i32]'.",
+            "[line 9, column 9] Error[B0007]: Expect type 'set[str]' but \
+             found 'set[i32]'.",
+        ]
+    );
+}
+
+#[test]
+fn string_index_is_str() {
+    let source = r#"
+fn main() {
+    let s = "abc"
+    let c: str = s[0]
+    print(s[1] == "b", c + s[2])
+    let n: i32 = s[0]
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec!["[line 6, column 9] Error[B0007]: Expect type 'i32' but found 'str'."]
+    );
+}
+
+#[test]
+fn syntax_errors_have_their_own_codes() {
+    let source = r#"
+fn main() {
+    print(1 + 2
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec!["[line 4, column 1] Error[B0021] at '}': Expect ')' after arguments."]
+    );
+
+    let source = r#"
+fn (a: i32): i32 {
+    return a
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec!["[line 2, column 4] Error[B0022] at '(': Expect function name."]
+    );
+}
+
+#[test]
+fn long_operator_chain_is_too_deep() {
+    let chain = vec!["1"; 20_000].join(" + ");
+    let source = format!("fn main() {{\n    print({chain})\n}}\n");
+
+    let errors = errors(&source);
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("Error[B0023]"));
+    assert!(errors[0].ends_with("Too much nesting."));
+}
+
+#[test]
+fn long_call_chain_is_too_deep() {
+    let chain = "[0]".repeat(20_000);
+    let source = format!("fn main() {{\n    print(a{chain})\n}}\n");
+
+    let errors = errors(&source);
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("Error[B0023]"));
+    assert!(errors[0].ends_with("Too much nesting."));
+}
+
+#[test]
+fn short_operator_chain_is_fine() {
+    let chain = vec!["1"; 1000].join(" + ");
+    let source = format!("fn main() {{\n    print({chain})\n}}\n");
+
+    assert!(errors(&source).is_empty());
+
+    let chain = vec!["\"a\""; 1000].join(" + ");
+    let source = format!("fn main() {{\n    print(len({chain}))\n}}\n");
+
+    assert!(errors(&source).is_empty());
+}
+
+#[test]
+fn double_underscore_names_are_reserved() {
+    let source = r#"
+struct __Point {
+    x: i32
+}
+
+fn scale(__factor: i32) {}
+
+fn main() {
+    let __global_count = 1
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 2, column 8] Error[B0020]: Name '__Point' is reserved.",
+            "[line 6, column 10] Error[B0020]: Name '__factor' is reserved.",
+            "[line 9, column 9] Error[B0020]: Name '__global_count' is reserved."
+        ]
+    );
+}
+
+#[test]
+fn numeric_builtins_check_their_arguments() {
+    let source = r#"
+fn main() {
+    let a: i32 = abs(-2)
+    let b: f64 = pow(2.0, 0.5)
+    let c: i32 = div(7, 2)
+    print(abs("x"))
+    print(pow(2, 1.5))
+    print(div(1, 2.0))
+    let d: i32 = abs(1.5)
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 6, column 11] Error[B0007]: Expect type 'i32' or 'f64' but \
+             found 'str'.",
+            "[line 7, column 11] Error[B0007]: Expect type 'i32' but found \
+             'f64'.",
+            "[line 8, column 11] Error[B0007]: Expect type 'i32' but found \
+             'f64'.",
+            "[line 9, column 9] Error[B0007]: Expect type 'i32' but found \
+             'f64'.",
+        ]
+    );
+}
+
+#[test]
+fn index_assignment_checks_types() {
+    let source = r#"
+fn main() {
+    let s = "abc"
+    s[0] = "x"
+    let a = [1, 2]
+    a["x"] = 1
+    a[0] = "y"
+    let m: map[str, i32] = {"a": 1}
+    m["b"] = 2
+    m[1] = 1
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 4, column 5] Error[B0007]: Can't assign to an index of 'str'.",
+            "[line 6, column 5] Error[B0007]: Expect type 'i32' but found 'str'.",
+            "[line 7, column 5] Error[B0007]: Expect type 'i32' but found 'str'.",
+            "[line 10, column 5] Error[B0007]: Expect type 'str' but found 'i32'.",
+        ]
+    );
+}
+
+#[test]
+fn map_builtins_check_their_arguments() {
+    let source = r#"
+fn main() {
+    let m: map[str, i32] = {"a": 1}
+    insert(m, "b", 2)
+    let found: bool = contains(m, "b")
+    let removed: i32? = remove(m, "a")
+    insert(m, 1, 2)
+    insert(m, "a", "b")
+    print(contains([1], 1))
+    print(remove(m))
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 7, column 5] Error[B0007]: Expect type 'str' but found 'i32'.",
+            "[line 8, column 5] Error[B0007]: Expect type 'i32' but found 'str'.",
+            "[line 9, column 11] Error[B0007]: Can't search '[i32]'.",
+            "[line 10, column 11] Error[B0009]: Expect 2 arguments but got 1.",
+        ]
+    );
+}
+
+#[test]
+fn array_builtins_check_their_arguments() {
+    let source = r#"
+fn main() {
+    let a = [1, 2]
+    push(a, 3)
+    let last: i32? = pop(a)
+    push(a, "x")
+    push(5, 1)
+    let m: map[str, i32] = {}
+    print(pop(m))
+    pop(a, 1)
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 6, column 5] Error[B0007]: Expect type 'i32' but found 'str'.",
+            "[line 7, column 5] Error[B0007]: Can't push to 'i32'.",
+            "[line 9, column 11] Error[B0007]: Can't pop from 'map[str, i32]'.",
+            "[line 10, column 5] Error[B0009]: Expect 1 arguments but got 2.",
+        ]
+    );
+}
+
+#[test]
+fn collection_literals_coerce_elements() {
+    let source = r#"
+fn main() {
+    let a: [i32?] = [1, none]
+    let b: [i32?] = [none, 1]
+    let c: [[i32?]] = [[1], [none, 2]]
+    let d: map[str, i32?] = {"a": 1, "b": none}
+    let e: [i32] = [1, none]
+    let f: [str] = [1, 2]
+    let g: map[str, i32] = {"a": none}
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 7, column 9] Error[B0007]: Expect type 'i32' but found '_?'.",
+            "[line 8, column 9] Error[B0007]: Expect type 'str' but found 'i32'.",
+            "[line 9, column 9] Error[B0007]: Expect type 'i32' but found '_?'.",
+        ]
+    );
+}
+
+#[test]
+fn unsuffixed_integers_are_i32() {
+    let source = r#"
+fn main() {
+    let a: f64 = 1f
+    let b: i32 = 10i
+    let c: f64 = 2.5f
+    let d: f64 = 1
+    print(2.0 * 2)
+    let e: i32 = 2.5f
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 6, column 9] Error[B0007]: Expect type 'f64' but found 'i32'.",
+            "[line 7, column 15] Error[B0007]: Can't apply '*' to 'f64' and \
+             'i32'.",
+            "[line 8, column 9] Error[B0007]: Expect type 'i32' but found 'f64'.",
+        ]
+    );
+}
+
+#[test]
+fn integer_suffix_on_float_literal() {
+    let source = r#"
+fn main() {
+    print(2.5i)
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 3, column 11] Error[B0025] at '2.5i': Integer suffix on a \
+             float literal."
+        ]
+    );
+}
+
+#[test]
+fn unused_names_warn() {
+    let source = r#"
+fn helper(): i32 {
+    return 1
+}
+
+fn used(a: i32, _b: i32): i32 {
+    return a
+}
+
+fn main() {
+    let total = used(1, 2)
+    let _skipped = 3
+
+    for i in 0..3 {
+        print("tick")
+    }
+}
+"#;
+
+    let warnings: Vec<String> =
+        blaze::check(source).iter().map(|d| d.to_string()).collect();
+
+    assert_eq!(
+        warnings,
+        vec![
+            "[line 2, column 4] Warning[B0026]: Function 'helper' is never \
+             used.",
+            "[line 11, column 9] Warning[B0026]: Variable 'total' is never \
+             used.",
+            "[line 14, column 9] Warning[B0026]: Variable 'i' is never used.",
+        ]
+    );
+}
+
+#[test]
+fn block_comments_nest() {
+    let source = r#"
+fn main() {
+    /* outer /* inner */ still a comment */
+    print(/* before */ 1 /* after */)
+}
+"#;
+
+    assert_eq!(errors(source), Vec::<String>::new());
+}
+
+#[test]
+fn unterminated_block_comment() {
+    let source = r#"
+fn main() {
+    print(1)
+    /* outer /* inner */
+    print(2)
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 4, column 5] Error[B0014]: Unterminated block comment.",
+            "[line 7, column 1] Error[B0021] at end: Expect '}' after block.",
+        ]
+    );
+}
+
+#[test]
+fn unterminated_string() {
+    let source = r#"
+fn main() {
+    print("one
+    print(2)
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 3, column 11] Error[B0002]: Unterminated string.",
+            "[line 6, column 1] Error[B0003] at ';': Expect expression.",
+            "[line 6, column 1] Error[B0021] at end: Expect '}' after block.",
+        ]
+    );
+}
+
+fn explicit(source: &str) -> Vec<String> {
+    let mut options = Options::new();
+    options.semicolons = Semicolons::Explicit;
+
+    blaze::check_with(source, &options)
+        .iter()
+        .map(|d| d.to_string())
+        .collect()
+}
+
+#[test]
+fn explicit_semicolons_are_required() {
+    let source = r#"
+fn main() {
+    let a = 1
+    print(a);
+}
+"#;
+
+    assert_eq!(
+        explicit(source),
+        vec![
+            "[line 4, column 5] Error[B0021] at 'print': Expect ';' after \
+             variable declaration."
+        ]
+    );
+}
+
+#[test]
+fn explicit_semicolons_accept_terminated_statements() {
+    let source = r#"
+fn add(a: i32, b: i32): i32 {
+    return a + b;
+}
+
+fn main() {
+    let total = add(1, 2);
+
+    if total > 2 {
+        print(total);
+    }
+}
+"#;
+
+    assert_eq!(explicit(source), Vec::<String>::new());
+}
+
+#[test]
+fn parser_recovers_after_an_error() {
+    let source = r#"
+fn first() {
+    let = 1
+}
+
+fn second() {
+    print(1 +)
+}
+
+fn main() {
+    first()
+    second(
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 3, column 9] Error[B0022] at '=': Expect variable name.",
+            "[line 7, column 14] Error[B0003] at ')': Expect expression.",
+            "[line 13, column 1] Error[B0003] at '}': Expect expression.",
+        ]
+    );
+}
+
+#[test]
+fn loop_values_come_from_their_breaks() {
+    let source = r#"
+fn main() {
+    let unit: i32 = loop { break }
+    let mixed = loop { if true { break 1 } break "a" }
+    for i in 0..3 { break 5 }
+    loop { break 5 }
+    let v = loop { break 2 }
+    print(v + "a")
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 3, column 9] Error[B0007]: Expect type 'i32' but found '()'.",
+            "[line 4, column 44] Error[B0007]: Expect type 'i32' but found \
+             'str'.",
+            "[line 5, column 21] Error[B0012]: Can't break with a value out \
+             of this loop.",
+            "[line 6, column 12] Error[B0012]: Can't break with a value out \
+             of this loop.",
+            "[line 8, column 13] Error[B0007]: Can't apply '+' to 'i32' and \
+             'str'.",
+        ]
+    );
+}
+
+#[test]
+fn constants_stay_read_only() {
+    let source = r#"
+struct P { x: i32 }
+
+const C: P = P { x: 1 }
+const A: [i32] = [1]
+
+fn main() {
+    C.x = 5
+    A[0] = 2
+    push(A, 3)
+    let copy = A
+    push(copy, 3)
+    print(C.x + len(copy))
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 8, column 5] Error[B0005]: Can't assign to constant 'C'.",
+            "[line 9, column 5] Error[B0005]: Can't assign to constant 'A'.",
+            "[line 10, column 10] Error[B0005]: Can't assign to constant 'A'.",
+        ]
+    );
+}
+
+#[test]
+fn keys_must_hash() {
+    let source = r#"
+fn main() {
+    let prices: map[f64, i32] = {}
+    let seen: set[f64] = {1.5}
+    let counts: map[str, i32] = {"a": 1}
+    print(len(prices) + len(seen) + len(counts))
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 3, column 9] Error[B0027]: Can't use 'f64' as a key.",
+            "[line 4, column 9] Error[B0027]: Can't use 'f64' as a key.",
+        ]
+    );
+}
+
+#[test]
+fn types_cant_contain_themselves() {
+    let source = r#"
+struct Node { value: i32, next: Node? }
+struct A { b: B }
+struct B { a: A }
+struct Tree { children: [Tree] }
+
+fn main() {}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 2, column 8] Error[B0028]: Type 'Node' contains itself.",
+            "[line 3, column 8] Error[B0028]: Type 'A' contains itself.",
+            "[line 4, column 8] Error[B0028]: Type 'B' contains itself.",
+        ]
+    );
+}
+
+#[test]
+fn functions_cant_be_compared() {
+    let source = r#"
+struct Handler { run: fn(i32): i32 }
+struct Point { x: i32, y: i32 }
+
+fn twice(n: i32): i32 { return n * 2 }
+
+fn main() {
+    let h = Handler { run: twice }
+    let p = Point { x: 1, y: 2 }
+    print(h == h, p == p, twice == twice)
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 10, column 13] Error[B0007]: Can't compare 'Handler' with \
+             'Handler'.",
+            "[line 10, column 33] Error[B0007]: Can't compare 'fn(i32): i32' \
+             with 'fn(i32): i32'.",
+        ]
+    );
+}
+
+#[test]
+fn bare_none_needs_a_type() {
+    let source = r#"
+fn main() {
+    let x = none
+    let y: i32? = none
+    print(x == none, y == none)
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec!["[line 3, column 9] Error[B0017]: Can't infer the type of 'x'."]
+    );
+}
+
+#[test]
+fn duplicate_declarations() {
+    let source = r#"
+struct Point { x: i32, x: i32 }
+enum Shape { Empty, Empty }
+
+impl Point {
+    fn norm(self): i32 { return self.x }
+}
+
+impl Point {
+    fn norm(self): i32 { return 0 }
+}
+
+fn area(width: i32, width: i32): i32 { return width }
+
+fn twice(n: i32): i32 { return n }
+fn twice(n: i32): i32 { return n * 2 }
+
+fn main() {
+    fn helper() {}
+    fn helper() {}
+    let n = 1
+    let n = n + 1
+    print(area(n, 2) + twice(n))
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 16, column 4] Error[B0029]: Function 'twice' is already \
+             declared.",
+            "[line 2, column 24] Error[B0029]: Field 'x' is already declared.",
+            "[line 3, column 21] Error[B0029]: Variant 'Empty' is already \
+             declared.",
+            "[line 10, column 8] Error[B0029]: Method 'norm' is already \
+             declared.",
+            "[line 13, column 21] Error[B0029]: Parameter 'width' is already \
+             declared.",
+            "[line 20, column 8] Error[B0029]: Function 'helper' is already \
+             declared.",
+        ]
+    );
+}
+
+#[test]
+fn bools_only_cast_to_integers() {
+    let source = r#"
+fn main() {
+    let a = true as i32
+    let b = false as f64
+    print(a, b)
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec!["[line 4, column 22] Error[B0007]: Can't cast 'bool' to 'f64'."]
+    );
+}
+
+#[test]
+fn globals_cant_initialize_from_themselves() {
+    let source = r#"
+let a: i32 = b + 1
+let b: i32 = a * 2
+let c: i32 = next()
+const D: i32 = b
+let e: i32 = twice(D)
+
+fn next(): i32 { return c + 1 }
+fn twice(n: i32): i32 { return n * 2 }
+
+fn main() {
+    print(a + c + e)
+}
+"#;
+
+    assert_eq!(
+        errors(source),
+        vec![
+            "[line 2, column 5] Error[B0030]: Initializer of 'a' depends on \
+             itself.",
+            "[line 3, column 5] Error[B0030]: Initializer of 'b' depends on \
+             itself.",
+            "[line 4, column 5] Error[B0030]: Initializer of 'c' depends on \
+             itself.",
+        ]
+    );
+
+    let notes: Vec<Option<String>> =
+        check(source).into_iter().map(|d| d.note).collect();
+
+    assert_eq!(
+        notes[2].as_deref(),
+        Some("It goes through 'c' -> 'next' -> 'c'.")
+    );
+}
+
+#[test]
+fn symbols_list_definitions_and_references() {
+    let source = r#"
+let total = 0
+
+fn add(n: i32) {
+    total = total + n
+}
+
+fn main() {
+    add(2)
+    print(total)
+}
+"#;
+
+    let symbols: Vec<(String, Vec<usize>)> = blaze::symbols(source)
+        .unwrap()
+        .into_iter()
+        .map(|symbol| {
+            (
+                format!("{} {}", symbol.kind, symbol.name.lexeme),
+                symbol.references.iter().map(|token| token.line).collect(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        symbols,
+        vec![
+            ("Variable total".to_string(), vec![5, 5, 10]),
+            ("Function add".to_string(), vec![9]),
+            ("Variable n".to_string(), vec![5]),
+            ("Function main".to_string(), vec![]),
+        ]
+    );
+}
@@ -0,0 +1,34 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn blaze(args: &[&str], source: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_blaze"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(source.as_bytes())
+        .unwrap();
+
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn dash_reads_the_script_from_stdin() {
+    let output =
+        blaze(&["--no-format", "-", "-"], "fn main() {\n    print(1)\n}\n");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("fn main()"));
+
+    let output = blaze(&["check", "-"], "fn main() {\n    print(x)\n}\n");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Undefined variable 'x'."));
+}
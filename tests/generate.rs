@@ -0,0 +1,903 @@
+use std::env;
+use std::fs;
+use std::process::{self, Command};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use blaze::{Checker, Generator, Parser, Scanner, Semicolons};
+
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+fn run(source: &str) -> String {
+    let rust = match blaze::compile(source) {
+        Ok(rust) => rust,
+        Err(diagnostics) => {
+            let messages: Vec<String> =
+                diagnostics.iter().map(|d| d.to_string()).collect();
+            panic!("blaze failed:\n{}", messages.join("\n"));
+        }
+    };
+
+    let directory = env::temp_dir().join(format!(
+        "blaze-test-{}-{}",
+        process::id(),
+        COUNT.fetch_add(1, Ordering::SeqCst)
+    ));
+    fs::create_dir_all(&directory).unwrap();
+
+    let file = directory.join("main.rs");
+    let binary = directory.join(format!("main{}", env::consts::EXE_SUFFIX));
+    fs::write(&file, rust).unwrap();
+
+    let rustc = env::var("BLAZE_RUSTC").unwrap_or_else(|_| "rustc".into());
+    let build = Command::new(rustc)
+        .arg(&file)
+        .arg("-o")
+        .arg(&binary)
+        .output()
+        .unwrap();
+
+    let warnings = String::from_utf8_lossy(&build.stderr).into_owned();
+    let output = if build.status.success() {
+        Some(Command::new(&binary).output().unwrap())
+    } else {
+        None
+    };
+
+    fs::remove_dir_all(&directory).unwrap();
+
+    let output = match output {
+        Some(output) => output,
+        None => panic!("rustc failed:\n{}", warnings),
+    };
+
+    assert!(warnings.is_empty(), "rustc warned:\n{}", warnings);
+    assert!(
+        output.status.success(),
+        "program failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn let_initializer_clones_variable() {
+    let source = r#"
+fn main() {
+    let s = "hi"
+    let t = s
+    print(s)
+    print(t)
+}
+"#;
+
+    assert_eq!(run(source), "hi\nhi\n");
+}
+
+#[test]
+fn assignment_clones_variable() {
+    let source = r#"
+fn main() {
+    let s = "hi"
+    let t = "bye"
+    print(t)
+    t = s
+    print(s)
+    print(t)
+}
+"#;
+
+    assert_eq!(run(source), "bye\nhi\nhi\n");
+}
+
+#[test]
+fn return_clones_variable() {
+    let source = r#"
+fn echo(s: str): str {
+    let t = s
+    print(t)
+    return s
+}
+
+fn main() {
+    print(echo("hi"))
+}
+"#;
+
+    assert_eq!(run(source), "hi\nhi\n");
+}
+
+#[test]
+fn element_clones_variable() {
+    let source = r#"
+fn main() {
+    let s = "hi"
+    let a = [s, s]
+    print(s)
+    print(len(a))
+}
+"#;
+
+    assert_eq!(run(source), "hi\n2\n");
+}
+
+#[test]
+fn map_entry_clones_variable() {
+    let source = r#"
+fn main() {
+    let s = "hi"
+    let m = {s: s}
+    print(s)
+    print(len(m))
+}
+"#;
+
+    assert_eq!(run(source), "hi\n1\n");
+}
+
+#[test]
+fn field_initializer_clones_variable() {
+    let source = r#"
+struct Name {
+    text: str
+}
+
+fn main() {
+    let s = "hi"
+    let n = Name { text: s }
+    print(s)
+    print(n.text)
+}
+"#;
+
+    assert_eq!(run(source), "hi\nhi\n");
+}
+
+#[test]
+fn array_survives_let() {
+    let source = r#"
+fn main() {
+    let a = [1, 2, 3]
+    let b = a
+    print(len(a))
+    print(b[0] + a[2])
+}
+"#;
+
+    assert_eq!(run(source), "3\n4\n");
+}
+
+#[test]
+fn nested_array_survives_element() {
+    let source = r#"
+fn main() {
+    let a = [1, 2]
+    let grid = [a, a]
+    print(len(grid))
+    print(grid[1][1] + a[0])
+}
+"#;
+
+    assert_eq!(run(source), "2\n3\n");
+}
+
+#[test]
+fn struct_survives_let() {
+    let source = r#"
+struct Point {
+    x: i32
+}
+
+fn main() {
+    let p = Point { x: 1 }
+    let q = p
+    print(p.x)
+    print(q.x)
+}
+"#;
+
+    assert_eq!(run(source), "1\n1\n");
+}
+
+#[test]
+fn for_variable_can_be_assigned() {
+    let source = r#"
+fn main() {
+    for i in 0..3 {
+        i = i * 10
+        print(i)
+    }
+}
+"#;
+
+    assert_eq!(run(source), "0\n10\n20\n");
+}
+
+#[test]
+fn match_bindings_are_owned_and_assignable() {
+    let source = r#"
+fn main() {
+    match "hello" {
+        "hi" => print("short")
+        other => {
+            other = other + "!"
+            print(len(other), other)
+        }
+    }
+
+    match 3 {
+        n => {
+            n = n + 1
+            print(n)
+        }
+    }
+}
+"#;
+
+    assert_eq!(run(source), "6 hello!\n4\n");
+}
+
+#[test]
+fn match_guards_pick_the_arm() {
+    let source = r#"
+enum Reading {
+    Value(i32),
+    Missing,
+}
+
+fn describe(reading: Reading): str {
+    match reading {
+        Reading::Value(n) if n > 10 => return "high"
+        Reading::Value(_) => return "low"
+        Reading::Missing => return "none"
+    }
+}
+
+fn main() {
+    print(describe(Reading::Value(20)), describe(Reading::Value(5)))
+    print(describe(Reading::Missing))
+
+    match "hello" {
+        word if len(word) > 3 => print("long", word)
+        word => print("short", word)
+    }
+}
+"#;
+
+    assert_eq!(run(source), "high low\nnone\nlong hello\n");
+}
+
+#[test]
+fn struct_update_copies_the_other_fields() {
+    let source = r#"
+struct Config {
+    name: str,
+    retries: i32,
+    verbose: bool,
+}
+
+fn main() {
+    let defaults = Config { name: "app", retries: 1, verbose: false }
+    let config = Config {
+        retries: 5,
+        ..defaults
+    }
+
+    print(config.name, config.retries, config.verbose)
+    print(defaults.retries)
+}
+"#;
+
+    assert_eq!(run(source), "app 5 false\n1\n");
+}
+
+#[test]
+fn try_blocks_catch_errors() {
+    let source = r#"
+fn parse(text: str): i32 ! str {
+    if text == "" {
+        raise "empty"
+    }
+
+    return len(text)
+}
+
+fn first(words: [str]): i32 {
+    for i in 0..len(words) {
+        try {
+            return try parse(words[i])
+        } catch {
+            continue
+        }
+    }
+
+    return 0
+}
+
+fn main() {
+    try {
+        let a = try parse("abc")
+        print(a)
+        let b = try parse("")
+        print(b)
+    } catch e {
+        print("caught", e)
+    }
+
+    try {
+        raise "direct"
+    } catch e {
+        print(e)
+    }
+
+    print(first(["", "hello"]))
+}
+"#;
+
+    assert_eq!(run(source), "3\ncaught empty\ndirect\n5\n");
+}
+
+#[test]
+fn set_builtins_change_the_set() {
+    let source = r#"
+fn main() {
+    let seen: set[i32] = {}
+    add(seen, 3)
+    add(seen, 3)
+    add(seen, 4)
+    print(len(seen), contains(seen, 3), contains(seen, 5))
+    print(remove(seen, 3), remove(seen, 3), len(seen))
+
+    let words = {"a", "b", "a"}
+    print(len(words), contains(words, "b"))
+
+    let empty: set[str]? = {}
+    print(empty == none)
+}
+"#;
+
+    assert_eq!(run(source), "2 true false\ntrue false 1\n2 true\nfalse\n");
+}
+
+#[test]
+fn enum_payloads_are_assignable() {
+    let source = r#"
+enum Shape {
+    Circle(f64),
+    Empty,
+}
+
+enum Wrap {
+    Full(Shape),
+    Nothing,
+}
+
+fn size(wrap: Wrap): f64 {
+    match wrap {
+        Wrap::Full(Shape::Circle(r)) => {
+            r = r * 2.0
+            return r
+        }
+        Wrap::Full(Shape::Empty) => return 1.0
+        Wrap::Nothing => return 0.0
+    }
+}
+
+fn main() {
+    print(size(Wrap::Full(Shape::Circle(1.5))))
+    print(size(Wrap::Full(Shape::Empty)), size(Wrap::Nothing))
+}
+"#;
+
+    assert_eq!(run(source), "3\n1 0\n");
+}
+
+#[test]
+fn checked_arithmetic_returns_none_on_overflow() {
+    let source = r#"
+fn main() {
+    print(checked_add(2147483647, 1) ?? -1)
+    print(checked_sub(5, 2) ?? -1)
+    print(checked_mul(65536, 65536) ?? -1)
+    print(checked_div(7, 0) ?? -1)
+}
+"#;
+
+    assert_eq!(run(source), "-1\n3\n-1\n-1\n");
+}
+
+#[test]
+fn pow_raises_integers_and_floats() {
+    let source = r#"
+fn main() {
+    print(pow(2, 10))
+    print(pow(2.0, -1.0))
+}
+"#;
+
+    assert_eq!(run(source), "1024\n0.5\n");
+}
+
+#[test]
+fn global_method_can_read_global() {
+    let source = r#"
+struct Counter {
+    count: i32
+}
+
+impl Counter {
+    fn bump(self, by: i32) {
+        self.count = self.count + by + peek()
+    }
+}
+
+let counter: Counter = Counter { count: 1 }
+
+fn peek(): i32 {
+    return counter.count
+}
+
+fn main() {
+    counter.bump(2)
+    print(counter.count)
+}
+"#;
+
+    assert_eq!(run(source), "4\n");
+}
+
+#[test]
+fn global_method_without_arguments() {
+    let source = r#"
+struct Counter {
+    count: i32
+}
+
+impl Counter {
+    fn reset(self) {
+        self.count = 0
+    }
+}
+
+let counter: Counter = Counter { count: 5 }
+
+fn main() {
+    counter.reset()
+    print(counter.count)
+}
+"#;
+
+    assert_eq!(run(source), "0\n");
+}
+
+#[test]
+fn indexed_places_in_maps_and_arrays() {
+    let source = r#"
+struct Point {
+    x: i32
+}
+
+impl Point {
+    fn bump(self) {
+        self.x = self.x + 1
+    }
+}
+
+let globals: map[str, Point] = {"g": Point { x: 7 }}
+
+fn main() {
+    let points: map[str, Point] = {"a": Point { x: 1 }}
+    points["a"].x = 5
+    points["a"].bump()
+    print(points["a"].x)
+
+    let list = [Point { x: 1 }, Point { x: 2 }]
+    list[1].x = 20
+    list[0].bump()
+    print(list[0].x, list[1].x)
+
+    globals["g"].bump()
+    print(globals["g"].x)
+}
+"#;
+
+    assert_eq!(run(source), "6\n2 20\n8\n");
+}
+
+#[test]
+fn string_index_is_str() {
+    let source = r#"
+fn main() {
+    let s = "abc"
+    let c: str = s[0]
+    print(s[1] == "b", c + s[2], get(s, 1) ?? "none", get(s, 9) ?? "none")
+}
+"#;
+
+    assert_eq!(run(source), "true ac b none\n");
+}
+
+#[test]
+fn plain_values_coerce_to_optionals() {
+    let source = r#"
+type MaybeInt = i32?
+
+struct Holder {
+    value: i32?
+}
+
+fn show(n: i32?): i32 {
+    return n ?? -1
+}
+
+fn wrap(n: i32): MaybeInt {
+    return n
+}
+
+let global: i32? = none
+
+fn main() {
+    let x: i32? = show(none)
+    print(x ?? 0)
+    x = 3
+
+    let holder = Holder { value: 4 }
+    print(holder.value ?? 0, show(5))
+    holder.value = (x ?? 0) + 4
+
+    let first: i32? = none
+    global = 11
+    print(holder.value ?? 0, wrap(2) ?? 0, first ?? global ?? 0)
+}
+"#;
+
+    assert_eq!(run(source), "-1\n4 5\n7 2 11\n");
+}
+
+#[test]
+fn user_items_shadow_runtime_names() {
+    let source = r#"
+struct Length {
+    value: i32
+}
+
+struct Cow {
+    value: i32
+}
+
+fn floor(value: f64): f64 {
+    return value + 1.0
+}
+
+fn pow(base: i32, exponent: i32): i32 {
+    return base * exponent
+}
+
+fn bench(): str {
+    return "bench"
+}
+
+fn main() {
+    let length = Length { value: 2 }
+    let a = [1, 2, 3]
+    let m: map[str, i32] = {"a": 4}
+    print(floor(1.5), pow(2, 3), bench(), length.value)
+    print(len(a), a[1], m["a"], "abc"[2])
+}
+"#;
+
+    assert_eq!(run(source), "2.5 6 bench 2\n3 2 4 c\n");
+}
+
+#[test]
+fn passes_match_compile() {
+    let source = r#"
+fn main() {
+    let x: i32? = 1
+    print(x ?? 0)
+}
+"#;
+
+    let (tokens, errors) = Scanner::new(source, Semicolons::Automatic).scan();
+    assert!(errors.is_empty());
+
+    let (statements, errors) = Parser::new(tokens).parse();
+    assert!(errors.is_empty());
+
+    let mut checker = Checker::new();
+    let (program, errors) = checker.check(&statements);
+    assert!(errors.is_empty());
+
+    let mut generator = Generator::new();
+    let (rust, errors) = generator.generate(&program);
+    assert!(errors.is_empty());
+
+    assert_eq!(rust, blaze::compile(source).unwrap());
+}
+
+#[test]
+fn index_assignment_stores_elements() {
+    let source = r#"
+let totals: map[str, i32] = {"a": 1}
+
+struct Grid {
+    cells: [[i32]]
+}
+
+fn main() {
+    let a = [1, 2, 3]
+    a[0] = 5
+    a[len(a) - 1] = a[0] + 1
+
+    let m: map[str, i32] = {"a": 1}
+    m["a"] = 2
+    m["b"] = 3
+
+    totals["z"] = 26
+
+    let grid = Grid { cells: [[0, 0], [0, 0]] }
+    grid.cells[1][0] = 4
+
+    print(a[0], a[2], m["a"], m["b"], len(m), totals["z"])
+    print(grid.cells[1][0])
+}
+"#;
+
+    assert_eq!(run(source), "5 6 2 3 2 26\n4\n");
+}
+
+#[test]
+fn map_builtins_change_the_map() {
+    let source = r#"
+let ages: map[str, i32] = {"ada": 36}
+
+struct Book {
+    pages: map[str, i32]
+}
+
+fn main() {
+    let m: map[str, i32] = {"a": 1}
+    insert(m, "b", 2)
+    print(contains(m, "b"), contains(m, "c"), len(m))
+    print(remove(m, "a") ?? 0, remove(m, "a") ?? 0, len(m))
+
+    insert(ages, "alan", 41)
+    print(remove(ages, "ada") ?? 0, contains(ages, "alan"))
+
+    let optional: map[str, i32?] = {}
+    insert(optional, "x", 3)
+    print(optional["x"] ?? 0)
+
+    let book = Book { pages: {"intro": 1} }
+    insert(book.pages, "end", 9)
+    print(len(book.pages))
+}
+"#;
+
+    assert_eq!(run(source), "true false 2\n1 0 1\n36 true\n3\n2\n");
+}
+
+#[test]
+fn array_builtins_change_the_array() {
+    let source = r#"
+let stack: [i32] = []
+
+fn main() {
+    let a = [1, 2]
+    push(a, 3)
+    print(len(a), a[2])
+    print(pop(a) ?? 0, pop(a) ?? 0, pop(a) ?? 0, pop(a) ?? -1, len(a))
+
+    push(stack, 7)
+    push(stack, 8)
+    print(pop(stack) ?? 0, len(stack))
+
+    let grid: [[i32]] = [[], []]
+    push(grid[1], 5)
+    print(len(grid[0]), grid[1][0])
+
+    let optional: [i32?] = []
+    push(optional, 4)
+    push(optional, none)
+    print(optional[0] ?? 0, len(optional))
+}
+"#;
+
+    assert_eq!(run(source), "3 3\n3 2 1 -1 0\n8 1\n0 5\n4 2\n");
+}
+
+#[test]
+fn lookups_borrow_their_collection() {
+    let source = r#"
+let names: [str] = ["ada", "alan"]
+
+fn main() {
+    let words = ["a", "b", "c"]
+    let seen: map[str, i32] = {"a": 1}
+    print(len(words), len(names), get(words, 1) ?? "", contains(seen, "a"))
+}
+"#;
+
+    let rust = blaze::compile(source).unwrap();
+    assert!(rust.contains("len(&words)"));
+    assert!(!rust.contains("words.clone()"));
+    assert!(!rust.contains("seen.clone()"));
+    assert_eq!(run(source), "3 2 b true\n");
+}
+
+#[test]
+fn collection_literals_wrap_optional_elements() {
+    let source = r#"
+fn total(values: [i32?]): i32 {
+    let sum = 0
+
+    for i in 0..len(values) {
+        sum = sum + (values[i] ?? 0)
+    }
+
+    return sum
+}
+
+fn main() {
+    let a: [i32?] = [1, none]
+    let b = [none, 2, 3]
+    let c: [[i32?]] = [[1], [none, 4]]
+    let m: map[str, i32?] = {"a": 5, "b": none}
+    print(a[0] ?? 0, b[2] ?? 0, c[1][1] ?? 0, m["a"] ?? 0, m["b"] ?? 0)
+    print(total([1, 2, none]))
+}
+"#;
+
+    assert_eq!(run(source), "1 3 4 5 0\n3\n");
+}
+
+#[test]
+fn suffixes_pick_the_literal_type() {
+    let source = r#"
+fn main() {
+    let a: f64 = 1f
+    let b: i32 = 10i
+    let c: f64 = 2.5f
+    print(a / 2.0, b / 4, c, 1e3f, 2.0 * 2f, 0x10i)
+}
+"#;
+
+    assert_eq!(run(source), "0.5 2 2.5 1000 4 16\n");
+}
+
+#[test]
+fn labeled_jumps_leave_the_outer_loop() {
+    let source = r#"
+fn main() {
+    let found = 0
+
+    outer: for i in 0..5 {
+        for j in 0..5 {
+            if j > i {
+                continue outer
+            }
+
+            if i * j == 6 {
+                found = i * 10 + j
+                break outer
+            }
+        }
+    }
+
+    print(found)
+}
+"#;
+
+    assert_eq!(run(source), "32\n");
+}
+
+#[test]
+fn conditional_evaluates_only_the_chosen_side() {
+    let source = r#"
+fn loud(text: str): str {
+    print("chose", text)
+    return text
+}
+
+fn main() {
+    let n = 3
+    let amount = n > 5 ? loud("many") : loud("few")
+    let size = n > 10 ? "big" : n > 1 ? "medium" : "small"
+    print(amount, size)
+}
+"#;
+
+    assert_eq!(run(source), "chose few\nfew medium\n");
+}
+
+#[test]
+fn bitwise_operators_follow_precedence() {
+    let source = r#"
+fn main() {
+    let flags = 0b1010
+    print(flags & 0b0110, flags | 1, flags ^ 0b1111)
+    print(1 << 4, -256 >> 2, !0)
+    print(1 | 2 & 4, 1 + 1 << 2)
+}
+"#;
+
+    assert_eq!(run(source), "2 11 5\n16 -64 -1\n1 8\n");
+}
+
+#[test]
+fn casts_convert_between_numbers() {
+    let source = r#"
+fn main() {
+    let count = 7
+    let half = count as f64 / 2.0
+    print(half, 2.9 as i32, -2.9 as i32, count as i32)
+}
+"#;
+
+    assert_eq!(run(source), "3.5 2 -2 7\n");
+}
+
+#[test]
+fn constants_take_any_initializer() {
+    let source = r#"
+fn f(): i32 { return 4 }
+
+let g: i32 = 5
+const X: i32 = f()
+const S: str = "a" + "b"
+const U: str = upper("c")
+const B: i32 = g + X
+const A: [i32] = [1, 2]
+
+fn main() {
+    print(X, S, U, B, A[1])
+}
+"#;
+
+    assert_eq!(run(source), "4 ab C 9 2\n");
+}
+
+#[test]
+fn structs_and_enums_compare_by_value() {
+    let source = r#"
+struct Point { x: i32, y: f64 }
+enum Shape { Circle(f64), Empty }
+
+fn main() {
+    let a = Point { x: 1, y: 2.5 }
+    let b = Point { x: 1, y: 2.5 }
+    let c = Shape::Circle(1.0)
+    print(a == b, a != Point { x: 2, y: 2.5 }, c == Shape::Empty)
+}
+"#;
+
+    assert_eq!(run(source), "true true false\n");
+}
+
+#[test]
+fn any_naming_style_compiles_quietly() {
+    let source = r#"
+struct point { X: i32 }
+
+fn Double(n: i32): i32 { return n * 2 }
+
+fn main() {
+    let Total = 1
+    Total = 2
+    Total = Double(point { X: 3 }.X)
+    print(Total)
+}
+"#;
+
+    assert_eq!(run(source), "6\n");
+}
+
+#[test]
+fn long_flat_chains_compile() {
+    let sum = vec!["1"; 1000].join(" + ");
+    let text = vec!["\"ab\""; 1000].join(" + ");
+    let source =
+        format!("fn main() {{\n    print({sum}, len({text}), -(-(-2)))\n}}\n");
+
+    assert_eq!(run(&source), "1000 2000 -2\n");
+}
@@ -0,0 +1,43 @@
+const README: &str = include_str!("../README.md");
+
+fn snippets() -> Vec<String> {
+    let mut snippets = Vec::new();
+    let mut current: Option<String> = None;
+
+    for line in README.lines() {
+        match current.as_mut() {
+            Some(snippet) if line == "```" => {
+                snippets.push(snippet.clone());
+                current = None;
+            }
+            Some(snippet) => {
+                snippet.push_str(line);
+                snippet.push('\n');
+            }
+            None if line == "```blaze" => current = Some(String::new()),
+            None => (),
+        }
+    }
+
+    snippets
+}
+
+#[test]
+fn readme_snippets_compile() {
+    let snippets = snippets();
+    assert!(!snippets.is_empty());
+
+    for snippet in snippets {
+        let source = if snippet.contains("fn main()") {
+            snippet
+        } else {
+            format!("{}\nfn main() {{}}\n", snippet)
+        };
+
+        if let Err(diagnostics) = blaze::compile(&source) {
+            let messages: Vec<String> =
+                diagnostics.iter().map(|d| d.to_string()).collect();
+            panic!("{}\n{}", source, messages.join("\n"));
+        }
+    }
+}